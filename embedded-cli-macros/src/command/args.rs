@@ -1,4 +1,4 @@
-use syn::Type;
+use syn::{GenericArgument, PathArguments, Type, TypePath};
 
 use crate::utils;
 
@@ -6,6 +6,8 @@ use crate::utils;
 pub enum ArgType {
     Option,
     Normal,
+    /// All occurrences of option are collected into `Values<'a, T>`
+    Append,
 }
 
 pub struct TypedArg<'a> {
@@ -30,6 +32,27 @@ impl<'a> TypedArg<'a> {
         }
     }
 
+    /// Arg of type `Values<'a, T>`, that collects all occurrences of option
+    pub fn append(ty: &'a Type) -> Option<Self> {
+        let Type::Path(TypePath { qself: None, path }) = ty else {
+            return None;
+        };
+        let segment = path.segments.last().filter(|s| s.ident == "Values")?;
+        let PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return None;
+        };
+        args.args
+            .iter()
+            .find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .map(|inner| TypedArg {
+                ty: ArgType::Append,
+                inner,
+            })
+    }
+
     pub fn inner(&self) -> &'_ Type {
         self.inner
     }
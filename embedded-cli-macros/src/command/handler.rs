@@ -0,0 +1,144 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, Lifetime, Visibility};
+
+use crate::utils::TargetType;
+
+use super::{args::ArgType, model::Command};
+
+/// Handler trait with method per command and dispatcher, that calls it.
+///
+/// First stream contains declaration of trait
+/// (it must be visible outside of anonymous scope),
+/// second one contains dispatcher.
+pub fn derive_handler(
+    vis: &Visibility,
+    target: &TargetType,
+    commands: &[Command],
+) -> (TokenStream, TokenStream) {
+    let ident = target.ident();
+    let handler_ident = format_ident!("{}Handler", ident);
+    let named_lifetime = target.named_lifetime();
+    let method_lifetime = target.lifetime().map(|lifetime| quote! { <#lifetime> });
+
+    let (methods, arms): (Vec<_>, Vec<_>) = commands
+        .iter()
+        .map(|command| {
+            let variant = &command.ident;
+            let method = method_ident(command);
+            let doc = format!("Called when `{}` command is received", command.name);
+
+            let (params, bindings) = command_params(command, target.lifetime());
+            let declarations = params.iter().map(|(name, ty)| quote! { #name: #ty });
+            let values = bindings.iter();
+            let names = params.iter().map(|(name, _)| name);
+
+            let pattern = match (&command.subcommand, command.named_args) {
+                (_, true) => quote! { #ident::#variant { #(#names: #values),* } },
+                (Some(_), false) => quote! { #ident::#variant(#(#values),*) },
+                (None, false) => quote! { #ident::#variant },
+            };
+            let values = bindings.iter();
+
+            (
+                quote! {
+                    #[doc = #doc]
+                    fn #method #method_lifetime(
+                        &mut self,
+                        cli: &mut ::embedded_cli::cli::CliHandle<'_, W, E>,
+                        #(#declarations),*
+                    ) -> Result<(), E>;
+                },
+                quote! {
+                    #pattern => handler.#method(cli, #(#values),*),
+                },
+            )
+        })
+        .unzip();
+
+    let doc = format!("Handler of [`{}`] commands", ident);
+
+    let declaration = quote! {
+        #[doc = #doc]
+        #vis trait #handler_ident<
+            W: ::embedded_cli::__private::io::Write<Error = E>,
+            E: ::embedded_cli::__private::io::Error,
+        > {
+            #(#methods)*
+        }
+    };
+
+    let dispatcher = quote! {
+        impl #named_lifetime #ident #named_lifetime {
+            /// Calls handler method, that corresponds to this command
+            #vis fn dispatch<
+                W: _io::Write<Error = E>,
+                E: _io::Error,
+                H: #handler_ident<W, E>,
+            >(
+                self,
+                cli: &mut _cli::cli::CliHandle<'_, W, E>,
+                handler: &mut H,
+            ) -> Result<(), E> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    };
+
+    (declaration, dispatcher)
+}
+
+fn method_ident(command: &Command) -> Ident {
+    let name = command
+        .ident
+        .to_string()
+        .from_case(Case::Pascal)
+        .to_case(Case::Snake);
+    if syn::parse_str::<Ident>(&name).is_ok() {
+        format_ident!("{}", name)
+    } else {
+        format_ident!("r#{}", name)
+    }
+}
+
+/// Name and type of each handler parameter with name of binding,
+/// used to destructure command
+fn command_params(
+    command: &Command,
+    lifetime: Option<&Lifetime>,
+) -> (Vec<(Ident, TokenStream)>, Vec<Ident>) {
+    let mut params = vec![];
+
+    for arg in &command.args {
+        params.push((
+            format_ident!("{}", arg.field_name),
+            field_type(&arg.field_type, arg.ty, lifetime),
+        ));
+    }
+
+    if let Some(subcommand) = &command.subcommand {
+        let name = subcommand.field_name.as_deref().unwrap_or("command");
+        params.push((
+            format_ident!("{}", name),
+            field_type(&subcommand.field_type, subcommand.ty, lifetime),
+        ));
+    }
+
+    let bindings = params
+        .iter()
+        .map(|(name, _)| format_ident!("arg_{}", name))
+        .collect();
+
+    (params, bindings)
+}
+
+fn field_type(field_type: &TokenStream, ty: ArgType, lifetime: Option<&Lifetime>) -> TokenStream {
+    match ty {
+        ArgType::Normal => quote! { #field_type },
+        ArgType::Option => quote! { Option<#field_type> },
+        ArgType::Append => quote! { ::embedded_cli::arguments::Values<#lifetime, #field_type> },
+    }
+}
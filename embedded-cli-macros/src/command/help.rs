@@ -1,6 +1,8 @@
 use darling::Result;
 use proc_macro2::TokenStream;
 use quote::quote;
+#[cfg(feature = "help")]
+use syn::LitByteStr;
 
 use super::{model::Command, TargetType};
 
@@ -11,10 +13,14 @@ use super::model::{CommandArg, CommandArgType};
 pub fn derive_help(
     target: &TargetType,
     help_title: &str,
+    link_section: Option<&str>,
     commands: &[Command],
 ) -> Result<TokenStream> {
-    let list_commands = create_help_all(commands, help_title)?;
-    let commands_help = commands.iter().map(create_command_help).collect::<Vec<_>>();
+    let list_commands = create_help_all(commands, help_title, link_section)?;
+    let commands_help = commands
+        .iter()
+        .map(|command| create_command_help(command, link_section))
+        .collect::<Vec<_>>();
 
     let ident = target.ident();
     let named_lifetime = target.named_lifetime();
@@ -24,7 +30,7 @@ pub fn derive_help(
         impl #named_lifetime _cli::service::Help for #ident #named_lifetime {
             fn command_count() -> usize { #command_count }
 
-            fn list_commands<W: _io::Write<Error = E>, E: _io::Error>(
+            fn list_commands<W: _io::Write<Error = E> + ?Sized, E: _io::Error>(
                 writer: &mut _cli::writer::Writer<'_, W, E>,
             ) -> Result<(), E> {
                 #list_commands
@@ -32,7 +38,7 @@ pub fn derive_help(
             }
 
             fn command_help<
-                W: _io::Write<Error = E>,
+                W: _io::Write<Error = E> + ?Sized,
                 E: _io::Error,
                 F: FnMut(&mut _cli::writer::Writer<'_, W, E>) -> Result<(), E>,
             >(
@@ -58,6 +64,7 @@ pub fn derive_help(
 pub fn derive_help(
     target: &TargetType,
     help_title: &str,
+    link_section: Option<&str>,
     commands: &[Command],
 ) -> Result<TokenStream> {
     let ident = target.ident();
@@ -71,13 +78,17 @@ pub fn derive_help(
 }
 
 #[cfg(feature = "help")]
-fn create_help_all(commands: &[Command], title: &str) -> Result<TokenStream> {
+fn create_help_all(
+    commands: &[Command],
+    title: &str,
+    link_section: Option<&str>,
+) -> Result<TokenStream> {
     let max_len = commands.iter().map(|c| c.name.len()).max().unwrap_or(0);
     let elements: Vec<_> = commands
         .iter()
         .map(|c| {
             let name = &c.name;
-            let help = c.help.short().unwrap_or("");
+            let help = quote_text(c.help.short().unwrap_or(""), link_section);
             quote! {
                 writer.write_list_element(#name, #help, #max_len)?;
             }
@@ -93,7 +104,7 @@ fn create_help_all(commands: &[Command], title: &str) -> Result<TokenStream> {
 }
 
 #[cfg(feature = "help")]
-fn create_command_help(command: &Command) -> TokenStream {
+fn create_command_help(command: &Command, link_section: Option<&str>) -> TokenStream {
     use convert_case::{Case, Casing};
     use quote::format_ident;
 
@@ -102,12 +113,13 @@ fn create_command_help(command: &Command) -> TokenStream {
     let name = &command.name;
 
     let help = command.help.long().map(|help| {
+        let help = quote_text(help, link_section);
         quote! { writer.writeln_str(#help)?; }
     });
 
     let usage = create_usage(name, command);
-    let args_help = create_args_help(&command.args);
-    let options_help = create_options_help(&command.args);
+    let args_help = create_args_help(&command.args, link_section);
+    let options_help = create_options_help(&command.args, link_section);
     let commands_help = create_commands_help(command);
 
     let blocks = help
@@ -132,7 +144,7 @@ fn create_command_help(command: &Command) -> TokenStream {
 
         for arg in &command.args {
             match &arg.arg_type {
-                CommandArgType::Flag { long, short } => {
+                CommandArgType::Count { long, short } | CommandArgType::Flag { long, short } => {
                     option_name_arms.push(parse::create_option_name_arm(
                         short,
                         long,
@@ -223,7 +235,7 @@ fn create_command_help(command: &Command) -> TokenStream {
 }
 
 #[cfg(feature = "help")]
-fn create_args_help(args: &[CommandArg]) -> Option<TokenStream> {
+fn create_args_help(args: &[CommandArg], link_section: Option<&str>) -> Option<TokenStream> {
     let longest_arg = args
         .iter()
         .filter(|a| a.arg_type.is_positional())
@@ -237,11 +249,9 @@ fn create_args_help(args: &[CommandArg]) -> Option<TokenStream> {
             CommandArgType::Positional => {
                 let name = arg.full_name();
 
-                let arg_help = arg.help.short().unwrap_or("");
+                let arg_help = quote_text(arg.help.short().unwrap_or(""), link_section);
 
-                Some(quote! {
-                    writer.write_list_element(#name, #arg_help, #longest_arg)?;
-                })
+                Some(write_arg_element(arg, &name, arg_help, longest_arg))
             }
             _ => None,
         })
@@ -268,72 +278,152 @@ fn create_commands_help(command: &Command) -> Option<TokenStream> {
 }
 
 #[cfg(feature = "help")]
-fn create_options_help(args: &[CommandArg]) -> TokenStream {
-    struct OptionHelp {
+fn create_options_help(args: &[CommandArg], link_section: Option<&str>) -> TokenStream {
+    struct OptionHelp<'a> {
+        heading: Option<&'a str>,
         name: String,
         help: String,
+        /// Help option is written by writer, since its short name is configurable
+        is_help: bool,
+        arg: Option<&'a CommandArg>,
     }
 
     let mut help_lines = args
         .iter()
-        .filter_map(|arg| match &arg.arg_type {
-            CommandArgType::Flag { long, short } => {
-                let name = short
-                    .map(|name| format!("-{}", name))
-                    .into_iter()
-                    .chain(long.iter().map(|name| format!("--{}", name)))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                let help = arg.help.short().unwrap_or("").to_string();
-
-                Some(OptionHelp { name, help })
-            }
-            CommandArgType::Option { long, short } => {
-                let name = short
+        .filter_map(|arg| {
+            let name = match &arg.arg_type {
+                CommandArgType::Flag { long, short } => short
                     .map(|name| format!("-{}", name))
                     .into_iter()
                     .chain(long.iter().map(|name| format!("--{}", name)))
                     .collect::<Vec<_>>()
-                    .join(", ");
-
-                let value = if arg.is_optional() {
-                    format!("[{}]", arg.value_name)
-                } else {
-                    format!("<{}>", arg.value_name)
-                };
-
-                let name = format!("{} {}", name, value);
+                    .join(", "),
+                CommandArgType::Count { long, short } => {
+                    let name = short
+                        .map(|name| format!("-{}", name))
+                        .into_iter()
+                        .chain(long.iter().map(|name| format!("--{}", name)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    // flag can be repeated
+                    format!("{}...", name)
+                }
+                CommandArgType::Option { long, short } => {
+                    let name = short
+                        .map(|name| format!("-{}", name))
+                        .into_iter()
+                        .chain(long.iter().map(|name| format!("--{}", name)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    let value = if arg.is_optional() {
+                        format!("[{}]", arg.value_name)
+                    } else {
+                        format!("<{}>", arg.value_name)
+                    };
+
+                    format!("{} {}", name, value)
+                }
+                CommandArgType::Positional => return None,
+            };
 
-                let help = arg.help.short().unwrap_or("").to_string();
+            let help = arg.help.short().unwrap_or("").to_string();
 
-                Some(OptionHelp { name, help })
-            }
-            CommandArgType::Positional => None,
+            Some(OptionHelp {
+                heading: arg.help_heading.as_deref(),
+                name,
+                help,
+                is_help: false,
+                arg: matches!(arg.arg_type, CommandArgType::Option { .. }).then_some(arg),
+            })
         })
         .collect::<Vec<_>>();
 
     help_lines.push(OptionHelp {
+        heading: None,
         name: "-h, --help".to_string(),
         help: "Print help".to_string(),
+        is_help: true,
+        arg: None,
     });
     let longest_name = help_lines.iter().map(|a| a.name.len()).max().unwrap();
 
-    let help_lines = help_lines
+    // options without heading are listed first,
+    // then each heading in order of first appearance
+    let mut headings: Vec<Option<&str>> = vec![None];
+    for help in &help_lines {
+        if !headings.contains(&help.heading) {
+            headings.push(help.heading);
+        }
+    }
+
+    let sections = headings
         .into_iter()
-        .map(|help| {
-            let name = help.name;
-            let help = help.help;
+        .map(|heading| {
+            let title = format!("{}:", heading.unwrap_or("Options"));
+            let lines = help_lines
+                .iter()
+                .filter(|help| help.heading == heading)
+                .map(|help| {
+                    if help.is_help {
+                        return quote! {
+                            writer.write_help_option(#longest_name)?;
+                        };
+                    }
+                    let text = quote_text(&help.help, link_section);
+                    if let Some(arg) = help.arg {
+                        return write_arg_element(arg, &help.name, text, longest_name);
+                    }
+                    let name = &help.name;
+                    let help = text;
+                    quote! {
+                        writer.write_list_element(#name, #help, #longest_name)?;
+                    }
+                })
+                .collect::<Vec<_>>();
+
             quote! {
-                writer.write_list_element(#name, #help, #longest_name)?;
+                writer.write_title(#title)?;
+                writer.writeln_str("")?;
+                #(#lines)*
             }
         })
-        .collect::<Vec<_>>();
+        .reduce(|acc, elem| {
+            quote! {
+                #acc
+                writer.writeln_str("")?;
+                #elem
+            }
+        })
+        .unwrap();
+
+    quote! { #sections }
+}
 
+/// Write list element of argument, that takes value.
+/// Possible values are listed, if type of value implements `ValueEnum`
+#[cfg(feature = "help")]
+fn write_arg_element(
+    arg: &CommandArg,
+    name: &str,
+    help: TokenStream,
+    longest_name: usize,
+) -> TokenStream {
+    let ty = &arg.field_type;
+    if ty.to_string().contains('\'') {
+        // borrowed values (like `&str`) can't be value enums
+        return quote! {
+            writer.write_list_element(#name, #help, #longest_name)?;
+        };
+    }
     quote! {
-        writer.write_title("Options:")?;
-        writer.writeln_str("")?;
-        #(#help_lines)*
+        {
+            #[allow(unused_imports)]
+            use _cli::__private::{EnumValues as _, NoValues as _};
+            let values = (&_cli::__private::PossibleValues::<#ty>::new()).values();
+            writer.write_list_element_with_values(#name, #help, values, #longest_name)?;
+        }
     }
 }
 
@@ -385,3 +475,24 @@ fn create_usage(name: &str, command: &Command) -> TokenStream {
             writer.writeln_str("")?;
     }
 }
+
+/// Expression with given text. When link section is given,
+/// text is stored in a static, that is placed in that section
+#[cfg(feature = "help")]
+fn quote_text(text: &str, link_section: Option<&str>) -> TokenStream {
+    match link_section {
+        Some(section) if !text.is_empty() => {
+            let len = text.len();
+            let bytes = LitByteStr::new(text.as_bytes(), proc_macro2::Span::call_site());
+            quote! {
+                {
+                    #[unsafe(link_section = #section)]
+                    static TEXT: [u8; #len] = *#bytes;
+                    // SAFETY: bytes are copied from valid str
+                    unsafe { ::core::str::from_utf8_unchecked(&TEXT) }
+                }
+            }
+        }
+        _ => quote! { #text },
+    }
+}
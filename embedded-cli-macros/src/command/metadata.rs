@@ -0,0 +1,103 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::utils::TargetType;
+
+use super::model::Command;
+
+#[cfg(feature = "metadata")]
+pub fn derive_metadata(target: &TargetType, commands: &[Command]) -> TokenStream {
+    use super::{
+        args::ArgType,
+        model::{CommandArgType, ValueHint},
+    };
+
+    let ident = target.ident();
+    let named_lifetime = target.named_lifetime();
+
+    let commands = commands.iter().map(|command| {
+        let name = &command.name;
+        let about = quote_opt_str(command.help.short());
+        let help = quote_opt_str(command.help.long());
+
+        let args = command.args.iter().map(|arg| {
+            let (kind, long, short) = match &arg.arg_type {
+                CommandArgType::Count { long, short } | CommandArgType::Flag { long, short } => {
+                    (quote! { Flag }, long, short)
+                }
+                CommandArgType::Option { long, short } => (quote! { Option }, long, short),
+                CommandArgType::Positional => (quote! { Positional }, &None, &None),
+            };
+            let long = quote_opt_str(long.as_deref());
+            let short = match short {
+                Some(short) => quote! { Some(#short) },
+                None => quote! { None },
+            };
+            let value_hint = match arg.value_hint {
+                ValueHint::FilePath => quote! { FilePath },
+                ValueHint::Hostname => quote! { Hostname },
+                ValueHint::Other => quote! { Other },
+            };
+            let value_name = &arg.value_name;
+            let required = !matches!(
+                arg.arg_type,
+                CommandArgType::Count { .. } | CommandArgType::Flag { .. }
+            ) && !arg.is_optional()
+                && arg.ty != ArgType::Append
+                && arg.default_value.is_none();
+            let help = quote_opt_str(arg.help.short());
+
+            quote! {
+                _cli::metadata::ArgInfo {
+                    kind: _cli::metadata::ArgKind::#kind,
+                    long: #long,
+                    short: #short,
+                    value_name: #value_name,
+                    value_hint: _cli::metadata::ValueHint::#value_hint,
+                    required: #required,
+                    help: #help,
+                }
+            }
+        });
+
+        let subcommands = match &command.subcommand {
+            Some(subcommand) => {
+                let ty = &subcommand.field_type;
+                quote! { <#ty as _cli::metadata::Metadata>::commands() }
+            }
+            None => quote! { _cli::__private::Vec::new() },
+        };
+
+        quote! {
+            _cli::metadata::CommandInfo {
+                name: #name,
+                about: #about,
+                help: #help,
+                args: _cli::__private::Vec::from([#(#args),*]),
+                subcommands: #subcommands,
+            }
+        }
+    });
+
+    quote! {
+        impl #named_lifetime _cli::metadata::Metadata for #ident #named_lifetime {
+            fn commands() -> _cli::__private::Vec<_cli::metadata::CommandInfo> {
+                _cli::__private::Vec::from([#(#commands),*])
+            }
+        }
+    }
+}
+
+#[allow(unused_variables)]
+#[cfg(not(feature = "metadata"))]
+pub fn derive_metadata(target: &TargetType, commands: &[Command]) -> TokenStream {
+    quote! {}
+}
+
+#[cfg(feature = "metadata")]
+fn quote_opt_str(text: Option<&str>) -> TokenStream {
+    match text {
+        Some(text) => quote! { Some(#text) },
+        None => quote! { None },
+    }
+}
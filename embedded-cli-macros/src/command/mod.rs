@@ -1,4 +1,4 @@
-use darling::{Error, FromDeriveInput, Result};
+use darling::{util::PathList, Error, FromDeriveInput, Result};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Data, DeriveInput};
@@ -11,23 +11,34 @@ mod args;
 mod autocomplete;
 #[cfg(feature = "help")]
 mod doc;
+mod handler;
 mod help;
+mod metadata;
 mod model;
+mod names;
+mod owned;
 mod parse;
 
 #[derive(FromDeriveInput, Default)]
 #[darling(default, attributes(command), forward_attrs(allow, doc, cfg))]
 struct ServiceAttrs {
+    derive_handler: bool,
+    derive_owned: bool,
     help_title: Option<String>,
+    owned_derive: Option<PathList>,
     skip_autocomplete: bool,
     skip_help: bool,
     skip_from_raw: bool,
+    strings_link_section: Option<String>,
 }
 
-pub fn derive_command(input: DeriveInput) -> Result<TokenStream> {
+/// Returns items, that must be visible outside of generated scope,
+/// and all other generated code
+pub fn derive_command(input: DeriveInput) -> Result<(TokenStream, TokenStream)> {
     let opts = ServiceAttrs::from_derive_input(&input)?;
     let DeriveInput {
         ident,
+        vis,
         data,
         generics,
         ..
@@ -59,14 +70,37 @@ pub fn derive_command(input: DeriveInput) -> Result<TokenStream> {
     let derive_help = if opts.skip_help {
         quote! {}
     } else {
-        help::derive_help(&target, &help_title, &commands)?
+        help::derive_help(
+            &target,
+            &help_title,
+            opts.strings_link_section.as_deref(),
+            &commands,
+        )?
     };
     let derive_from_raw = if opts.skip_from_raw {
         quote! {}
     } else {
         parse::derive_from_raw(&target, &commands)?
     };
+    let derive_metadata = metadata::derive_metadata(&target, &commands);
+    let impl_names = names::derive_names(&target, &commands)?;
     let impl_processor = processor::impl_processor(&target)?;
+    let (owned_type, derive_owned) = match (opts.derive_owned, opts.owned_derive) {
+        (true, derives) => {
+            owned::derive_owned(&vis, &target, &commands, &derives.unwrap_or_default())?
+        }
+        (false, Some(_)) => {
+            return Err(Error::custom(
+                "owned_derive can be used only with derive_owned",
+            ))
+        }
+        (false, None) => (quote! {}, quote! {}),
+    };
+    let (handler_trait, derive_handler) = if opts.derive_handler {
+        handler::derive_handler(&vis, &target, &commands)
+    } else {
+        (quote! {}, quote! {})
+    };
 
     let output = quote! {
         #derive_autocomplete
@@ -75,8 +109,22 @@ pub fn derive_command(input: DeriveInput) -> Result<TokenStream> {
 
         #derive_from_raw
 
+        #derive_metadata
+
+        #impl_names
+
         #impl_processor
+
+        #derive_owned
+
+        #derive_handler
+    };
+
+    let items = quote! {
+        #owned_type
+
+        #handler_trait
     };
 
-    Ok(output)
+    Ok((items, output))
 }
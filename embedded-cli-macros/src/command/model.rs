@@ -2,7 +2,7 @@ use convert_case::{Case, Casing};
 use darling::{Error, FromField, FromMeta, FromVariant, Result};
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{Expr, Field, Fields, FieldsNamed, FieldsUnnamed, Variant};
+use syn::{Expr, Field, Fields, FieldsNamed, FieldsUnnamed, Path, Type, Variant};
 
 use super::args::{ArgType, TypedArg};
 
@@ -14,7 +14,10 @@ use super::doc::Help;
 #[darling(default, attributes(command), forward_attrs(allow, doc, cfg))]
 struct CommandAttrs {
     attrs: Vec<syn::Attribute>,
+    default_subcommand: Option<String>,
     name: Option<String>,
+    on_parsed: Option<Path>,
+    options_first: bool,
     subcommand: bool,
 }
 
@@ -64,6 +67,21 @@ impl FromMeta for ShortName {
     }
 }
 
+#[derive(Debug)]
+enum ArgAction {
+    /// Flag increments value each time it's given (like `-vvv`)
+    Count,
+}
+
+impl FromMeta for ArgAction {
+    fn from_string(value: &str) -> Result<Self> {
+        match value {
+            "count" => Ok(Self::Count),
+            _ => Err(Error::unknown_value(value)),
+        }
+    }
+}
+
 #[derive(Debug)]
 enum TypedDefault {
     Generated,
@@ -80,19 +98,57 @@ impl FromMeta for TypedDefault {
     }
 }
 
+/// Kind of value, that host tools can use to suggest values
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ValueHint {
+    #[default]
+    Other,
+    FilePath,
+    Hostname,
+}
+
+impl FromMeta for ValueHint {
+    fn from_expr(value: &Expr) -> Result<Self> {
+        match value {
+            Expr::Path(path) => match path.path.get_ident() {
+                Some(ident) => Self::from_string(&ident.to_string()),
+                None => Err(Error::unexpected_expr_type(value)),
+            },
+            Expr::Lit(lit) => Self::from_value(&lit.lit),
+            _ => Err(Error::unexpected_expr_type(value)),
+        }
+    }
+
+    fn from_string(value: &str) -> Result<Self> {
+        match value {
+            "FilePath" => Ok(Self::FilePath),
+            "Hostname" => Ok(Self::Hostname),
+            "Other" => Ok(Self::Other),
+            _ => Err(Error::unknown_value(value)),
+        }
+    }
+}
+
 #[derive(Debug, FromField, Default)]
 #[darling(default, attributes(arg), forward_attrs(allow, doc, cfg))]
 struct ArgAttrs {
+    action: Option<ArgAction>,
+    append: bool,
     default_value: Option<String>,
     default_value_t: Option<TypedDefault>,
+    #[cfg_attr(not(feature = "help"), allow(dead_code))]
+    help_heading: Option<String>,
     short: Option<ShortName>,
     long: Option<LongName>,
+    trailing: bool,
+    value_hint: Option<ValueHint>,
     value_name: Option<String>,
 }
 
 #[derive(Debug, FromField, Default)]
 #[darling(default, attributes(command), forward_attrs(allow, doc, cfg))]
 struct FieldCommandAttrs {
+    default_subcommand: Option<String>,
     subcommand: bool,
 }
 
@@ -104,6 +160,12 @@ pub enum CommandArgType {
         long: Option<String>,
         short: Option<char>,
     },
+    /// Arg is flag, that counts how many times it is given (`-vvv`).
+    /// At least one of long or short is set to Some
+    Count {
+        long: Option<String>,
+        short: Option<char>,
+    },
     /// Arg is option and is set via long (--name) or short (-n) syntax.
     /// At least one of long or short is set to Some
     Option {
@@ -118,7 +180,9 @@ impl CommandArgType {
     pub fn is_option(&self) -> bool {
         matches!(
             self,
-            CommandArgType::Flag { .. } | CommandArgType::Option { .. }
+            CommandArgType::Count { .. }
+                | CommandArgType::Flag { .. }
+                | CommandArgType::Option { .. }
         )
     }
 
@@ -134,7 +198,15 @@ pub struct CommandArg {
     pub field_type: TokenStream,
     #[cfg(feature = "help")]
     pub help: Help,
+    /// Title of section in help, where this option is listed
+    #[cfg(feature = "help")]
+    pub help_heading: Option<String>,
+    /// Positional arg, that takes rest of the line as it was typed
+    pub trailing: bool,
     pub ty: ArgType,
+    /// Kind of value, that is exported with metadata
+    #[cfg_attr(not(feature = "metadata"), allow(dead_code))]
+    pub value_hint: ValueHint,
     pub value_name: String,
 }
 
@@ -160,20 +232,70 @@ impl CommandArg {
             LongName::Fixed(name) => name,
         });
 
-        let aa = TypedArg::new(&field.ty);
+        let aa = if arg_attrs.append {
+            TypedArg::append(&field.ty).ok_or_else(|| {
+                Error::custom("append requires field of type Values<'a, T>").with_span(&field.ty)
+            })?
+        } else {
+            TypedArg::new(&field.ty)
+        };
 
         let ty = aa.ty();
         let field_type = aa.inner();
         let field_type = quote! { #field_type };
         let arg_type = if long.is_some() || short.is_some() {
-            if field_type.to_string() == "bool" {
+            if matches!(arg_attrs.action, Some(ArgAction::Count)) {
+                if ty != ArgType::Normal
+                    || arg_attrs.default_value.is_some()
+                    || arg_attrs.default_value_t.is_some()
+                {
+                    return Err(Error::custom(
+                        "Counted flag must have integer type without default value",
+                    )
+                    .with_span(&field.ty));
+                }
+                CommandArgType::Count { long, short }
+            } else if field_type.to_string() == "bool" {
                 CommandArgType::Flag { long, short }
             } else {
                 CommandArgType::Option { long, short }
             }
+        } else if arg_attrs.action.is_some() {
+            return Err(
+                Error::custom("action can be used only with long or short flag")
+                    .with_span(&field.ty),
+            );
         } else {
             CommandArgType::Positional
         };
+        if arg_attrs.value_hint.is_some()
+            && matches!(
+                arg_type,
+                CommandArgType::Count { .. } | CommandArgType::Flag { .. }
+            )
+        {
+            return Err(Error::custom("value_hint can't be used with flag").with_span(&field.ty));
+        }
+        if arg_attrs.trailing
+            && (arg_type != CommandArgType::Positional
+                || ty == ArgType::Append
+                || !matches!(&aa.inner(), Type::Reference(reference) if is_str(&reference.elem)))
+        {
+            return Err(Error::custom(
+                "trailing can be used only with positional argument of type &str",
+            )
+            .with_span(&field.ty));
+        }
+        if ty == ArgType::Append
+            && (!matches!(arg_type, CommandArgType::Option { .. })
+                || arg_attrs.default_value.is_some()
+                || arg_attrs.default_value_t.is_some())
+        {
+            return Err(Error::custom(
+                "append can be used only with long or short option without default value",
+            )
+            .with_span(&field.ty));
+        }
 
         let default_value = match (arg_attrs.default_value, arg_attrs.default_value_t) {
             (Some(value), None) => Some(quote! {
@@ -201,14 +323,18 @@ impl CommandArg {
             field_type,
             #[cfg(feature = "help")]
             help: Help::parse(&field.attrs)?,
+            #[cfg(feature = "help")]
+            help_heading: arg_attrs.help_heading,
+            trailing: arg_attrs.trailing,
             ty,
+            value_hint: arg_attrs.value_hint.unwrap_or_default(),
             value_name,
         })
     }
 
     pub fn full_name(&self) -> String {
         match &self.arg_type {
-            CommandArgType::Flag { long, short } => long
+            CommandArgType::Count { long, short } | CommandArgType::Flag { long, short } => long
                 .as_ref()
                 .map(|name| format!("--{}", name))
                 .or(short.map(|n| format!("-{}", n)))
@@ -226,10 +352,15 @@ impl CommandArg {
                 }
             }
             CommandArgType::Positional => {
-                if self.is_optional() {
+                let name = if self.is_optional() {
                     format!("[{}]", self.value_name)
                 } else {
                     format!("<{}>", self.value_name)
+                };
+                if self.trailing {
+                    format!("{}...", name)
+                } else {
+                    name
                 }
             }
         }
@@ -241,13 +372,15 @@ impl CommandArg {
 }
 
 pub struct Subcommand {
+    /// Name of subcommand, that is used when no subcommand is given
+    pub default: Option<String>,
     pub field_name: Option<String>,
     pub field_type: TokenStream,
     pub ty: ArgType,
 }
 
 impl Subcommand {
-    fn parse_field(field: &Field) -> Result<Self> {
+    fn parse_field(field: &Field, default: Option<String>) -> Result<Self> {
         let arg = TypedArg::new(&field.ty);
 
         let ty = arg.ty();
@@ -257,22 +390,15 @@ impl Subcommand {
         let field_name = field.ident.as_ref().map(|ident| ident.to_string());
 
         Ok(Self {
+            default,
             field_name,
             field_type,
             ty,
         })
     }
 
-    pub fn full_name(&self) -> String {
-        if self.is_optional() {
-            "[COMMAND]".to_string()
-        } else {
-            "<COMMAND>".to_string()
-        }
-    }
-
     pub fn is_optional(&self) -> bool {
-        self.ty == ArgType::Option
+        self.ty == ArgType::Option || self.default.is_some()
     }
 }
 
@@ -283,6 +409,11 @@ pub struct Command {
     pub help: Help,
     pub ident: Ident,
     pub named_args: bool,
+    /// Function, that is called with parsed command before it is returned
+    pub on_parsed: Option<Path>,
+    /// Options are accepted only before first positional argument,
+    /// all following tokens are values (even if they start with `-`)
+    pub options_first: bool,
     pub subcommand: Option<Subcommand>,
 }
 
@@ -291,12 +422,26 @@ impl Command {
         let variant_ident = &variant.ident;
         let attrs = CommandAttrs::from_variant(variant)?;
 
+        if attrs.default_subcommand.is_some() && !attrs.subcommand {
+            return Err(
+                Error::custom("default_subcommand can be used only with subcommand")
+                    .with_span(variant_ident),
+            );
+        }
+
         let (named_args, (args, subcommand)) = match &variant.fields {
             Fields::Unit => (false, (vec![], None)),
             Fields::Unnamed(fields) => (false, Self::parse_tuple_variant(&attrs, fields)?),
             Fields::Named(fields) => (true, Self::parse_struct_variant(fields)?),
         };
 
+        if attrs.options_first && args.iter().any(|arg| arg.ty == ArgType::Append) {
+            return Err(
+                Error::custom("options_first can't be used together with append")
+                    .with_span(variant_ident),
+            );
+        }
+
         let name = attrs.name.unwrap_or_else(|| {
             variant_ident
                 .to_string()
@@ -311,12 +456,15 @@ impl Command {
             help: Help::parse(&attrs.attrs)?,
             ident: variant_ident.clone(),
             named_args,
+            on_parsed: attrs.on_parsed,
+            options_first: attrs.options_first,
             subcommand,
         })
     }
 
     fn parse_struct_variant(fields: &FieldsNamed) -> Result<(Vec<CommandArg>, Option<Subcommand>)> {
         let mut has_positional = false;
+        let mut has_trailing = false;
         let mut subcommand = None;
 
         let mut errors = Error::accumulator();
@@ -340,9 +488,18 @@ impl Command {
                                 )
                                 .with_span(&field.ident));
                             }
-                            subcommand = Some(Subcommand::parse_field(field)?);
+                            subcommand = Some(Subcommand::parse_field(
+                                field,
+                                command_attrs.default_subcommand,
+                            )?);
                             Ok(None)
                         } else {
+                            if command_attrs.default_subcommand.is_some() {
+                                return Err(Error::custom(
+                                    "default_subcommand can be used only with subcommand",
+                                )
+                                .with_span(&field.ident));
+                            }
                             let arg = CommandArg::parse(field)?;
 
                             if arg.arg_type.is_positional() && subcommand.is_some() {
@@ -351,7 +508,14 @@ impl Command {
                                 )
                                 .with_span(&field.ident));
                             }
+                            if arg.arg_type.is_positional() && has_trailing {
+                                return Err(Error::custom(
+                                    "Trailing argument must be the last positional argument",
+                                )
+                                .with_span(&field.ident));
+                            }
                             has_positional |= arg.arg_type.is_positional();
+                            has_trailing |= arg.trailing;
 
                             Ok(Some(arg))
                         }
@@ -376,8 +540,15 @@ impl Command {
             return Err(Error::custom("Tuple variant must be a subcommand").with_span(&fields));
         }
 
-        let subcommand = Some(Subcommand::parse_field(&fields.unnamed[0])?);
+        let subcommand = Some(Subcommand::parse_field(
+            &fields.unnamed[0],
+            attrs.default_subcommand.clone(),
+        )?);
 
         Ok((vec![], subcommand))
     }
 }
+
+pub fn is_str(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("str"))
+}
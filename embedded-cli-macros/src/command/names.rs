@@ -0,0 +1,42 @@
+use convert_case::{Case, Casing};
+use darling::Result;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::utils::TargetType;
+
+use super::model::Command;
+
+pub fn derive_names(target: &TargetType, commands: &[Command]) -> Result<TokenStream> {
+    let ident = target.ident();
+    let named_lifetime = target.named_lifetime();
+
+    let names = commands.iter().map(|command| &command.name);
+    let consts = commands.iter().map(|command| {
+        let name = &command.name;
+        let const_ident = format_ident!(
+            "NAME_{}",
+            command
+                .ident
+                .to_string()
+                .from_case(Case::Camel)
+                .to_case(Case::UpperSnake)
+        );
+        let doc = format!("Name of `{}` command", name);
+        quote! {
+            #[doc = #doc]
+            pub const #const_ident: &'static str = #name;
+        }
+    });
+
+    let output = quote! {
+        impl #named_lifetime #ident #named_lifetime {
+            /// Names of all commands in declaration order
+            pub const COMMAND_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            #(#consts)*
+        }
+    };
+
+    Ok(output)
+}
@@ -0,0 +1,207 @@
+use darling::{util::PathList, Error, Result};
+use proc_macro2::TokenStream;
+#[cfg(feature = "owned")]
+use quote::{format_ident, quote};
+#[cfg(feature = "owned")]
+use syn::Type;
+use syn::Visibility;
+
+use crate::utils::TargetType;
+
+use super::model::Command;
+#[cfg(feature = "owned")]
+use super::{args::ArgType, model::is_str};
+
+/// Owned twin of command type (with given derives) and conversion into it.
+///
+/// First stream contains declaration of owned type
+/// (it must be visible outside of anonymous scope),
+/// second one contains conversion.
+#[cfg(feature = "owned")]
+pub fn derive_owned(
+    vis: &Visibility,
+    target: &TargetType,
+    commands: &[Command],
+    derives: &PathList,
+) -> Result<(TokenStream, TokenStream)> {
+    let ident = target.ident();
+    let owned_ident = format_ident!("{}Owned", ident);
+    let named_lifetime = target.named_lifetime();
+
+    let mut errors = Error::accumulator();
+    let (variants, arms): (Vec<_>, Vec<_>) = commands
+        .iter()
+        .filter_map(|command| errors.handle_in(|| owned_variant(ident, &owned_ident, command)))
+        .unzip();
+    errors.finish()?;
+
+    let doc = format!("Owned version of [`{}`]", ident);
+    let derives = if derives.is_empty() {
+        quote! {}
+    } else {
+        let derives = derives.iter();
+        quote! { #[derive(#(#derives),*)] }
+    };
+
+    let declaration = quote! {
+        #[doc = #doc]
+        #derives
+        #vis enum #owned_ident {
+            #(#variants),*
+        }
+    };
+
+    let conversion = quote! {
+        impl #named_lifetime #ident #named_lifetime {
+            #vis fn into_owned(self) -> #owned_ident {
+                self.into()
+            }
+        }
+
+        impl #named_lifetime From<#ident #named_lifetime> for #owned_ident {
+            fn from(command: #ident #named_lifetime) -> Self {
+                match command {
+                    #(#arms)*
+                }
+            }
+        }
+    };
+
+    Ok((declaration, conversion))
+}
+
+#[allow(unused_variables)]
+#[cfg(not(feature = "owned"))]
+pub fn derive_owned(
+    vis: &Visibility,
+    target: &TargetType,
+    commands: &[Command],
+    derives: &PathList,
+) -> Result<(TokenStream, TokenStream)> {
+    Err(Error::custom(
+        "derive_owned requires `owned` feature of embedded-cli",
+    ))
+}
+
+#[cfg(feature = "owned")]
+fn owned_variant(
+    ident: &syn::Ident,
+    owned_ident: &syn::Ident,
+    command: &Command,
+) -> Result<(TokenStream, TokenStream)> {
+    let variant = &command.ident;
+
+    let mut fields = vec![];
+    for arg in &command.args {
+        fields.push((
+            format_ident!("{}", arg.field_name),
+            owned_field(&arg.field_type, arg.ty, false)?,
+        ));
+    }
+
+    if let Some(subcommand) = &command.subcommand {
+        let (ty, conversion) = owned_field(&subcommand.field_type, subcommand.ty, true)?;
+        match &subcommand.field_name {
+            Some(name) => fields.push((format_ident!("{}", name), (ty, conversion))),
+            None => {
+                let conversion = conversion(quote! { sub_command });
+                return Ok((
+                    quote! { #variant(#ty) },
+                    quote! {
+                        #ident::#variant(sub_command) => #owned_ident::#variant(#conversion),
+                    },
+                ));
+            }
+        }
+    }
+
+    if !command.named_args {
+        return Ok((
+            quote! { #variant },
+            quote! { #ident::#variant => #owned_ident::#variant, },
+        ));
+    }
+
+    let names = fields.iter().map(|(name, _)| name);
+    let declarations = fields.iter().map(|(name, (ty, _))| quote! { #name: #ty });
+    let conversions = fields.iter().map(|(name, (_, conversion))| {
+        let conversion = conversion(quote! { #name });
+        quote! { #name: #conversion }
+    });
+
+    Ok((
+        quote! { #variant { #(#declarations),* } },
+        quote! {
+            #ident::#variant { #(#names),* } => #owned_ident::#variant { #(#conversions),* },
+        },
+    ))
+}
+
+/// Creates expression, that converts given borrowed value into owned one
+#[cfg(feature = "owned")]
+type Conversion = fn(TokenStream) -> TokenStream;
+
+/// Type of owned field and function, that creates conversion
+/// of borrowed value into owned one
+#[cfg(feature = "owned")]
+fn owned_field(
+    field_type: &TokenStream,
+    ty: ArgType,
+    subcommand: bool,
+) -> Result<(TokenStream, Conversion)> {
+    let field_type: Type = syn::parse2(field_type.clone())?;
+
+    let owned = match &field_type {
+        Type::Reference(reference) if is_str(&reference.elem) => {
+            Some(quote! { ::embedded_cli::__private::String })
+        }
+        Type::Path(path) if subcommand && is_borrowed(&field_type) => {
+            // subcommand must also derive owned twin
+            let mut path = path.clone();
+            if let Some(last) = path.path.segments.last_mut() {
+                last.ident = format_ident!("{}Owned", last.ident);
+                last.arguments = syn::PathArguments::None;
+            }
+            Some(quote! { #path })
+        }
+        field_type if is_borrowed(field_type) => {
+            return Err(Error::custom(
+                "Only &str can be converted to owned value. Try using owned type.",
+            )
+            .with_span(field_type))
+        }
+        _ => None,
+    };
+
+    Ok(match (owned, ty) {
+        (Some(owned), ArgType::Normal) => (owned, |value| quote! { #value.into() }),
+        (Some(owned), ArgType::Option) => (
+            quote! { Option<#owned> },
+            |value| quote! { #value.map(Into::into) },
+        ),
+        (None, ArgType::Normal) => (quote! { #field_type }, |value| value),
+        (None, ArgType::Option) => (quote! { Option<#field_type> }, |value| value),
+        (owned, ArgType::Append) => {
+            let owned = owned.unwrap_or_else(|| quote! { #field_type });
+            (
+                quote! { ::embedded_cli::__private::Vec<#owned> },
+                |value| quote! { #value.iter().map(Into::into).collect() },
+            )
+        }
+    })
+}
+
+/// Whether type contains any references or lifetimes
+#[cfg(feature = "owned")]
+fn is_borrowed(ty: &Type) -> bool {
+    fn has_borrow(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Punct(punct) => {
+                punct.as_char() == '&' || punct.as_char() == '\''
+            }
+            proc_macro2::TokenTree::Group(group) => has_borrow(group.stream()),
+            _ => false,
+        })
+    }
+    has_borrow(quote! { #ty })
+}
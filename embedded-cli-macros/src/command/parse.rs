@@ -19,6 +19,8 @@ pub fn derive_from_raw(target: &TargetType, commands: &[Command]) -> Result<Toke
     let output = quote! {
 
         impl<'a> _cli::service::FromRaw<'a> for #ident #named_lifetime {
+            const NAMES: &'static [&'static str] = Self::COMMAND_NAMES;
+
             fn parse(command: _cli::command::RawCommand<'a>) -> Result<Self, _cli::service::ParseError<'a>> {
                 #parsing
                 Ok(command)
@@ -46,7 +48,7 @@ fn command_parsing(ident: &Ident, command: &Command) -> TokenStream {
     let variant_fqn = quote! { #ident::#variant_name };
 
     let rhs = if command.args.is_empty() && command.subcommand.is_none() {
-        quote! { #variant_fqn, }
+        quote! { #variant_fqn }
     } else {
         let (parsing, arguments) = create_arg_parsing(command);
         if command.named_args {
@@ -66,7 +68,19 @@ fn command_parsing(ident: &Ident, command: &Command) -> TokenStream {
         }
     };
 
-    quote! {  #name => #rhs }
+    let rhs = if let Some(on_parsed) = &command.on_parsed {
+        quote! {
+            {
+                let mut command = #rhs;
+                #on_parsed(&mut command)?;
+                command
+            }
+        }
+    } else {
+        rhs
+    };
+
+    quote! {  #name => #rhs, }
 }
 
 fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
@@ -78,6 +92,7 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
     let mut option_value_arms = vec![];
 
     let mut positional = 0usize;
+    let mut has_trailing = false;
     for arg in &command.args {
         let fi_raw = format_ident!("{}", arg.field_name);
         let fi = format_ident!("arg_{}", arg.field_name);
@@ -100,6 +115,20 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
                     },
                 ));
             }
+            CommandArgType::Count { long, short } => {
+                arg_default = Some(quote! { 0 });
+
+                option_name_arms.push(create_option_name_arm(
+                    short,
+                    long,
+                    quote! {
+                        {
+                            #fi = Some(#fi.map_or(1, |count: #ty| count.saturating_add(1)));
+                            state = States::Normal;
+                        }
+                    },
+                ));
+            }
             CommandArgType::Option { long, short } => {
                 arg_default = arg.default_value.clone();
                 let state = format_ident!(
@@ -108,10 +137,24 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
                 );
                 extra_states.push(quote! { #state, });
 
-                let parse_value = create_parse_arg_value(ty);
+                let name = long
+                    .as_ref()
+                    .map(|name| format!("--{}", name))
+                    .or(short.map(|name| format!("-{}", name)))
+                    .unwrap();
+                let parse_value = create_parse_arg_value(ty, &name);
+                let store_value = if arg.ty == ArgType::Append {
+                    // values are parsed again when iterated, so only count is stored
+                    quote! {
+                        let _: #ty = #parse_value;
+                        #fi += 1;
+                    }
+                } else {
+                    quote! { #fi = Some(#parse_value); }
+                };
                 option_value_arms.push(quote! {
                     _cli::arguments::Arg::Value(val) if state == States::#state => {
-                        #fi = Some(#parse_value);
+                        #store_value
                         state = States::Normal;
                     }
                 });
@@ -124,19 +167,53 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
             }
             CommandArgType::Positional => {
                 arg_default = arg.default_value.clone();
-                let parse_value = create_parse_arg_value(ty);
-
-                positional_value_arms.push(quote! {
-                    #positional => {
-                        #fi = Some(#parse_value);
-                    },
-                });
+                let name = format!("<{}>", arg.value_name);
+                let parse_value = create_parse_arg_value(ty, &name);
+
+                if arg.trailing {
+                    has_trailing = true;
+                    // without original line rest of it can't be taken as typed
+                    positional_value_arms.push(quote! {
+                        #positional => {
+                            let Some(rest) = command.line_from(arg_index) else {
+                                return Err(_cli::service::ParseError::LineUnavailable {
+                                    name: #name,
+                                });
+                            };
+                            #fi = Some(rest);
+                            break;
+                        },
+                    });
+                } else {
+                    positional_value_arms.push(quote! {
+                        #positional => {
+                            #fi = Some(#parse_value);
+                        },
+                    });
+                }
                 positional += 1;
             }
         }
 
         let constructor_arg = match arg.ty {
             ArgType::Option => quote! { #fi_raw: #fi },
+            ArgType::Append => {
+                let (long, short) = match &arg.arg_type {
+                    CommandArgType::Option { long, short } => (long, short),
+                    _ => unreachable!("only options can be appended"),
+                };
+                let long = match long {
+                    Some(long) => quote! { Some(#long) },
+                    None => quote! { None },
+                };
+                let short = match short {
+                    Some(short) => quote! { Some(#short) },
+                    None => quote! { None },
+                };
+                quote! {
+                    #fi_raw: _cli::arguments::Values::new(command.args(), #short, #long, #fi)
+                }
+            }
             ArgType::Normal => {
                 if let Some(default) = arg_default {
                     quote! {
@@ -153,8 +230,13 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
             }
         };
 
+        let initial = if arg.ty == ArgType::Append {
+            quote! { 0usize }
+        } else {
+            quote! { None }
+        };
         variables.push(quote! {
-            let mut #fi = None;
+            let mut #fi = #initial;
         });
         arguments.push(quote! {
             #constructor_arg,
@@ -184,16 +266,29 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
             break;
         });
 
-        let constructor_arg = match subcommand.ty {
-            ArgType::Option => quote! { #fi_raw #fi },
-            ArgType::Normal => {
-                let name = subcommand.full_name();
+        let constructor_arg = match (subcommand.ty, &subcommand.default) {
+            (arg_type, Some(default)) => {
+                let value = match arg_type {
+                    ArgType::Option => quote! { Some(sub) },
+                    ArgType::Normal | ArgType::Append => quote! { sub },
+                };
                 quote! {
-                    #fi_raw #fi.ok_or(_cli::service::ParseError::MissingRequiredArgument {
-                        name: #name,
-                    })?
+                    #fi_raw match #fi {
+                        Some(sub) => #value,
+                        None => {
+                            let raw = _cli::command::RawCommand::new(#default, Default::default());
+                            let sub = <#ty as _cli::service::FromRaw>::parse(raw)?;
+                            #value
+                        }
+                    }
                 }
             }
+            (ArgType::Option, None) => quote! { #fi_raw #fi },
+            (ArgType::Normal | ArgType::Append, None) => quote! {
+                #fi_raw #fi.ok_or(_cli::service::ParseError::MissingSubcommand {
+                    expected: <#ty as _cli::service::FromRaw>::NAMES,
+                })?
+            },
         };
 
         variables.push(quote! {
@@ -220,19 +315,45 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
             })
         }
     } else {
+        // after first positional all tokens are values
+        let values_only = if command.options_first {
+            quote! { args.values_only(); }
+        } else {
+            quote! {}
+        };
         quote! {
             _cli::arguments::Arg::Value(val) if state == States::Normal => {
-                match positional {
+                // trailing argument never returns to the loop,
+                // so counters are updated before value is stored
+                let index = positional;
+                positional += 1;
+                #values_only
+                match index {
                     #(#positional_value_arms)*
                     _ => return Err(_cli::service::ParseError::UnexpectedArgument{
                         value: val
                     })
                 }
-                positional += 1;
             }
         }
     };
 
+    let negative_numbers = if command.options_first {
+        quote! { args.negative_numbers(); }
+    } else {
+        quote! {}
+    };
+
+    // index of current argument is needed to find rest of the line
+    let (index_declaration, index_increment) = if has_trailing {
+        (
+            quote! { let mut arg_index = 0usize; },
+            quote! { arg_index += 1; },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
     let parsing = quote! {
         #(#variables)*
 
@@ -245,6 +366,8 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
         let mut positional = 0;
 
         let mut args = command.args().args();
+        #negative_numbers
+        #index_declaration
         while let Some(arg) = args.next() {
             match arg {
                 #(#option_name_arms)*
@@ -259,6 +382,7 @@ fn create_arg_parsing(command: &Command) -> (TokenStream, Vec<TokenStream>) {
                 }
                 _cli::arguments::Arg::DoubleDash => {}
             }
+            #index_increment
         }
     };
 
@@ -291,8 +415,14 @@ pub fn create_option_name_arm(
     }
 }
 
-fn create_parse_arg_value(ty: &TokenStream) -> TokenStream {
+fn create_parse_arg_value(ty: &TokenStream, name: &str) -> TokenStream {
     quote! {
-        <#ty as _cli::arguments::FromArgument>::from_arg(val)?,
+        <#ty as _cli::arguments::FromArgument>::from_arg(val).map_err(|err| {
+            _cli::service::ParseError::ParseValueError {
+                name: Some(#name),
+                value: err.value,
+                expected: err.expected,
+            }
+        })?
     }
 }
@@ -1,18 +1,27 @@
 use darling::{Error, FromVariant, Result};
 use proc_macro2::Ident;
-use syn::{Fields, Type, Variant};
+use syn::{Fields, Path, Type, Variant};
 
 #[derive(Debug, FromVariant, Default)]
 #[darling(default, attributes(group), forward_attrs(allow, doc, cfg))]
 struct GroupAttrs {
+    enabled: Option<Path>,
+    fallback: bool,
     hidden: bool,
+    prefix: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct CommandGroup {
+    /// Function, that is called to check if group is enabled at runtime
+    pub enabled: Option<Path>,
     pub ident: Ident,
     pub field_type: Type,
+    /// Group receives all commands, that are unknown to other groups
+    pub fallback: bool,
     pub hidden: bool,
+    /// Name, that must be written before each command of this group
+    pub prefix: Option<String>,
 }
 
 impl CommandGroup {
@@ -38,10 +47,42 @@ impl CommandGroup {
             }
         };
 
+        if attrs.fallback {
+            if attrs.prefix.is_some() {
+                return Err(Error::custom("Fallback group can't have a prefix").with_span(variant));
+            }
+            let is_raw = match &field.ty {
+                Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "RawCommand"),
+                _ => false,
+            };
+            if !is_raw {
+                return Err(
+                    Error::custom("Fallback group must hold RawCommand").with_span(&field.ty)
+                );
+            }
+        }
+
+        if let Some(prefix) = &attrs.prefix {
+            if prefix.is_empty() || prefix.contains(' ') {
+                return Err(
+                    Error::custom("Group prefix must be non-empty and contain no spaces")
+                        .with_span(variant),
+                );
+            }
+        }
+
         Ok(Self {
+            enabled: attrs.enabled,
             ident: variant_ident.clone(),
             field_type: field.ty.clone(),
-            hidden: attrs.hidden,
+            fallback: attrs.fallback,
+            // fallback group has no known commands to show in help or autocompletion
+            hidden: attrs.hidden || attrs.fallback,
+            prefix: attrs.prefix,
         })
     }
 }
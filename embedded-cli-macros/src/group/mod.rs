@@ -1,6 +1,7 @@
+use convert_case::{Case, Casing};
 use darling::{Error, Result};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{Data, DeriveInput};
 
 use crate::{processor, utils::TargetType};
@@ -31,18 +32,27 @@ pub fn derive_command_group(input: DeriveInput) -> Result<TokenStream> {
         .iter()
         .filter_map(|variant| errors.handle_in(|| CommandGroup::parse(variant)))
         .collect();
+    for group in groups.iter().filter(|group| group.fallback).skip(1) {
+        errors.push(
+            Error::custom("Only one fallback group can be specified").with_span(&group.ident),
+        );
+    }
     errors.finish()?;
 
     let derive_autocomplete = derive_autocomplete(&target, &groups);
     let derive_help = derive_help(&target, &groups);
     let derive_from_raw = derive_from_raw(&target, &groups);
+    let derive_metadata = derive_metadata(&target, &groups);
     let impl_processor = processor::impl_processor(&target)?;
+    let impl_group_processor = impl_group_processor(&target, &groups);
 
     let output = quote! {
         #derive_autocomplete
         #derive_help
         #derive_from_raw
+        #derive_metadata
         #impl_processor
+        #impl_group_processor
     };
 
     Ok(output)
@@ -53,13 +63,50 @@ fn derive_autocomplete(target: &TargetType, groups: &[CommandGroup]) -> TokenStr
     let ident = target.ident();
     let named_lifetime = target.named_lifetime();
 
+    let mut prefixes = Vec::new();
     let groups = groups
         .iter()
         .filter(|group| !group.hidden)
-        .map(|group| {
+        .filter_map(|group| {
             let ty = &group.field_type;
-            quote! {
-                <#ty as _cli::service::Autocomplete>::autocomplete(request.clone(), autocompletion);
+            match &group.prefix {
+                // groups with same prefix are completed only once
+                Some(prefix) if group.enabled.is_none() && prefixes.contains(&prefix) => None,
+                Some(prefix) => {
+                    if group.enabled.is_none() {
+                        prefixes.push(prefix);
+                    }
+                    Some(when_enabled(
+                        group,
+                        quote! {
+                            match &request {
+                                _cli::autocomplete::Request::CommandName(name) => {
+                                    if let Some(autocompleted) = #prefix.strip_prefix(*name) {
+                                        autocompletion.merge_autocompletion(autocompleted);
+                                    }
+                                }
+                                // first argument after prefix is a name of command from the group
+                                _cli::autocomplete::Request::ArgValue {
+                                    command,
+                                    arg: 0,
+                                    partial,
+                                } if *command == #prefix => {
+                                    <#ty as _cli::service::Autocomplete>::autocomplete(
+                                        _cli::autocomplete::Request::CommandName(partial),
+                                        autocompletion,
+                                    );
+                                }
+                                _ => {}
+                            }
+                        },
+                    ))
+                }
+                None => Some(when_enabled(
+                    group,
+                    quote! {
+                        <#ty as _cli::service::Autocomplete>::autocomplete(request.clone(), autocompletion);
+                    },
+                )),
             }
         })
         .collect::<Vec<_>>();
@@ -98,14 +145,21 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
         .enumerate()
         .map(|(i, group)| {
             let ty = &group.field_type;
-            if i > 0 {
+            let count = if let Some(enabled) = &group.enabled {
                 quote! {
-                    + <#ty as _cli::service::Help>::command_count()
+                    (if #enabled() { <#ty as _cli::service::Help>::command_count() } else { 0 })
                 }
             } else {
                 quote! {
                     <#ty as _cli::service::Help>::command_count()
                 }
+            };
+            if i > 0 {
+                quote! {
+                    + #count
+                }
+            } else {
+                count
             }
         })
         .collect::<Vec<_>>();
@@ -116,14 +170,52 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
         .enumerate()
         .map(|(i, group)| {
             let ty = &group.field_type;
-            if i > 0 {
+            let help = if let Some(prefix) = &group.prefix {
+                let list_prefix = format!("{} ", prefix);
                 quote! {
-                    .or_else(|_| <#ty as _cli::service::Help>::command_help(parent, command.clone(), writer))
+                    (if command.name() == #prefix {
+                        match command.subcommand() {
+                            Some(command) => {
+                                let mut parent = |writer: &mut _cli::writer::Writer<'_, W, E>| {
+                                    parent(writer)?;
+                                    writer.write_str(#list_prefix)?;
+                                    Ok(())
+                                };
+                                <#ty as _cli::service::Help>::command_help(&mut parent, command, writer)
+                            }
+                            None => {
+                                let prefix = writer.set_list_prefix(#list_prefix);
+                                let result = <#ty as _cli::service::Help>::list_commands(writer);
+                                writer.set_list_prefix(prefix);
+                                result.map_err(_cli::service::HelpError::from)
+                            }
+                        }
+                    } else {
+                        Err(_cli::service::HelpError::UnknownCommand)
+                    })
                 }
             } else {
                 quote! {
                     <#ty as _cli::service::Help>::command_help(parent, command.clone(), writer)
                 }
+            };
+            let help = if let Some(enabled) = &group.enabled {
+                quote! {
+                    (if #enabled() {
+                        #help
+                    } else {
+                        Err(_cli::service::HelpError::UnknownCommand)
+                    })
+                }
+            } else {
+                help
+            };
+            if i > 0 {
+                quote! {
+                    .or_else(|_| #help)
+                }
+            } else {
+                help
             }
         })
         .collect::<Vec<_>>();
@@ -133,15 +225,31 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
         .filter(|group| !group.hidden)
         .map(|group| {
             let ty = &group.field_type;
-            quote! {
-                if <#ty as _cli::service::Help>::command_count() > 0 {
-                    if has_output {
-                        writer.writeln_str("")?;
-                    }
+            let list = if let Some(prefix) = &group.prefix {
+                let list_prefix = format!("{} ", prefix);
+                quote! {
+                    let prefix = writer.set_list_prefix(#list_prefix);
+                    let result = <#ty as _cli::service::Help>::list_commands(writer);
+                    writer.set_list_prefix(prefix);
+                    result?;
+                }
+            } else {
+                quote! {
                     <#ty as _cli::service::Help>::list_commands(writer)?;
-                    has_output = true;
                 }
-            }
+            };
+            when_enabled(
+                group,
+                quote! {
+                    if <#ty as _cli::service::Help>::command_count() > 0 {
+                        if has_output {
+                            writer.writeln_str("")?;
+                        }
+                        #list
+                        has_output = true;
+                    }
+                },
+            )
         })
         .collect::<Vec<_>>();
 
@@ -152,7 +260,7 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
                 #(#command_counts)*
             }
 
-            fn list_commands<W: _io::Write<Error = E>, E: _io::Error>(
+            fn list_commands<W: _io::Write<Error = E> + ?Sized, E: _io::Error>(
                 writer: &mut _cli::writer::Writer<'_, W, E>,
             ) -> Result<(), E> {
                 let mut has_output = false;
@@ -161,7 +269,7 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
             }
 
             fn command_help<
-                W: _io::Write<Error = E>,
+                W: _io::Write<Error = E> + ?Sized,
                 E: _io::Error,
                 F: FnMut(&mut _cli::writer::Writer<'_, W, E>) -> Result<(), E>,
             >(
@@ -187,24 +295,139 @@ fn derive_help(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
     }
 }
 
-fn derive_from_raw(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
+#[cfg(feature = "metadata")]
+fn derive_metadata(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
     let ident = target.ident();
     let named_lifetime = target.named_lifetime();
 
     let groups = groups
         .iter()
+        .filter(|group| !group.hidden)
         .map(|group| {
-            let ident = &group.ident;
             let ty = &group.field_type;
-            quote! {
-                match <#ty as _cli::service::FromRaw>::parse(raw.clone()) {
-                    Ok(cmd) => {
-                        return Ok(Self:: #ident (cmd));
+            let extend = if let Some(prefix) = &group.prefix {
+                // groups with same prefix are merged into single command
+                quote! {
+                    let subcommands = <#ty as _cli::metadata::Metadata>::commands();
+                    match commands.iter_mut().find(|command| command.name == #prefix) {
+                        Some(command) => command.subcommands.extend(subcommands),
+                        None => commands.push(_cli::metadata::CommandInfo {
+                            name: #prefix,
+                            about: None,
+                            help: None,
+                            args: _cli::__private::Vec::new(),
+                            subcommands,
+                        }),
                     }
-                    Err(_cli::service::ParseError::UnknownCommand) => {}
-                    Err(err) => return Err(err),
                 }
+            } else {
+                quote! {
+                    commands.extend(<#ty as _cli::metadata::Metadata>::commands());
+                }
+            };
+            when_enabled(group, extend)
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #named_lifetime _cli::metadata::Metadata for #ident #named_lifetime {
+            fn commands() -> _cli::__private::Vec<_cli::metadata::CommandInfo> {
+                let mut commands = _cli::__private::Vec::new();
+                #(#groups)*
+                commands
+            }
+        }
+    }
+}
+
+#[allow(unused_variables)]
+#[cfg(not(feature = "metadata"))]
+fn derive_metadata(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
+    quote! {}
+}
+
+/// Wraps code, so it's executed only when group is enabled at runtime
+fn when_enabled(group: &CommandGroup, code: TokenStream) -> TokenStream {
+    if let Some(enabled) = &group.enabled {
+        quote! {
+            if #enabled() {
+                #code
+            }
+        }
+    } else {
+        code
+    }
+}
+
+/// Creates expression, that processes raw command with fallback group
+/// (or returns error when fallback group is disabled)
+fn fallback(group: &CommandGroup, process: TokenStream) -> TokenStream {
+    if let Some(enabled) = &group.enabled {
+        quote! {
+            if #enabled() {
+                #process
+            } else {
+                Err(_cli::service::ParseError::UnknownCommand.into())
+            }
+        }
+    } else {
+        process
+    }
+}
+
+/// Wraps processing of raw command, so it's done only when command
+/// starts with group prefix (and prefix is removed from processed command)
+/// and group is enabled
+fn with_prefix(group: &CommandGroup, process: TokenStream) -> TokenStream {
+    let ty = &group.field_type;
+    let process = if let Some(prefix) = &group.prefix {
+        quote! {
+            if raw.name() == #prefix {
+                let raw = raw.subcommand().ok_or(
+                    _cli::service::ParseError::MissingSubcommand {
+                        expected: <#ty as _cli::service::FromRaw>::NAMES,
+                    },
+                )?;
+                #process
             }
+        }
+    } else {
+        process
+    };
+    when_enabled(group, process)
+}
+
+fn derive_from_raw(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
+    let ident = target.ident();
+    let named_lifetime = target.named_lifetime();
+
+    let fallback = groups
+        .iter()
+        .find(|group| group.fallback)
+        .map(|group| {
+            let ident = &group.ident;
+            fallback(group, quote! { Ok(Self:: #ident (raw)) })
+        })
+        .unwrap_or(quote! { Err(_cli::service::ParseError::UnknownCommand) });
+
+    let groups = groups
+        .iter()
+        .filter(|group| !group.fallback)
+        .map(|group| {
+            let ident = &group.ident;
+            let ty = &group.field_type;
+            with_prefix(
+                group,
+                quote! {
+                    match <#ty as _cli::service::FromRaw>::parse(raw.clone()) {
+                        Ok(cmd) => {
+                            return Ok(Self:: #ident (cmd));
+                        }
+                        Err(_cli::service::ParseError::UnknownCommand) => {}
+                        Err(err) => return Err(err),
+                    }
+                },
+            )
         })
         .collect::<Vec<_>>();
 
@@ -213,7 +436,108 @@ fn derive_from_raw(target: &TargetType, groups: &[CommandGroup]) -> TokenStream
             fn parse(raw: _cli::command::RawCommand<'a>) -> Result<Self, _cli::service::ParseError<'a>> {
                 #(#groups)*
 
-                Err(_cli::service::ParseError::UnknownCommand)
+                #fallback
+            }
+        }
+    }
+}
+
+fn impl_group_processor(target: &TargetType, groups: &[CommandGroup]) -> TokenStream {
+    let ident = target.ident();
+    let named_lifetime = target.named_lifetime();
+
+    let fields = groups
+        .iter()
+        .map(|group| {
+            format_ident!(
+                "{}",
+                group
+                    .ident
+                    .to_string()
+                    .from_case(Case::Pascal)
+                    .to_case(Case::Snake)
+            )
+        })
+        .collect::<Vec<_>>();
+    let types = (0..groups.len())
+        .map(|i| format_ident!("P{}", i))
+        .collect::<Vec<_>>();
+
+    let fallback = groups
+        .iter()
+        .zip(fields.iter())
+        .find(|(group, _)| group.fallback)
+        .map(|(group, field)| fallback(group, quote! { self.#field.process(cli, raw) }))
+        .unwrap_or(quote! { Err(_cli::service::ParseError::UnknownCommand.into()) });
+
+    let route = groups
+        .iter()
+        .zip(fields.iter())
+        .filter(|(group, _)| !group.fallback)
+        .map(|(group, field)| {
+            let ty = &group.field_type;
+            with_prefix(
+                group,
+                quote! {
+                    match <#ty as _cli::service::FromRaw>::parse(raw.clone()) {
+                        Ok(_) => return self.#field.process(cli, raw),
+                        Err(_cli::service::ParseError::UnknownCommand) => {}
+                        Err(err) => return Err(err.into()),
+                    }
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #named_lifetime #ident #named_lifetime {
+            /// Creates processor, that routes commands of each group
+            /// to its own processor (given in the same order as group variants)
+            #[allow(clippy::too_many_arguments)]
+            fn group_processor<
+                W: _io::Write<Error = E>,
+                E: _io::Error,
+                #(#types: _cli::service::CommandProcessor<W, E>),*
+            >(
+                #(#fields: #types),*
+            ) -> impl _cli::service::CommandProcessor<W, E> {
+                struct GroupProcessor<#(#types),*> {
+                    #(#fields: #types),*
+                }
+
+                impl<
+                        W: _io::Write<Error = E>,
+                        E: _io::Error,
+                        #(#types: _cli::service::CommandProcessor<W, E>),*
+                    > _cli::service::CommandProcessor<W, E> for GroupProcessor<#(#types),*>
+                {
+                    fn process<'a>(
+                        &mut self,
+                        cli: &mut _cli::cli::CliHandle<'_, W, E>,
+                        raw: _cli::command::RawCommand<'a>,
+                    ) -> Result<(), _cli::service::ProcessError<'a, E>> {
+                        #(#route)*
+
+                        #fallback
+                    }
+
+                    fn on_event(
+                        &mut self,
+                        cli: &mut _cli::cli::CliHandle<'_, W, E>,
+                        event: _cli::cli::CliEvent<'_>,
+                    ) -> Result<(), E> {
+                        #(self.#fields.on_event(cli, event.clone())?;)*
+                        Ok(())
+                    }
+
+                    fn on_input(&mut self, input: _cli::input::Input<'_>) {
+                        #(self.#fields.on_input(input);)*
+                    }
+                }
+
+                GroupProcessor {
+                    #(#fields),*
+                }
             }
         }
     }
@@ -6,18 +6,21 @@ mod command;
 mod group;
 mod processor;
 mod utils;
+mod value_enum;
 
 #[proc_macro_derive(Command, attributes(command, arg))]
 pub fn derive_command(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input);
 
-    let output = match command::derive_command(input) {
+    let (items, output) = match command::derive_command(input) {
         Ok(output) => output,
         Err(e) => return e.write_errors().into(),
     };
 
     // wrap with anonymous scope
     quote! {
+        #items
+
         const _: () = {
             extern crate embedded_cli as _cli;
             use _cli::__private::io as _io;
@@ -48,3 +51,23 @@ pub fn derive_command_group(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+#[proc_macro_derive(ValueEnum, attributes(value))]
+pub fn derive_value_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+
+    let output = match value_enum::derive_value_enum(input) {
+        Ok(output) => output,
+        Err(e) => return e.write_errors().into(),
+    };
+
+    // wrap with anonymous scope
+    quote! {
+        const _: () = {
+            extern crate embedded_cli as _cli;
+
+            #output
+        };
+    }
+    .into()
+}
@@ -50,6 +50,95 @@ pub fn impl_processor(target: &TargetType) -> Result<TokenStream> {
                     _ph: core::marker::PhantomData,
                 }
             }
+
+            fn fallible_processor<
+                U: _cli::__private::ufmt::uDisplay,
+                W: _io::Write<Error = E>,
+                E: _io::Error,
+                F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, #ident #unnamed_lifetime) -> Result<(), _cli::service::CommandError<E, U>>,
+            >(
+                f: F,
+            ) -> impl _cli::service::CommandProcessor<W, E> {
+                struct Processor<
+                    U: _cli::__private::ufmt::uDisplay,
+                    W: _io::Write<Error = E>,
+                    E: _io::Error,
+                    F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, #ident #unnamed_lifetime) -> Result<(), _cli::service::CommandError<E, U>>,
+                > {
+                    f: F,
+                    _ph: core::marker::PhantomData<(U, W, E)>,
+                }
+
+                impl<
+                        U: _cli::__private::ufmt::uDisplay,
+                        W: _io::Write<Error = E>,
+                        E: _io::Error,
+                        F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, #ident #unnamed_lifetime) -> Result<(), _cli::service::CommandError<E, U>>,
+                    > _cli::service::CommandProcessor<W, E> for Processor<U, W, E, F>
+                {
+                    fn process<'a>(
+                        &mut self,
+                        cli: &mut _cli::cli::CliHandle<'_, W, E>,
+                        raw: _cli::command::RawCommand<'a>,
+                    ) -> Result<(), _cli::service::ProcessError<'a, E>> {
+                        let cmd = <#ident #unnamed_lifetime as _cli::service::FromRaw>::parse(raw)?;
+                        match (self.f)(cli, cmd) {
+                            Ok(()) => {}
+                            Err(_cli::service::CommandError::WriteError(err)) => return Err(err.into()),
+                            Err(_cli::service::CommandError::User(err)) => cli.writer().error(&err)?,
+                        }
+                        Ok(())
+                    }
+                }
+
+                Processor {
+                    f,
+                    _ph: core::marker::PhantomData,
+                }
+            }
+
+            fn stateful_processor<
+                S,
+                W: _io::Write<Error = E>,
+                E: _io::Error,
+                F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, &mut S, #ident #unnamed_lifetime) -> Result<(), E>,
+            >(
+                f: F,
+            ) -> impl _cli::service::StatefulProcessor<S, W, E> {
+                struct Processor<
+                    S,
+                    W: _io::Write<Error = E>,
+                    E: _io::Error,
+                    F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, &mut S, #ident #unnamed_lifetime) -> Result<(), E>,
+                > {
+                    f: F,
+                    _ph: core::marker::PhantomData<(S, W, E)>,
+                }
+
+                impl<
+                        S,
+                        W: _io::Write<Error = E>,
+                        E: _io::Error,
+                        F: FnMut(&mut _cli::cli::CliHandle<'_, W, E>, &mut S, #ident #unnamed_lifetime) -> Result<(), E>,
+                    > _cli::service::StatefulProcessor<S, W, E> for Processor<S, W, E, F>
+                {
+                    fn process<'a>(
+                        &mut self,
+                        cli: &mut _cli::cli::CliHandle<'_, W, E>,
+                        state: &mut S,
+                        raw: _cli::command::RawCommand<'a>,
+                    ) -> Result<(), _cli::service::ProcessError<'a, E>> {
+                        let cmd = <#ident #unnamed_lifetime as _cli::service::FromRaw>::parse(raw)?;
+                        (self.f)(cli, state, cmd)?;
+                        Ok(())
+                    }
+                }
+
+                Processor {
+                    f,
+                    _ph: core::marker::PhantomData,
+                }
+            }
         }
     };
 
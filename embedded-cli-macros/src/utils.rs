@@ -1,12 +1,12 @@
-use syn::{Generics, PathArguments, Type, TypePath};
+use syn::{Generics, Lifetime, PathArguments, Type, TypePath};
 
 use darling::{usage::GenericsExt, Error, Result};
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 
 pub struct TargetType {
-    has_lifetime: bool,
     ident: Ident,
+    lifetime: Option<Lifetime>,
 }
 
 impl TargetType {
@@ -33,20 +33,25 @@ impl TargetType {
             accum.finish()?;
         }
 
-        let has_lifetime = !generics.declared_lifetimes().is_empty();
+        let lifetime = generics
+            .lifetimes()
+            .next()
+            .map(|param| param.lifetime.clone());
 
-        Ok(Self {
-            has_lifetime,
-            ident,
-        })
+        Ok(Self { ident, lifetime })
     }
 
     pub fn ident(&self) -> &Ident {
         &self.ident
     }
 
+    /// Lifetime, as it is declared in target type
+    pub fn lifetime(&self) -> Option<&Lifetime> {
+        self.lifetime.as_ref()
+    }
+
     pub fn named_lifetime(&self) -> TokenStream {
-        if self.has_lifetime {
+        if self.lifetime.is_some() {
             quote! {
                 <'a>
             }
@@ -56,7 +61,7 @@ impl TargetType {
     }
 
     pub fn unnamed_lifetime(&self) -> TokenStream {
-        if self.has_lifetime {
+        if self.lifetime.is_some() {
             quote! {
                 <'_>
             }
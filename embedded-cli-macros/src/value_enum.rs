@@ -0,0 +1,97 @@
+use convert_case::{Case, Casing};
+use darling::{Error, FromVariant, Result};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+#[derive(Debug, FromVariant, Default)]
+#[darling(default, attributes(value))]
+struct ValueAttrs {
+    name: Option<String>,
+}
+
+pub fn derive_value_enum(input: DeriveInput) -> Result<TokenStream> {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        ..
+    } = input;
+
+    let data = if let Data::Enum(data) = data {
+        data
+    } else {
+        return Err(Error::custom("ValueEnum can be derived only for an enum").with_span(&ident));
+    };
+
+    if !generics.params.is_empty() {
+        return Err(Error::custom("ValueEnum can't be generic").with_span(&generics));
+    }
+
+    let mut errors = Error::accumulator();
+    let variants =
+        data.variants
+            .iter()
+            .filter_map(|variant| {
+                errors.handle_in(|| {
+                    if !matches!(variant.fields, Fields::Unit) {
+                        return Err(Error::custom("ValueEnum supports only unit variants")
+                            .with_span(variant));
+                    }
+                    let attrs = ValueAttrs::from_variant(variant)?;
+                    let name = attrs.name.unwrap_or_else(|| {
+                        variant
+                            .ident
+                            .to_string()
+                            .from_case(Case::Camel)
+                            .to_case(Case::Kebab)
+                    });
+                    Ok((variant.ident.clone(), name))
+                })
+            })
+            .collect::<Vec<_>>();
+    errors.finish()?;
+
+    let names = variants.iter().map(|(_, name)| name).collect::<Vec<_>>();
+    let expected = format!(
+        "one of: {}",
+        names
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let name_arms = variants.iter().map(|(variant, name)| {
+        quote! { Self::#variant => #name, }
+    });
+    let parse_arms = variants.iter().map(|(variant, name)| {
+        quote! { #name => Ok(Self::#variant), }
+    });
+
+    let output = quote! {
+        impl _cli::arguments::ValueEnum for #ident {
+            const VALUES: &'static [&'static str] = &[#(#names),*];
+
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#name_arms)*
+                }
+            }
+        }
+
+        impl<'a> _cli::arguments::FromArgument<'a> for #ident {
+            fn from_arg(arg: &'a str) -> Result<Self, _cli::arguments::FromArgumentError<'a>> {
+                match arg {
+                    #(#parse_arms)*
+                    _ => Err(_cli::arguments::FromArgumentError {
+                        value: arg,
+                        expected: #expected,
+                    }),
+                }
+            }
+        }
+    };
+
+    Ok(output)
+}
@@ -1,5 +1,7 @@
+use core::{fmt::Debug, marker::PhantomData};
+
 use crate::{
-    token::{Tokens, TokensIter},
+    token::{TokenOffsets, Tokens, TokensIter},
     utils,
 };
 
@@ -44,6 +46,34 @@ impl<'a> ArgList<'a> {
     pub fn args(&self) -> ArgsIter<'a> {
         ArgsIter::new(self.tokens.iter())
     }
+
+    /// Returns iterator over arguments together with byte offset
+    /// of the token (inside given original line), that contains each argument.
+    ///
+    /// Line must be the one, from which these arguments were tokenized
+    /// (arguments are matched to the last tokens of the line)
+    pub fn args_with_offsets(&self, line: &'a str) -> ArgsWithOffsets<'a> {
+        let count = self.tokens.iter().count();
+        let skip = TokenOffsets::new(line).count().saturating_sub(count);
+        ArgsWithOffsets {
+            args: self.args(),
+            offset: 0,
+            offsets: TokenOffsets::new(line).skip(skip),
+        }
+    }
+
+    /// Returns iterator over arguments as they were typed
+    #[cfg(feature = "builtins")]
+    pub(crate) fn tokens(&self) -> TokensIter<'a> {
+        self.tokens.iter()
+    }
+}
+
+impl<'a> Default for ArgList<'a> {
+    /// Empty list without any arguments
+    fn default() -> Self {
+        Self::new(Tokens::from_raw("", true))
+    }
 }
 
 impl<'a> PartialEq for ArgList<'a> {
@@ -54,6 +84,8 @@ impl<'a> PartialEq for ArgList<'a> {
 
 #[derive(Debug)]
 pub struct ArgsIter<'a> {
+    negative_numbers: bool,
+
     values_only: bool,
 
     /// Short options (utf8 chars) that
@@ -66,12 +98,24 @@ pub struct ArgsIter<'a> {
 impl<'a> ArgsIter<'a> {
     fn new(tokens: TokensIter<'a>) -> Self {
         Self {
+            negative_numbers: false,
             values_only: false,
             leftover: "",
             tokens,
         }
     }
 
+    /// Treat tokens like `-5` as values instead of short options
+    pub fn negative_numbers(&mut self) {
+        self.negative_numbers = true;
+    }
+
+    /// Treat all following tokens as values (same as after `--`),
+    /// so tokens like `-5` are not parsed as options
+    pub fn values_only(&mut self) {
+        self.values_only = true;
+    }
+
     /// Converts whats left in this iterator back to `ArgList`
     ///
     /// If iterator was in the middle of iterating of collapsed
@@ -105,6 +149,8 @@ impl<'a> Iterator for ArgsIter<'a> {
                 } else {
                     Arg::LongOption(unsafe { raw.get_unchecked(2..) })
                 }
+            } else if self.negative_numbers && bytes[1].is_ascii_digit() {
+                Arg::Value(raw)
             } else {
                 let (opt, leftover) =
                     unsafe { utils::char_pop_front(raw.get_unchecked(1..)).unwrap_unchecked() };
@@ -120,6 +166,28 @@ impl<'a> Iterator for ArgsIter<'a> {
     }
 }
 
+/// Iterator over arguments with byte offsets of their tokens
+#[derive(Debug)]
+pub struct ArgsWithOffsets<'a> {
+    args: ArgsIter<'a>,
+    offset: usize,
+    offsets: core::iter::Skip<TokenOffsets<'a>>,
+}
+
+impl<'a> Iterator for ArgsWithOffsets<'a> {
+    type Item = (usize, Arg<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // collapsed short options share offset of their token
+        let new_token = self.args.leftover.is_empty();
+        let arg = self.args.next()?;
+        if new_token {
+            self.offset = self.offsets.next().unwrap_or(self.offset);
+        }
+        Some((self.offset, arg))
+    }
+}
+
 #[derive(Debug)]
 pub struct FromArgumentError<'a> {
     pub value: &'a str,
@@ -132,6 +200,16 @@ pub trait FromArgument<'a> {
         Self: Sized;
 }
 
+/// Enum with unit variants, that is parsed from name of one of them.
+/// Usually implemented with `#[derive(ValueEnum)]` (together with `FromArgument`)
+pub trait ValueEnum: Sized {
+    /// Names of all variants in order of declaration
+    const VALUES: &'static [&'static str];
+
+    /// Returns name of this variant
+    fn name(&self) -> &'static str;
+}
+
 impl<'a> FromArgument<'a> for &'a str {
     fn from_arg(arg: &'a str) -> Result<Self, FromArgumentError<'a>> {
         Ok(arg)
@@ -158,11 +236,212 @@ macro_rules! impl_arg_fromstr {
 
 impl_arg_fromstr! {char, bool, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64}
 
+/// Unsigned integer, that can be given with size suffix: SI (`k`, `M`, `G`)
+/// or binary (`Ki`, `Mi`, `Gi`). For example, `4k` is 4000 and `512Ki` is 524288.
+///
+/// Use it as a type of argument instead of plain integer:
+/// `Erase { len: Size<u32> }`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Size<T>(pub T);
+
+/// Parse value with optional size suffix
+fn parse_size(arg: &str) -> Option<u64> {
+    let digits = arg
+        .bytes()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(arg.len());
+    let (value, suffix) = arg.split_at(digits);
+    let multiplier: u64 = match suffix {
+        "" => 1,
+        "k" | "K" => 1_000,
+        "M" => 1_000_000,
+        "G" => 1_000_000_000,
+        "Ki" => 1 << 10,
+        "Mi" => 1 << 20,
+        "Gi" => 1 << 30,
+        _ => return None,
+    };
+    value.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+macro_rules! impl_arg_size {
+    ($($id:ident),+) => (
+        $(
+            impl<'a> FromArgument<'a> for Size<$id> {
+                fn from_arg(arg: &'a str) -> Result<Self, FromArgumentError<'a>> {
+                    parse_size(arg)
+                        .and_then(|value| $id::try_from(value).ok())
+                        .map(Size)
+                        .ok_or(FromArgumentError {
+                            value: arg,
+                            expected: concat!(stringify!($id), " size"),
+                        })
+                }
+            }
+        )+
+    )
+}
+
+impl_arg_size! {u8, u16, u32, u64, usize}
+
+/// All values of option, that can be given multiple times (`#[arg(append)]`).
+///
+/// Values are not stored anywhere: they are found in arguments of the command
+/// and parsed again on each iteration. All of them are already validated
+/// when command is parsed.
+pub struct Values<'a, T> {
+    args: ArgList<'a>,
+    len: usize,
+    long: Option<&'static str>,
+    short: Option<char>,
+    _ph: PhantomData<T>,
+}
+
+impl<'a, T> Values<'a, T> {
+    /// Used by generated code with number of values,
+    /// found while command was parsed
+    #[doc(hidden)]
+    pub fn new(
+        args: ArgList<'a>,
+        short: Option<char>,
+        long: Option<&'static str>,
+        len: usize,
+    ) -> Self {
+        Self {
+            args,
+            len,
+            long,
+            short,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Returns how many times option was given
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<'a, T: FromArgument<'a>> Values<'a, T> {
+    pub fn iter(&self) -> ValuesIter<'a, T> {
+        ValuesIter {
+            args: self.args.args(),
+            expect_value: false,
+            left: self.len,
+            long: self.long,
+            short: self.short,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Clone for Values<'a, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.args.clone(), self.short, self.long, self.len)
+    }
+}
+
+impl<'a, T: FromArgument<'a> + Debug> Debug for Values<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: FromArgument<'a> + PartialEq> PartialEq for Values<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T: FromArgument<'a>> IntoIterator for &Values<'a, T> {
+    type Item = T;
+    type IntoIter = ValuesIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: FromArgument<'a>> IntoIterator for Values<'a, T> {
+    type Item = T;
+    type IntoIter = ValuesIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over values of option, that can be given multiple times
+#[derive(Debug)]
+pub struct ValuesIter<'a, T> {
+    args: ArgsIter<'a>,
+    /// Previous argument was name of the option
+    expect_value: bool,
+    left: usize,
+    long: Option<&'static str>,
+    short: Option<char>,
+    _ph: PhantomData<T>,
+}
+
+impl<'a, T: FromArgument<'a>> Iterator for ValuesIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // arguments are matched the same way generated parser does
+        while self.left > 0 {
+            match self.args.next()? {
+                Arg::LongOption(name) if Some(name) == self.long => self.expect_value = true,
+                Arg::ShortOption(name) if Some(name) == self.short => self.expect_value = true,
+                Arg::Value(value) if self.expect_value => {
+                    self.expect_value = false;
+                    self.left -= 1;
+                    if let Ok(value) = T::from_arg(value) {
+                        return Some(value);
+                    }
+                }
+                Arg::Value(_) | Arg::DoubleDash => {}
+                Arg::LongOption(_) | Arg::ShortOption(_) => self.expect_value = false,
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
 
-    use crate::{arguments::ArgList, token::Tokens};
+    use crate::{
+        arguments::{ArgList, FromArgument, Size},
+        token::Tokens,
+    };
+
+    #[test]
+    fn offsets() {
+        let line = r#"cmd  -vs "some file" --level=3 -- -x"#;
+        let mut input = line.as_bytes().to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+        let tokens = Tokens::new(input);
+        let mut tokens = tokens.iter();
+        tokens.next();
+        let args = ArgList::new(tokens.into_tokens());
+
+        assert_eq!(
+            args.args_with_offsets(line).collect::<std::vec::Vec<_>>(),
+            &[
+                (5, Arg::ShortOption('v')),
+                (5, Arg::ShortOption('s')),
+                (9, Arg::Value("some file")),
+                (21, Arg::LongOption("level=3")),
+                (31, Arg::DoubleDash),
+                (34, Arg::Value("-x")),
+            ]
+        );
+    }
 
     use super::Arg;
 
@@ -207,6 +486,40 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn negative_numbers() {
+        let mut input = b"-5 -v -12x".to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+        let args = ArgList::new(Tokens::new(input));
+        let mut iter = args.args();
+        iter.negative_numbers();
+
+        assert_eq!(iter.next(), Some(Arg::Value("-5")));
+        assert_eq!(iter.next(), Some(Arg::ShortOption('v')));
+        iter.values_only();
+        assert_eq!(iter.next(), Some(Arg::Value("-12x")));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[rstest]
+    #[case("0", Some(0))]
+    #[case("123", Some(123))]
+    #[case("4k", Some(4_000))]
+    #[case("4K", Some(4_000))]
+    #[case("1M", Some(1_000_000))]
+    #[case("2G", Some(2_000_000_000))]
+    #[case("512Ki", Some(524_288))]
+    #[case("1Mi", Some(1_048_576))]
+    #[case("1Gi", Some(1_073_741_824))]
+    #[case("5Gi", None)]
+    #[case("k", None)]
+    #[case("4kb", None)]
+    #[case("-4k", None)]
+    #[case("4 k", None)]
+    fn size_suffix(#[case] arg: &str, #[case] expected: Option<u32>) {
+        assert_eq!(Size::<u32>::from_arg(arg).ok().map(|size| size.0), expected);
+    }
+
     #[test]
     fn test_eq() {
         let mut input = b"arg1 arg2".to_vec();
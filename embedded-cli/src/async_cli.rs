@@ -0,0 +1,415 @@
+//! Cli, that writes its output to async writer (requires `async` feature).
+//!
+//! Input is still processed synchronously, but all output of each
+//! processed byte is collected into output buffer and then written
+//! to `embedded_io_async::Write`, so executor is not blocked by slow writer.
+//!
+//! Input can be read from `embedded_io_async::Read` with `AsyncCli::run`
+//! (for example, from reader of `embassy_sync::pipe::Pipe`, that is filled by USB task).
+//!
+//! Commands can also be processed by `AsyncCommandProcessor`, that can `.await`
+//! (for example, on peripheral) while holding `CliHandle`.
+
+use core::fmt::Debug;
+
+use embedded_io::{ErrorKind, ErrorType, Write};
+
+use crate::{
+    buffer::Buffer,
+    builder::CliBuilder,
+    cli::{Cli, CliError, CliEvent, CliHandle},
+    command::RawCommand,
+    input::Input,
+    service::{Autocomplete, CommandProcessor, Help, ProcessError, StatefulProcessor},
+    writer::Writer,
+};
+
+/// Output of single processed byte (or of single `write` call)
+/// didn't fit into output buffer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OutputOverflow;
+
+impl embedded_io::Error for OutputOverflow {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::OutOfMemory
+    }
+}
+
+/// Error of async cli
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AsyncError<E> {
+    /// Cli was accessed while it's processing input (see `CliError::Busy`)
+    Busy,
+
+    /// Output didn't fit into output buffer.
+    /// Output, that fit, is still written
+    OutputOverflow,
+
+    /// Error of async writer
+    Write(E),
+}
+
+impl<E> From<OutputOverflow> for AsyncError<E> {
+    fn from(_: OutputOverflow) -> Self {
+        Self::OutputOverflow
+    }
+}
+
+impl<E> From<CliError<OutputOverflow>> for AsyncError<E> {
+    fn from(error: CliError<OutputOverflow>) -> Self {
+        match error {
+            CliError::Busy => Self::Busy,
+            CliError::Write(OutputOverflow) => Self::OutputOverflow,
+        }
+    }
+}
+
+/// Error of `AsyncCli::run`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RunError<R, W> {
+    /// Cli was accessed while it's processing input (see `CliError::Busy`)
+    Busy,
+
+    /// Output didn't fit into output buffer (see `AsyncError::OutputOverflow`)
+    OutputOverflow,
+
+    /// Error of async reader
+    Read(R),
+
+    /// Error of async writer
+    Write(W),
+}
+
+impl<R, W> From<AsyncError<W>> for RunError<R, W> {
+    fn from(error: AsyncError<W>) -> Self {
+        match error {
+            AsyncError::Busy => Self::Busy,
+            AsyncError::OutputOverflow => Self::OutputOverflow,
+            AsyncError::Write(err) => Self::Write(err),
+        }
+    }
+}
+
+/// Command processor, that can `.await` while command is processed.
+///
+/// Input is processed synchronously, so events and input callbacks
+/// are still synchronous. Paging with `CliHandle::pager` is not supported.
+// executors of embedded targets are usually single threaded,
+// so futures are not required to be `Send`
+#[allow(async_fn_in_trait)]
+pub trait AsyncCommandProcessor<W: Write<Error = E>, E: embedded_io::Error> {
+    async fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>>;
+
+    /// Same as `CommandProcessor::on_event`
+    #[allow(unused_variables)]
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Same as `CommandProcessor::on_input`
+    #[allow(unused_variables)]
+    fn on_input(&mut self, input: Input<'_>) {}
+}
+
+/// Synchronous processor, that keeps commands in cli,
+/// so they are processed later by async processor
+pub(crate) struct Deferring<'p, P> {
+    processor: &'p mut P,
+}
+
+impl<'p, P> Deferring<'p, P> {
+    pub(crate) fn new(processor: &'p mut P) -> Self {
+        Self { processor }
+    }
+}
+
+impl<'p, W, E, P> CommandProcessor<W, E> for Deferring<'p, P>
+where
+    W: Write<Error = E>,
+    E: embedded_io::Error,
+    P: AsyncCommandProcessor<W, E>,
+{
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        _: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        cli.defer();
+        Ok(())
+    }
+
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        self.processor.on_event(cli, event)
+    }
+
+    fn on_input(&mut self, input: Input<'_>) {
+        self.processor.on_input(input)
+    }
+}
+
+/// Writer, that collects output in a buffer until it is written
+/// to async writer
+pub struct BufferedOutput<B: Buffer> {
+    buffer: B,
+    len: usize,
+}
+
+impl<B: Buffer> BufferedOutput<B> {
+    fn new(buffer: B) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.buffer.as_slice()[..self.len]
+    }
+}
+
+impl<B: Buffer> Debug for BufferedOutput<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BufferedOutput")
+            .field("buffer", &self.as_slice())
+            .finish()
+    }
+}
+
+impl<B: Buffer> ErrorType for BufferedOutput<B> {
+    type Error = OutputOverflow;
+}
+
+impl<B: Buffer> Write for BufferedOutput<B> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let free = &mut self.buffer.as_slice_mut()[self.len..];
+        if free.is_empty() {
+            return Err(OutputOverflow);
+        }
+        let len = buf.len().min(free.len());
+        free[..len].copy_from_slice(&buf[..len]);
+        self.len += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // output is written to async writer by AsyncCli
+        Ok(())
+    }
+}
+
+/// Cli, that writes its output to async writer.
+///
+/// Command processors receive `CliHandle<'_, BufferedOutput<OutputBuffer>, OutputOverflow>`,
+/// so all output of single command must fit into output buffer.
+/// Otherwise processing fails the same way as it does on error of synchronous writer.
+pub struct AsyncCli<W, CommandBuffer, HistoryBuffer, OutputBuffer>
+where
+    W: embedded_io_async::Write,
+    CommandBuffer: Buffer,
+    HistoryBuffer: Buffer,
+    OutputBuffer: Buffer,
+{
+    cli: Cli<BufferedOutput<OutputBuffer>, OutputOverflow, CommandBuffer, HistoryBuffer>,
+    writer: W,
+}
+
+impl<W, CommandBuffer, HistoryBuffer, OutputBuffer> Debug
+    for AsyncCli<W, CommandBuffer, HistoryBuffer, OutputBuffer>
+where
+    W: embedded_io_async::Write,
+    CommandBuffer: Buffer,
+    HistoryBuffer: Buffer,
+    OutputBuffer: Buffer,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AsyncCli").field("cli", &self.cli).finish()
+    }
+}
+
+impl<W, CommandBuffer, HistoryBuffer, OutputBuffer>
+    AsyncCli<W, CommandBuffer, HistoryBuffer, OutputBuffer>
+where
+    W: embedded_io_async::Write,
+    CommandBuffer: Buffer,
+    HistoryBuffer: Buffer,
+    OutputBuffer: Buffer,
+{
+    pub(crate) async fn from_builder<BW: Write<Error = BE>, BE: embedded_io::Error>(
+        builder: CliBuilder<BW, BE, CommandBuffer, HistoryBuffer>,
+        writer: W,
+        output_buffer: OutputBuffer,
+    ) -> Result<Self, AsyncError<W::Error>> {
+        let result = builder
+            .writer(BufferedOutput::new(output_buffer))
+            .build()
+            .map(|cli| Self { cli, writer });
+        match result {
+            Ok(mut cli) => {
+                cli.flush().await?;
+                Ok(cli)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns inner cli. Output of its methods is kept in output buffer
+    /// until `flush` is called
+    pub fn cli(
+        &mut self,
+    ) -> &mut Cli<BufferedOutput<OutputBuffer>, OutputOverflow, CommandBuffer, HistoryBuffer> {
+        &mut self.cli
+    }
+
+    /// Write all buffered output to async writer
+    pub async fn flush(&mut self) -> Result<(), AsyncError<W::Error>> {
+        let output = self.cli.output();
+        if output.len > 0 {
+            self.writer
+                .write_all(output.as_slice())
+                .await
+                .map_err(AsyncError::Write)?;
+            output.len = 0;
+            self.writer.flush().await.map_err(AsyncError::Write)?;
+        }
+        Ok(())
+    }
+
+    /// Same as `Cli::process_byte`, but output is written to async writer
+    pub async fn process_byte<C, P>(
+        &mut self,
+        b: u8,
+        processor: &mut P,
+    ) -> Result<(), AsyncError<W::Error>>
+    where
+        C: Autocomplete + Help,
+        P: CommandProcessor<BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        let result = self.cli.process_byte::<C, _>(b, processor);
+        // output that fit into buffer is written even on overflow
+        self.flush().await?;
+        Ok(result?)
+    }
+
+    /// Same as `Cli::process_byte_with_state`, but output is written to async writer
+    pub async fn process_byte_with_state<C, S, P>(
+        &mut self,
+        b: u8,
+        state: &mut S,
+        processor: &mut P,
+    ) -> Result<(), AsyncError<W::Error>>
+    where
+        C: Autocomplete + Help,
+        P: StatefulProcessor<S, BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        let result = self
+            .cli
+            .process_byte_with_state::<C, _, _>(b, state, processor);
+        self.flush().await?;
+        Ok(result?)
+    }
+
+    /// Process all given bytes. Output is written after each byte,
+    /// so output buffer only needs to fit output of single byte
+    pub async fn process_slice<C, P>(
+        &mut self,
+        bytes: &[u8],
+        processor: &mut P,
+    ) -> Result<(), AsyncError<W::Error>>
+    where
+        C: Autocomplete + Help,
+        P: CommandProcessor<BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        for &b in bytes {
+            self.process_byte::<C, _>(b, processor).await?;
+        }
+        Ok(())
+    }
+
+    /// Same as `process_byte`, but command is processed by async processor.
+    ///
+    /// Echo of entered command is written to async writer before processor is called.
+    /// Output of processor is collected into output buffer and is written together
+    /// with new prompt after returned future is resolved.
+    /// Returned future must be polled to completion
+    /// (cli can't process input after command processing was cancelled)
+    pub async fn process_byte_async<C, P>(
+        &mut self,
+        b: u8,
+        processor: &mut P,
+    ) -> Result<(), AsyncError<W::Error>>
+    where
+        C: Autocomplete + Help,
+        P: AsyncCommandProcessor<BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        let result = self
+            .cli
+            .process_byte::<C, _>(b, &mut Deferring::new(processor));
+        self.flush().await?;
+        // command is processed even if its echo didn't fit, so it's not left in cli
+        let deferred = self.cli.process_deferred::<C, _>(processor).await;
+        self.flush().await?;
+        result?;
+        Ok(deferred?)
+    }
+
+    /// Same as `process_slice`, but commands are processed by async processor
+    /// (see `process_byte_async`)
+    pub async fn process_slice_async<C, P>(
+        &mut self,
+        bytes: &[u8],
+        processor: &mut P,
+    ) -> Result<(), AsyncError<W::Error>>
+    where
+        C: Autocomplete + Help,
+        P: AsyncCommandProcessor<BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        for &b in bytes {
+            self.process_byte_async::<C, _>(b, processor).await?;
+        }
+        Ok(())
+    }
+
+    /// Read input from async reader and process it until reader reaches end of input
+    /// or error occurs.
+    /// Readers, that never end (like pipes), make this future run until error:
+    ///
+    /// ```ignore
+    /// let mut cli = CliBuilder::default().build_async(pipe_tx, [0; 256]).await?;
+    /// cli.run::<Base, _, _>(pipe_rx, &mut Base::processor(|cli, command| Ok(()))).await?;
+    /// ```
+    pub async fn run<C, R, P>(
+        &mut self,
+        mut reader: R,
+        processor: &mut P,
+    ) -> Result<(), RunError<R::Error, W::Error>>
+    where
+        C: Autocomplete + Help,
+        R: embedded_io_async::Read,
+        P: CommandProcessor<BufferedOutput<OutputBuffer>, OutputOverflow>,
+    {
+        let mut buf = [0; 16];
+        loop {
+            let len = reader.read(&mut buf).await.map_err(RunError::Read)?;
+            if len == 0 {
+                return Ok(());
+            }
+            self.process_slice::<C, _>(&buf[..len], processor).await?;
+        }
+    }
+
+    /// Same as `Cli::write`, but output is written to async writer
+    pub async fn write(
+        &mut self,
+        f: impl FnOnce(
+            &mut Writer<'_, BufferedOutput<OutputBuffer>, OutputOverflow>,
+        ) -> Result<(), OutputOverflow>,
+    ) -> Result<(), AsyncError<W::Error>> {
+        let result = self.cli.write(f);
+        self.flush().await?;
+        Ok(result?)
+    }
+}
@@ -1,13 +1,31 @@
-use crate::utils;
+use crate::{token::Tokens, utils};
 
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub enum Request<'a> {
+    /// Request to autocomplete value of command argument
+    ArgValue {
+        /// Name of the command
+        command: &'a str,
+
+        /// Index of the argument (among tokens after command name,
+        /// quoted value is a single token)
+        arg: usize,
+
+        /// Already typed part of the value
+        partial: &'a str,
+    },
+
     /// Request to autocomplete given text to command name
     CommandName(&'a str),
 }
 
 impl<'a> Request<'a> {
+    /// Create request from input, that is split into tokens the same way
+    /// as entered command, so quoted value with spaces is a single argument.
+    ///
+    /// Returns `None` if there is nothing to complete: input is empty,
+    /// ends with closing quote or contains escaped chars inside quotes
     pub fn from_input(input: &'a str) -> Option<Self> {
         let input = utils::trim_start(input);
 
@@ -15,12 +33,36 @@ impl<'a> Request<'a> {
             return None;
         }
 
-        // if no space given, then only command name is entered so we complete it
-        if !input.contains(' ') {
-            Some(Request::CommandName(input))
-        } else {
-            None
+        let mut tokens = Tokens::from_line(input)?.iter();
+        let command = tokens.next()?;
+
+        // trailing space inside quotes is a part of last token
+        let new_token = input.ends_with(' ') && !Tokens::is_quoted(input);
+        let args = tokens.clone().count();
+        if args == 0 && !new_token {
+            // if no args given, then only command name is entered so we complete it
+            return Self::ends_input(command, input).then_some(Request::CommandName(command));
         }
+        let (arg, partial) = if new_token {
+            (args, "")
+        } else {
+            let partial = tokens.last().unwrap_or("");
+            if !Self::ends_input(partial, input) {
+                return None;
+            }
+            (args - 1, partial)
+        };
+        Some(Request::ArgValue {
+            command,
+            arg,
+            partial,
+        })
+    }
+
+    /// Whether given token (borrowed from input) ends at the end of input,
+    /// so autocompletion can be appended to it
+    fn ends_input(token: &str, input: &str) -> bool {
+        token.as_ptr() as usize + token.len() == input.as_ptr() as usize + input.len()
     }
 }
 
@@ -96,7 +138,57 @@ impl<'a> Autocompletion<'a> {
 mod tests {
     use rstest::rstest;
 
-    use crate::autocomplete::Autocompletion;
+    use crate::autocomplete::{Autocompletion, Request};
+
+    #[rstest]
+    #[case("set", None)]
+    #[case("set ", Some(0))]
+    #[case("set fi", Some(0))]
+    #[case("set -v  ", Some(1))]
+    #[case("set  -v fi", Some(1))]
+    #[case(r#"set "a b" fi"#, Some(1))]
+    #[case(r#"set "a b"  "#, Some(1))]
+    #[case(r#"set "a b" "c "#, Some(1))]
+    fn arg_value_request(#[case] input: &str, #[case] expected: Option<usize>) {
+        let request = Request::from_input(input).unwrap();
+
+        match request {
+            Request::ArgValue {
+                command,
+                arg,
+                partial,
+            } => {
+                assert_eq!(command, "set");
+                assert_eq!(Some(arg), expected);
+                assert!(input.ends_with(partial));
+            }
+            Request::CommandName(name) => {
+                assert_eq!(name, "set");
+                assert_eq!(expected, None);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(r#"set "a b" "c d "#, 1, "c d ")]
+    #[case(r#""set" -v"#, 0, "-v")]
+    fn quoted_arg_value_request(#[case] input: &str, #[case] arg: usize, #[case] partial: &str) {
+        let request = Request::from_input(input).unwrap();
+
+        assert!(matches!(
+            request,
+            Request::ArgValue { command: "set", arg: a, partial: p } if a == arg && p == partial
+        ));
+    }
+
+    #[rstest]
+    #[case("   ")]
+    #[case(r#""set""#)]
+    #[case(r#"set "a b""#)]
+    #[case(r#"set "a \"b"#)]
+    fn no_request(#[case] input: &str) {
+        assert!(Request::from_input(input).is_none());
+    }
 
     #[test]
     fn no_merge() {
@@ -2,16 +2,62 @@ use core::{convert::Infallible, fmt::Debug};
 
 use embedded_io::{Error, Write};
 
-use crate::{buffer::Buffer, cli::Cli, writer::EmptyWriter};
+#[cfg(feature = "async")]
+use crate::async_cli::{AsyncCli, AsyncError};
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::{
+    buffer::Buffer,
+    cli::{Auth, AutocompleteHook, Cli, ErrorHandler, TabInsert},
+    input::KeyMap,
+    prompt::Prompt,
+    writer::{EmptyWriter, Transcript},
+};
 
 pub const DEFAULT_CMD_LEN: usize = 40;
 pub const DEFAULT_HISTORY_LEN: usize = 100;
-pub const DEFAULT_PROMPT: &str = "$ ";
+pub const DEFAULT_PROMPT: Prompt = Prompt::new("$ ");
+
+/// Error returned by `CliBuilder::history_init`, when history can't be restored
+#[cfg(feature = "history")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HistoryInitError {
+    /// Bytes are not valid history
+    Invalid,
+    /// Bytes don't fit into history buffer
+    TooLong,
+}
 
 pub struct CliBuilder<W: Write<Error = E>, E: Error, CommandBuffer: Buffer, HistoryBuffer: Buffer> {
+    pub(crate) auth: Option<Auth>,
+    pub(crate) autocomplete_hook: Option<AutocompleteHook>,
+    pub(crate) banner: &'static str,
+    pub(crate) bell_on_error: bool,
+    pub(crate) colors: bool,
     pub(crate) command_buffer: CommandBuffer,
+    pub(crate) command_separator: bool,
+    pub(crate) degraded_prompt: Option<Prompt>,
+    #[cfg(feature = "builtins")]
+    pub(crate) delay: Option<fn(u32)>,
+    pub(crate) error_handler: Option<ErrorHandler>,
+    pub(crate) flow_control: bool,
+    pub(crate) help_command: bool,
+    pub(crate) help_on_error: bool,
+    pub(crate) help_short: Option<char>,
     pub(crate) history_buffer: HistoryBuffer,
-    pub(crate) prompt: &'static str,
+    pub(crate) history_len: usize,
+    pub(crate) history_valid_only: bool,
+    pub(crate) keymap: KeyMap,
+    pub(crate) machine_mode: bool,
+    pub(crate) max_tokens: Option<usize>,
+    pub(crate) parse_error_events: bool,
+    pub(crate) prompt: Prompt,
+    pub(crate) scroll_region: Option<u16>,
+    pub(crate) secret_mask: Option<char>,
+    pub(crate) tab_insert: TabInsert,
+    pub(crate) trace_commands: bool,
+    pub(crate) transcript: Option<fn(Transcript<'_>)>,
+    pub(crate) utf8_replacement: bool,
     pub(crate) writer: W,
 }
 
@@ -26,6 +72,7 @@ where
         f.debug_struct("CliBuilder")
             .field("command_buffer", &self.command_buffer.as_slice())
             .field("history_buffer", &self.history_buffer.as_slice())
+            .field("history_len", &self.history_len)
             .finish()
     }
 }
@@ -41,36 +88,382 @@ where
         Cli::from_builder(self)
     }
 
+    /// Build cli, that writes its output to async writer.
+    /// Output is collected in given buffer before it is written
+    /// (see `AsyncCli`). Writer, set in builder, is not used
+    #[cfg(feature = "async")]
+    pub async fn build_async<AW: embedded_io_async::Write, OutputBuffer: Buffer>(
+        self,
+        writer: AW,
+        output_buffer: OutputBuffer,
+    ) -> Result<AsyncCli<AW, CommandBuffer, HistoryBuffer, OutputBuffer>, AsyncError<AW::Error>>
+    {
+        AsyncCli::from_builder(self, writer, output_buffer).await
+    }
+
+    /// Require password before any command is accepted.
+    ///
+    /// Cli starts locked and shows given prompt instead of usual one.
+    /// Typed chars are hidden (see `secret_mask`) and are not passed to history,
+    /// autocompletion or transcript. Submitted line is passed to `check`,
+    /// cli is unlocked when it returns true.
+    /// Cli can be locked again with `Cli::lock` or `CliHandle::lock`
+    pub fn auth(self, prompt: &'static str, check: fn(&str) -> bool) -> Self {
+        CliBuilder {
+            auth: Some(Auth { check, prompt }),
+            ..self
+        }
+    }
+
+    /// Function, that is called with each autocompletion request
+    /// after generated autocompletion is done.
+    /// Can be used to complete argument values (`Request::ArgValue`),
+    /// for example file names or known identifiers.
+    /// Requires `autocomplete` feature
+    pub fn autocomplete_hook(self, hook: AutocompleteHook) -> Self {
+        CliBuilder {
+            autocomplete_hook: Some(hook),
+            ..self
+        }
+    }
+
+    /// Text, that is written before the first prompt
+    /// and again each time host is connected (see `Cli::on_connect`)
+    pub fn banner(self, banner: &'static str) -> Self {
+        CliBuilder { banner, ..self }
+    }
+
+    /// Write BEL (0x07) together with error messages,
+    /// so terminal beeps (or flashes) when command fails
+    pub fn bell_on_error(self, bell_on_error: bool) -> Self {
+        CliBuilder {
+            bell_on_error,
+            ..self
+        }
+    }
+
+    /// Enable coloring of output with ANSI escape sequences
+    /// (for example, severity tags written by `Writer`)
+    pub fn colors(self, colors: bool) -> Self {
+        CliBuilder { colors, ..self }
+    }
+
     pub fn command_buffer<B: Buffer>(
         self,
         command_buffer: B,
     ) -> CliBuilder<W, E, B, HistoryBuffer> {
         CliBuilder {
+            auth: self.auth,
+            autocomplete_hook: self.autocomplete_hook,
+            banner: self.banner,
+            bell_on_error: self.bell_on_error,
+            colors: self.colors,
             command_buffer,
+            command_separator: self.command_separator,
+            degraded_prompt: self.degraded_prompt,
+            #[cfg(feature = "builtins")]
+            delay: self.delay,
+            error_handler: self.error_handler,
+            flow_control: self.flow_control,
+            help_command: self.help_command,
+            help_on_error: self.help_on_error,
+            help_short: self.help_short,
             history_buffer: self.history_buffer,
+            history_len: self.history_len,
+            history_valid_only: self.history_valid_only,
+            keymap: self.keymap,
+            machine_mode: self.machine_mode,
+            max_tokens: self.max_tokens,
+            parse_error_events: self.parse_error_events,
             writer: self.writer,
             prompt: self.prompt,
+            scroll_region: self.scroll_region,
+            secret_mask: self.secret_mask,
+            tab_insert: self.tab_insert,
+            trace_commands: self.trace_commands,
+            transcript: self.transcript,
+            utf8_replacement: self.utf8_replacement,
+        }
+    }
+
+    /// Split input into multiple commands on unquoted `;`
+    /// (like `led set 5; status`), so they are processed one after another.
+    ///
+    /// Commands after one with paged output (or deferred to async processor)
+    /// are not processed
+    pub fn command_separator(self, command_separator: bool) -> Self {
+        CliBuilder {
+            command_separator,
+            ..self
         }
     }
 
+    /// Prompt, that is shown instead of normal one while cli is degraded
+    /// (for example, when some subsystem is offline).
+    /// Prompts are switched with `Cli::set_degraded`
+    pub fn degraded_prompt(self, prompt: impl Into<Prompt>) -> Self {
+        CliBuilder {
+            degraded_prompt: Some(prompt.into()),
+            ..self
+        }
+    }
+
+    /// Function, that blocks for given number of milliseconds.
+    /// Enables built-in `sleep <MS>` command
+    #[cfg(feature = "builtins")]
+    pub fn delay(self, delay: fn(u32)) -> Self {
+        CliBuilder {
+            delay: Some(delay),
+            ..self
+        }
+    }
+
+    /// Function, that writes parse errors instead of built-in messages,
+    /// so they can be localized or customized.
+    /// Nothing is written if function writes nothing
+    /// (line end is added automatically when needed)
+    pub fn error_handler(self, error_handler: ErrorHandler) -> Self {
+        CliBuilder {
+            error_handler: Some(error_handler),
+            ..self
+        }
+    }
+
+    /// Enable XON/XOFF software flow control.
+    ///
+    /// When enabled, XOFF (0x13) received from the remote side pauses output:
+    /// it's discarded until XON (0x11) is received, then prompt and current input
+    /// are written again (see `Cli::is_output_paused`)
+    pub fn flow_control(self, flow_control: bool) -> Self {
+        CliBuilder {
+            flow_control,
+            ..self
+        }
+    }
+
+    /// Handle `help` command by cli itself (enabled by default).
+    /// Disable it when application has its own `help` command,
+    /// so it is passed to processor as any other command.
+    /// `-h` and `--help` options are still handled by cli
+    pub fn help_command(self, help_command: bool) -> Self {
+        CliBuilder {
+            help_command,
+            ..self
+        }
+    }
+
+    /// Print help of the command after error message when command
+    /// fails to parse because of missing or unexpected arguments.
+    /// Requires `help` feature, otherwise only error is printed
+    pub fn help_on_error(self, help_on_error: bool) -> Self {
+        CliBuilder {
+            help_on_error,
+            ..self
+        }
+    }
+
+    /// Short option, that requests help of command (`-h` by default).
+    /// Set to other char or to `None` when commands need `-h` for their own options
+    /// (for example, `--host`). `--help` always requests help
+    pub fn help_short(self, help_short: Option<char>) -> Self {
+        CliBuilder { help_short, ..self }
+    }
+
+    /// Build cli without history, so no memory is used for it.
+    /// Up and Down keys are then ignored
+    /// (same as when `history` feature is disabled)
+    pub fn history_disabled(self) -> CliBuilder<W, E, CommandBuffer, [u8; 0]> {
+        self.history_buffer([])
+    }
+
+    /// Use given buffer for history.
+    ///
+    /// History, that was already restored with `history_init`, is copied
+    /// into new buffer. If it doesn't fit, history starts empty
+    /// (call `history_init` after this to know whether history was restored)
     pub fn history_buffer<B: Buffer>(
         self,
-        history_buffer: B,
+        mut history_buffer: B,
     ) -> CliBuilder<W, E, CommandBuffer, B> {
+        let history_len = self
+            .history_buffer
+            .as_slice()
+            .get(..self.history_len)
+            .zip(history_buffer.as_slice_mut().get_mut(..self.history_len))
+            .map_or(0, |(restored, buffer)| {
+                buffer.copy_from_slice(restored);
+                restored.len()
+            });
         CliBuilder {
+            auth: self.auth,
+            autocomplete_hook: self.autocomplete_hook,
+            banner: self.banner,
+            bell_on_error: self.bell_on_error,
+            colors: self.colors,
             command_buffer: self.command_buffer,
+            command_separator: self.command_separator,
+            degraded_prompt: self.degraded_prompt,
+            #[cfg(feature = "builtins")]
+            delay: self.delay,
+            error_handler: self.error_handler,
+            flow_control: self.flow_control,
+            help_command: self.help_command,
+            help_on_error: self.help_on_error,
+            help_short: self.help_short,
             history_buffer,
+            history_len,
+            history_valid_only: self.history_valid_only,
+            keymap: self.keymap,
+            machine_mode: self.machine_mode,
+            max_tokens: self.max_tokens,
+            parse_error_events: self.parse_error_events,
             writer: self.writer,
             prompt: self.prompt,
+            scroll_region: self.scroll_region,
+            secret_mask: self.secret_mask,
+            tab_insert: self.tab_insert,
+            trace_commands: self.trace_commands,
+            transcript: self.transcript,
+            utf8_replacement: self.utf8_replacement,
         }
     }
 
-    pub fn prompt(self, prompt: &'static str) -> Self {
+    /// Restore history from bytes, previously returned by `Cli::history_bytes`
+    /// (for example, stored in flash). Bytes are copied into current history buffer
+    /// (and later into the one given to `history_buffer`), so call it after
+    /// `history_buffer` when snapshot might not fit into default buffer.
+    ///
+    /// If bytes don't fit into buffer or are not valid history
+    /// (null terminated non-empty UTF-8 commands), error is returned
+    /// and history starts empty
+    #[cfg(feature = "history")]
+    pub fn history_init(&mut self, bytes: &[u8]) -> Result<(), HistoryInitError> {
+        self.history_len = 0;
+        if !History::<HistoryBuffer>::is_valid(bytes) {
+            return Err(HistoryInitError::Invalid);
+        }
+        let buffer = self
+            .history_buffer
+            .as_slice_mut()
+            .get_mut(..bytes.len())
+            .ok_or(HistoryInitError::TooLong)?;
+        buffer.copy_from_slice(bytes);
+        self.history_len = bytes.len();
+        Ok(())
+    }
+
+    /// Keep in history only commands, that were parsed successfully,
+    /// so typos don't take space in small history buffer
+    pub fn history_valid_only(self, history_valid_only: bool) -> Self {
         CliBuilder {
-            command_buffer: self.command_buffer,
-            history_buffer: self.history_buffer,
-            writer: self.writer,
-            prompt,
+            history_valid_only,
+            ..self
+        }
+    }
+
+    /// Key bindings, that are checked before built-in ones
+    /// (for example, to treat DEL as backspace or to add Ctrl+A/Ctrl+E).
+    /// See `KeyMap`
+    pub fn keymap(self, keymap: KeyMap) -> Self {
+        CliBuilder { keymap, ..self }
+    }
+
+    /// Write key-value pairs in machine-readable form
+    /// (see `Writer::kv`)
+    pub fn machine_mode(self, machine_mode: bool) -> Self {
+        CliBuilder {
+            machine_mode,
+            ..self
+        }
+    }
+
+    /// Reject input lines with more than given number of tokens
+    /// (command name, its subcommands, options and values) with an error
+    /// before command is parsed.
+    /// Protects small targets from spending too much time on pathological pasted input
+    pub fn max_tokens(self, max_tokens: usize) -> Self {
+        CliBuilder {
+            max_tokens: Some(max_tokens),
+            ..self
+        }
+    }
+
+    /// Pass parse errors to processor as `CliEvent::ParseError`
+    /// instead of writing them, so application can report them its own way
+    /// (for example, over a structured channel)
+    pub fn parse_error_events(self, parse_error_events: bool) -> Self {
+        CliBuilder {
+            parse_error_events,
+            ..self
+        }
+    }
+
+    /// Set prompt, that is shown before input.
+    /// Create prompt with `Prompt::new` in const context to validate it at compile time
+    pub fn prompt(self, prompt: impl Into<Prompt>) -> Self {
+        CliBuilder {
+            prompt: prompt.into(),
+            ..self
+        }
+    }
+
+    /// Keep prompt pinned to the bottom line of terminal with given number of rows.
+    ///
+    /// Scroll region (DECSTBM) is set to all other rows, so all output
+    /// (of commands or written with `Cli::write`) scrolls above the prompt
+    /// and prompt is never cleared. Terminal must have at least 2 rows
+    pub fn scroll_region(self, rows: u16) -> Self {
+        CliBuilder {
+            scroll_region: (rows >= 2).then_some(rows),
+            ..self
+        }
+    }
+
+    /// Char, that is echoed instead of each typed char of password
+    /// (`*` by default). With `None` nothing is echoed at all
+    pub fn secret_mask(self, secret_mask: Option<char>) -> Self {
+        CliBuilder {
+            secret_mask,
+            ..self
+        }
+    }
+
+    /// What Tab key inserts when it isn't used for autocompletion:
+    /// when `autocomplete` feature is disabled or when cursor is inside quoted argument.
+    /// Useful for commands that forward text payloads.
+    /// By default nothing is inserted
+    pub fn tab_insert(self, tab_insert: TabInsert) -> Self {
+        CliBuilder { tab_insert, ..self }
+    }
+
+    /// Print name and arguments of each command (as they were tokenized)
+    /// before passing command to processor.
+    /// Useful to diagnose quoting or option problems
+    pub fn trace_commands(self, trace_commands: bool) -> Self {
+        CliBuilder {
+            trace_commands,
+            ..self
+        }
+    }
+
+    /// Function, that receives every byte written by cli
+    /// and every byte passed to it as input.
+    /// Can be used to mirror session to another port
+    /// or to keep it in memory for post-mortem analysis
+    pub fn transcript(self, transcript: fn(Transcript<'_>)) -> Self {
+        CliBuilder {
+            transcript: Some(transcript),
+            ..self
+        }
+    }
+
+    /// Replace invalid utf-8 input with U+FFFD replacement char
+    /// instead of silently dropping it.
+    /// Useful to notice corrupted bytes on noisy serial links
+    pub fn utf8_replacement(self, utf8_replacement: bool) -> Self {
+        CliBuilder {
+            utf8_replacement,
+            ..self
         }
     }
 
@@ -79,10 +472,36 @@ where
         writer: T,
     ) -> CliBuilder<T, TE, CommandBuffer, HistoryBuffer> {
         CliBuilder {
+            auth: self.auth,
+            autocomplete_hook: self.autocomplete_hook,
+            banner: self.banner,
+            bell_on_error: self.bell_on_error,
+            colors: self.colors,
             command_buffer: self.command_buffer,
+            command_separator: self.command_separator,
+            degraded_prompt: self.degraded_prompt,
+            #[cfg(feature = "builtins")]
+            delay: self.delay,
+            error_handler: self.error_handler,
+            flow_control: self.flow_control,
+            help_command: self.help_command,
+            help_on_error: self.help_on_error,
+            help_short: self.help_short,
             history_buffer: self.history_buffer,
+            history_len: self.history_len,
+            history_valid_only: self.history_valid_only,
+            keymap: self.keymap,
+            machine_mode: self.machine_mode,
+            max_tokens: self.max_tokens,
+            parse_error_events: self.parse_error_events,
             writer,
             prompt: self.prompt,
+            scroll_region: self.scroll_region,
+            secret_mask: self.secret_mask,
+            tab_insert: self.tab_insert,
+            trace_commands: self.trace_commands,
+            transcript: self.transcript,
+            utf8_replacement: self.utf8_replacement,
         }
     }
 }
@@ -92,10 +511,36 @@ impl Default
 {
     fn default() -> Self {
         Self {
+            auth: None,
+            autocomplete_hook: None,
+            banner: "",
+            bell_on_error: false,
+            colors: false,
             command_buffer: [0; DEFAULT_CMD_LEN],
+            command_separator: false,
+            degraded_prompt: None,
+            #[cfg(feature = "builtins")]
+            delay: None,
+            error_handler: None,
+            flow_control: false,
+            help_command: true,
+            help_on_error: false,
+            help_short: Some('h'),
             history_buffer: [0; DEFAULT_HISTORY_LEN],
+            history_len: 0,
+            history_valid_only: false,
+            keymap: KeyMap::new(&[]),
+            machine_mode: false,
+            max_tokens: None,
+            parse_error_events: false,
             writer: EmptyWriter,
             prompt: DEFAULT_PROMPT,
+            scroll_region: None,
+            secret_mask: Some('*'),
+            tab_insert: TabInsert::Nothing,
+            trace_commands: false,
+            transcript: None,
+            utf8_replacement: false,
         }
     }
 }
@@ -0,0 +1,55 @@
+//! Tiny built-in commands, that are useful for simple automation
+//! without writing custom commands
+
+use crate::{
+    arguments::{Arg, FromArgument},
+    command::RawCommand,
+    service::ParseError,
+    token::TokensIter,
+};
+
+pub(crate) const ECHO: &str = "echo";
+pub(crate) const SLEEP: &str = "sleep";
+
+pub(crate) enum Builtin<'a> {
+    /// Write all arguments (as they were typed) separated with spaces
+    Echo(TokensIter<'a>),
+
+    /// Block for given number of milliseconds
+    Sleep(u32),
+}
+
+impl<'a> Builtin<'a> {
+    /// Returns `None` if command is not a built-in.
+    /// `sleep` is recognized only if it is enabled
+    pub(crate) fn parse(
+        command: &RawCommand<'a>,
+        sleep: bool,
+    ) -> Option<Result<Self, ParseError<'a>>> {
+        match command.name() {
+            ECHO => Some(Ok(Builtin::Echo(command.args().tokens()))),
+            SLEEP if sleep => {
+                let mut args = command.args().args();
+                let res = match args.next() {
+                    Some(Arg::Value(value)) => match args.next() {
+                        Some(Arg::Value(value)) => Err(ParseError::UnexpectedArgument { value }),
+                        Some(Arg::LongOption(name)) => {
+                            Err(ParseError::UnexpectedLongOption { name })
+                        }
+                        Some(Arg::ShortOption(name)) => {
+                            Err(ParseError::UnexpectedShortOption { name })
+                        }
+                        _ => u32::from_arg(value)
+                            .map(Builtin::Sleep)
+                            .map_err(ParseError::from),
+                    },
+                    Some(Arg::LongOption(name)) => Err(ParseError::UnexpectedLongOption { name }),
+                    Some(Arg::ShortOption(name)) => Err(ParseError::UnexpectedShortOption { name }),
+                    _ => Err(ParseError::MissingRequiredArgument { name: "<MS>" }),
+                };
+                Some(res)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 pub use crate::builder::CliBuilder;
+#[cfg(feature = "history")]
+pub use crate::builder::HistoryInitError;
 
 use core::fmt::Debug;
 
@@ -6,54 +8,294 @@ use core::fmt::Debug;
 use core::marker::PhantomData;
 
 use crate::{
+    arguments::Arg,
+    autocomplete::{Autocompletion, Request},
     buffer::Buffer,
     builder::DEFAULT_PROMPT,
     codes,
     command::RawCommand,
     editor::Editor,
+    help::HelpRequest,
     input::{ControlInput, Input, InputGenerator},
-    service::{Autocomplete, CommandProcessor, Help, ParseError, ProcessError},
+    pager::{PageSource, Pager},
+    prompt::Prompt,
+    service::{
+        Autocomplete, CommandProcessor, Dispatched, Help, ParseError, ProcessError,
+        StatefulProcessor, WithState,
+    },
     token::Tokens,
-    utils,
-    writer::{WriteExt, Writer},
+    utils, width,
+    writer::{DynWriter, Tap, WriteExt, Writer},
 };
 
-#[cfg(feature = "autocomplete")]
-use crate::autocomplete::Request;
+#[cfg(feature = "builtins")]
+use crate::builtins::{self, Builtin};
 
 #[cfg(feature = "help")]
-use crate::{help::HelpRequest, service::HelpError};
+use crate::service::HelpError;
 
 #[cfg(feature = "history")]
 use crate::history::History;
+#[cfg(target_has_atomic = "ptr")]
+use crate::interrupt::InterruptReader;
+
+#[cfg(feature = "async")]
+use crate::async_cli::{AsyncCommandProcessor, Deferring};
 
 use embedded_io::{Error, Write};
 
+const MORE_PROMPT: &str = "--More--";
+
+#[cfg(feature = "history")]
+const SEARCH_PROMPT: &str = "(reverse-i-search)`";
+
+/// Error of cli
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CliError<E> {
+    /// Cli was accessed while it's processing input (see `Cli::is_processing`).
+    /// Nothing was done, cli state is not changed
+    Busy,
+
+    /// Error of writer
+    Write(E),
+}
+
+impl<E: Error> From<E> for CliError<E> {
+    fn from(error: E) -> Self {
+        Self::Write(error)
+    }
+}
+
+/// Event, that is not a command, but can still be processed by application
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CliEvent<'a> {
+    /// Function key with number from 1 to 12 was pressed
+    FunctionKey(u8),
+
+    /// Help was requested. Emitted (with `help` feature) before built-in help
+    /// is written, so application can add its own output.
+    /// To replace built-in help call `CliHandle::skip_help`
+    Help(HelpRequest<'a>),
+
+    /// Command was received, but failed to parse.
+    /// Emitted before error is reported, so application can count
+    /// malformed input or handle command its own way.
+    /// To not report error call `CliHandle::skip_error`
+    InvalidCommand(RawCommand<'a>),
+
+    /// Line was received (Enter was pressed), but is not yet parsed.
+    /// Not emitted for secret input (like passwords)
+    LineReceived(&'a str),
+
+    /// Input failed to parse. Emitted instead of error message
+    /// when enabled with `CliBuilder::parse_error_events`
+    ParseError(ParseError<'a>),
+}
+
+/// Function, that completes autocompletion request
+/// (see `CliBuilder::autocomplete_hook`)
+pub type AutocompleteHook = fn(Request<'_>, &mut Autocompletion<'_>);
+
+/// Function, that writes parse error (see `CliBuilder::error_handler`)
+pub type ErrorHandler = fn(&mut dyn core::fmt::Write, ParseError<'_>) -> core::fmt::Result;
+
+/// Memory usage of history buffer
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HistoryUsage {
+    /// Number of commands stored in history
+    pub entries: usize,
+
+    /// Bytes of buffer, that are not used by any command
+    pub free: usize,
+
+    /// Bytes of buffer, used by stored commands (including separators)
+    pub used: usize,
+}
+
+/// What Tab key inserts when it isn't used for autocompletion
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TabInsert {
+    /// Tab key is ignored
+    #[default]
+    Nothing,
+
+    /// Literal tab char is inserted.
+    /// It is echoed as single space, so cursor movement stays correct
+    Tab,
+
+    /// Given number of spaces is inserted
+    Spaces(u8),
+}
+
+/// How typed chars are echoed back to terminal
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EchoMode {
+    /// Typed chars are echoed as is
+    #[default]
+    Normal,
+
+    /// Nothing is echoed
+    Hidden,
+
+    /// Given char is echoed instead of each typed char
+    Masked(char),
+}
+
+/// Password check, that is required before commands are accepted
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Auth {
+    pub(crate) check: fn(&str) -> bool,
+    pub(crate) prompt: &'static str,
+}
+
 pub struct CliHandle<'a, W: Write<Error = E>, E: embedded_io::Error> {
-    new_prompt: Option<&'static str>,
+    changes: HandleChanges<W, E>,
+    history_usage: HistoryUsage,
     writer: Writer<'a, W, E>,
 }
 
+/// Changes, that were requested through `CliHandle`,
+/// applied to cli after command or event is processed
+struct HandleChanges<W: Write<Error = E>, E: embedded_io::Error> {
+    #[cfg(feature = "async")]
+    defer: bool,
+    degraded: Option<bool>,
+    /// Something was written by processor, so output doesn't end with new line
+    dirty: bool,
+    echo: Option<EchoMode>,
+    lock: bool,
+    machine_mode: bool,
+    paging: Option<Paging<W, E>>,
+    prompt: Option<Prompt>,
+    prompt_suffix: Option<&'static str>,
+    skip_builtin: bool,
+}
+
 impl<'a, W, E> CliHandle<'a, W, E>
 where
     W: Write<Error = E>,
     E: embedded_io::Error,
 {
     /// Set new prompt to use in CLI
-    pub fn set_prompt(&mut self, prompt: &'static str) {
-        self.new_prompt = Some(prompt)
+    pub fn set_prompt(&mut self, prompt: impl Into<Prompt>) {
+        self.changes.prompt = Some(prompt.into())
+    }
+
+    /// Switch between normal and degraded prompt
+    /// (see `Cli::set_degraded`)
+    pub fn set_degraded(&mut self, degraded: bool) {
+        self.changes.degraded = Some(degraded)
+    }
+
+    /// Set new text, that is shown right after prompt
+    /// (see `Cli::set_prompt_suffix`)
+    pub fn set_prompt_suffix(&mut self, suffix: &'static str) {
+        self.changes.prompt_suffix = Some(suffix)
+    }
+
+    /// Set how typed chars are echoed after command is processed
+    /// (see `Cli::set_echo`)
+    pub fn set_echo(&mut self, echo: EchoMode) {
+        self.changes.echo = Some(echo)
+    }
+
+    /// Lock cli after command is processed, so password is required again
+    /// (see `CliBuilder::auth`). Does nothing if authentication is not enabled
+    pub fn lock(&mut self) {
+        self.changes.lock = true
+    }
+
+    /// Do not write built-in help after `CliEvent::Help` is processed
+    pub fn skip_help(&mut self) {
+        self.changes.skip_builtin = true
+    }
+
+    /// Do not report error after `CliEvent::InvalidCommand` is processed
+    pub fn skip_error(&mut self) {
+        self.changes.skip_builtin = true
+    }
+
+    /// Enable or disable machine-readable output of key-value pairs.
+    ///
+    /// Changes apply immediately to the writer of this handle.
+    pub fn set_machine_mode(&mut self, enabled: bool) {
+        self.writer.set_machine_mode(enabled)
+    }
+
+    /// Write output of given source split into pages
+    /// with given number of lines (see `Pager`).
+    ///
+    /// First page is written immediately. If there is more output,
+    /// next pages are written from the same source when key is pressed,
+    /// without processing the command again.
+    /// Given context (for example, id of listed object) is passed
+    /// to source with each page
+    pub fn pager(
+        &mut self,
+        lines: usize,
+        context: usize,
+        source: PageSource<W, E>,
+    ) -> Result<(), E> {
+        // page must contain at least one line, otherwise there will be no progress
+        let lines = lines.max(1);
+        let mut pager = Pager::new(&mut self.writer, lines);
+        source(&mut pager, context, 0)?;
+        if pager.has_more() {
+            self.changes.paging = Some(Paging {
+                context,
+                lines,
+                next: lines,
+                source,
+            });
+        }
+        Ok(())
     }
 
     pub fn writer(&mut self) -> &mut Writer<'a, W, E> {
         &mut self.writer
     }
 
-    fn new(writer: Writer<'a, W, E>) -> Self {
+    /// Returns memory usage of history buffer
+    /// (as it was before command was processed)
+    pub fn history_usage(&self) -> HistoryUsage {
+        self.history_usage
+    }
+
+    /// Keep command in editor, so it's processed later by async processor
+    #[cfg(feature = "async")]
+    pub(crate) fn defer(&mut self) {
+        self.changes.defer = true
+    }
+
+    fn new(writer: Writer<'a, W, E>, history_usage: HistoryUsage) -> Self {
         Self {
-            new_prompt: None,
+            changes: HandleChanges {
+                #[cfg(feature = "async")]
+                defer: false,
+                degraded: None,
+                dirty: false,
+                echo: None,
+                lock: false,
+                machine_mode: false,
+                paging: None,
+                prompt: None,
+                prompt_suffix: None,
+                skip_builtin: false,
+            },
+            history_usage,
             writer,
         }
     }
+
+    /// Consume handle, so its changes can be applied to cli
+    fn into_changes(self) -> HandleChanges<W, E> {
+        HandleChanges {
+            dirty: self.writer.is_dirty(),
+            machine_mode: self.writer.is_machine_mode(),
+            ..self.changes
+        }
+    }
 }
 
 impl<'a, W, E> Debug for CliHandle<'a, W, E>
@@ -73,18 +315,151 @@ enum NavigateHistory {
 }
 
 enum NavigateInput {
+    BackWord,
     Backward,
+    End,
     Forward,
+    ForwardWord,
+    Start,
+}
+
+/// Command, that is kept in editor until it's processed by async processor
+#[cfg(feature = "async")]
+#[derive(Clone, Copy, Debug, Default)]
+struct Deferred {
+    command: Tokenized,
+
+    /// Command was pushed to history
+    #[cfg(feature = "history")]
+    pushed: bool,
+}
+
+/// State of reverse search in history (Ctrl+R).
+/// Typed query is kept in editor, while input, that was being edited,
+/// is stashed in history
+#[cfg(feature = "history")]
+#[derive(Clone, Copy, Debug)]
+struct Search {
+    /// Start of history element, that matches query
+    matched: Option<usize>,
+}
+
+/// Position of tokenized command, that is kept in editor
+#[cfg(feature = "async")]
+#[derive(Clone, Copy, Debug, Default)]
+struct Tokenized {
+    /// Tokens are borrowed from command as it was typed,
+    /// so original line is still available
+    borrowed: bool,
+
+    /// Start of command in editor text
+    start: usize,
+
+    /// End of command in original (not tokenized) text
+    end: usize,
+
+    /// Length of tokenized command
+    len: usize,
+}
+
+#[cfg(feature = "async")]
+impl Tokenized {
+    /// Returns tokens of this command
+    ///
+    /// # Safety
+    /// Given text must still contain this tokenized command
+    unsafe fn tokens<'t>(&self, text: &'t str) -> Tokens<'t> {
+        if self.borrowed {
+            let line = text.get_unchecked(self.start..self.end);
+            // command was already checked to be borrowable
+            Tokens::from_line(line).unwrap_or(Tokens::from_raw("", true))
+        } else {
+            Tokens::from_raw(text.get_unchecked(self.start..self.start + self.len), false)
+        }
+    }
+
+    /// Returns original line of this command, if it wasn't modified by tokenization
+    fn line<'t>(&self, text: &'t str) -> Option<&'t str> {
+        text.get(self.start..self.end).filter(|_| self.borrowed)
+    }
+}
+
+/// State of paged output, that is paused until key is pressed
+struct Paging<W: Write<Error = E>, E: Error> {
+    /// Context, that is passed to source with each page
+    context: usize,
+
+    /// How many lines are shown on each page
+    lines: usize,
+
+    /// Index of the first line of the next page
+    next: usize,
+
+    source: PageSource<W, E>,
+}
+
+impl<W: Write<Error = E>, E: Error> Debug for Paging<W, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Paging")
+            .field("context", &self.context)
+            .field("lines", &self.lines)
+            .field("next", &self.next)
+            .finish()
+    }
 }
 
 #[doc(hidden)]
 pub struct Cli<W: Write<Error = E>, E: Error, CommandBuffer: Buffer, HistoryBuffer: Buffer> {
+    auth: Option<Auth>,
+    #[cfg_attr(not(feature = "autocomplete"), allow(dead_code))]
+    autocomplete_hook: Option<AutocompleteHook>,
+    banner: &'static str,
+    bell_on_error: bool,
+    colors: bool,
+    command_separator: bool,
+    /// Degraded prompt is shown instead of normal one
+    degraded: bool,
+    degraded_prompt: Option<Prompt>,
+    #[cfg(feature = "async")]
+    deferred: Option<Deferred>,
+    #[cfg(feature = "builtins")]
+    delay: Option<fn(u32)>,
+    echo: EchoMode,
+    error_handler: Option<ErrorHandler>,
     editor: Option<Editor<CommandBuffer>>,
+    flow_control: bool,
+    help_command: bool,
+    help_on_error: bool,
+    help_short: Option<char>,
     #[cfg(feature = "history")]
     history: History<HistoryBuffer>,
+    history_valid_only: bool,
     input_generator: Option<InputGenerator>,
-    prompt: &'static str,
-    writer: W,
+    /// Password is required before commands are accepted
+    locked: bool,
+    machine_mode: bool,
+    max_tokens: Option<usize>,
+    parse_error_events: bool,
+    paging: Option<Paging<W, E>>,
+    /// Cursor position, where bracketed paste started.
+    /// Pasted text is not echoed until paste ends
+    paste_start: Option<usize>,
+    /// Error was reported while last command was processed
+    parse_failed: bool,
+    prompt: Prompt,
+    /// Prompt (with current input) was cleared and is not yet written back
+    prompt_hidden: bool,
+    /// Text, that is shown right after prompt
+    prompt_suffix: &'static str,
+    prompt_suspended: bool,
+    /// Number of terminal rows, when prompt is pinned to the bottom row
+    scroll_region: Option<u16>,
+    #[cfg(feature = "history")]
+    search: Option<Search>,
+    secret_mask: Option<char>,
+    tab_insert: TabInsert,
+    trace_commands: bool,
+    writer: Tap<W>,
     #[cfg(not(feature = "history"))]
     _ph: PhantomData<HistoryBuffer>,
 }
@@ -98,9 +473,38 @@ where
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Cli")
+            .field("auth", &self.auth)
+            .field("autocomplete_hook", &self.autocomplete_hook)
+            .field("banner", &self.banner)
+            .field("bell_on_error", &self.bell_on_error)
+            .field("colors", &self.colors)
+            .field("command_separator", &self.command_separator)
+            .field("degraded", &self.degraded)
+            .field("degraded_prompt", &self.degraded_prompt)
+            .field("echo", &self.echo)
+            .field("error_handler", &self.error_handler)
             .field("editor", &self.editor)
+            .field("help_command", &self.help_command)
+            .field("help_on_error", &self.help_on_error)
+            .field("help_short", &self.help_short)
+            .field("history_valid_only", &self.history_valid_only)
             .field("input_generator", &self.input_generator)
+            .field("locked", &self.locked)
+            .field("machine_mode", &self.machine_mode)
+            .field("max_tokens", &self.max_tokens)
+            .field("output_paused", &self.writer.paused)
+            .field("parse_error_events", &self.parse_error_events)
+            .field("paging", &self.paging)
+            .field("paste_start", &self.paste_start)
+            .field("parse_failed", &self.parse_failed)
             .field("prompt", &self.prompt)
+            .field("prompt_hidden", &self.prompt_hidden)
+            .field("prompt_suffix", &self.prompt_suffix)
+            .field("prompt_suspended", &self.prompt_suspended)
+            .field("scroll_region", &self.scroll_region)
+            .field("secret_mask", &self.secret_mask)
+            .field("tab_insert", &self.tab_insert)
+            .field("trace_commands", &self.trace_commands)
             .finish()
     }
 }
@@ -119,18 +523,55 @@ where
         command_buffer: CommandBuffer,
         history_buffer: HistoryBuffer,
     ) -> Result<Self, E> {
+        // pasted text can't be longer than command buffer
+        let paste_limit = command_buffer.len();
         let mut cli = Self {
+            auth: None,
+            autocomplete_hook: None,
+            banner: "",
+            bell_on_error: false,
+            colors: false,
+            command_separator: false,
+            #[cfg(feature = "async")]
+            deferred: None,
+            degraded: false,
+            degraded_prompt: None,
+            #[cfg(feature = "builtins")]
+            delay: None,
+            error_handler: None,
+            echo: EchoMode::Normal,
             editor: Some(Editor::new(command_buffer)),
+            flow_control: false,
+            help_command: true,
+            help_on_error: false,
+            help_short: Some('h'),
             #[cfg(feature = "history")]
             history: History::new(history_buffer),
-            input_generator: Some(InputGenerator::new()),
+            history_valid_only: false,
+            input_generator: Some(InputGenerator::new().with_paste_limit(paste_limit)),
+            locked: false,
+            machine_mode: false,
+            max_tokens: None,
+            paging: None,
+            paste_start: None,
+            parse_error_events: false,
+            parse_failed: false,
             prompt: DEFAULT_PROMPT,
-            writer,
+            prompt_hidden: false,
+            prompt_suffix: "",
+            prompt_suspended: false,
+            scroll_region: None,
+            #[cfg(feature = "history")]
+            search: None,
+            secret_mask: None,
+            tab_insert: TabInsert::Nothing,
+            trace_commands: false,
+            writer: Tap::new(writer, None),
             #[cfg(not(feature = "history"))]
             _ph: PhantomData,
         };
 
-        cli.writer.flush_str(cli.prompt)?;
+        cli.writer.flush_str(cli.prompt.text())?;
 
         Ok(cli)
     }
@@ -138,18 +579,67 @@ where
     pub(crate) fn from_builder(
         builder: CliBuilder<W, E, CommandBuffer, HistoryBuffer>,
     ) -> Result<Self, E> {
+        // pasted text can't be longer than command buffer
+        let paste_limit = builder.command_buffer.len();
         let mut cli = Self {
+            auth: builder.auth,
+            autocomplete_hook: builder.autocomplete_hook,
+            banner: builder.banner,
+            bell_on_error: builder.bell_on_error,
+            colors: builder.colors,
+            command_separator: builder.command_separator,
+            #[cfg(feature = "async")]
+            deferred: None,
+            degraded: false,
+            degraded_prompt: builder.degraded_prompt,
+            #[cfg(feature = "builtins")]
+            delay: builder.delay,
+            error_handler: builder.error_handler,
+            echo: EchoMode::Normal,
             editor: Some(Editor::new(builder.command_buffer)),
+            flow_control: builder.flow_control,
+            help_command: builder.help_command,
+            help_on_error: builder.help_on_error,
+            help_short: builder.help_short,
             #[cfg(feature = "history")]
-            history: History::new(builder.history_buffer),
-            input_generator: Some(InputGenerator::new()),
+            history: History::from_raw(builder.history_buffer, builder.history_len),
+            history_valid_only: builder.history_valid_only,
+            input_generator: Some(
+                InputGenerator::new()
+                    .with_utf8_replacement(builder.utf8_replacement)
+                    .with_keymap(builder.keymap)
+                    .with_paste_limit(paste_limit),
+            ),
+            locked: builder.auth.is_some(),
+            machine_mode: builder.machine_mode,
+            max_tokens: builder.max_tokens,
+            paging: None,
+            paste_start: None,
+            parse_error_events: builder.parse_error_events,
+            parse_failed: false,
             prompt: builder.prompt,
-            writer: builder.writer,
+            prompt_hidden: false,
+            prompt_suffix: "",
+            prompt_suspended: false,
+            scroll_region: builder.scroll_region,
+            #[cfg(feature = "history")]
+            search: None,
+            secret_mask: builder.secret_mask,
+            tab_insert: builder.tab_insert,
+            trace_commands: builder.trace_commands,
+            writer: Tap::new(builder.writer, builder.transcript),
             #[cfg(not(feature = "history"))]
             _ph: PhantomData,
         };
 
-        cli.writer.flush_str(cli.prompt)?;
+        if let Some(rows) = cli.scroll_region {
+            cli.writer
+                .write_csi(&[1, rows - 1], codes::SET_SCROLL_REGION)?;
+            cli.writer.write_csi(&[rows, 1], codes::CURSOR_POSITION)?;
+        }
+        cli.write_banner()?;
+        cli.write_prompt_text()?;
+        cli.writer.flush()?;
 
         Ok(cli)
     }
@@ -162,191 +652,1290 @@ where
         &mut self,
         b: u8,
         processor: &mut P,
-    ) -> Result<(), E> {
+    ) -> Result<(), CliError<E>> {
+        if self.is_processing() {
+            return Err(CliError::Busy);
+        }
+        // secret input is not reported
+        let secret = self.is_secret();
+        if !secret {
+            self.writer.received(b);
+        }
+
+        // input must be shown together with prompt
+        if self.prompt_hidden {
+            self.write_prompt()?;
+        }
+
         if let (Some(mut editor), Some(mut input_generator)) =
             (self.editor.take(), self.input_generator.take())
         {
             let result = input_generator
                 .accept(b)
-                .map(|input| match input {
-                    Input::Control(control) => {
+                .inspect(|&input| {
+                    if !secret {
+                        processor.on_input(input)
+                    }
+                })
+                .map(|input| match (self.paging.take(), input) {
+                    (
+                        paging,
+                        Input::Control(control @ (ControlInput::XOff | ControlInput::XOn)),
+                    ) => {
+                        self.paging = paging;
+                        self.on_flow_control(&editor, control)
+                    }
+                    // paged output is finished first, even if echo was changed meanwhile
+                    (Some(paging), input) => self.on_paging_input(paging, input),
+                    (None, input) if secret => {
+                        self.on_secret_input::<C, _>(&mut editor, input, processor)
+                    }
+                    #[cfg(feature = "history")]
+                    (None, input) if self.search.is_some() => {
+                        self.on_search_input::<C, _>(&mut editor, input, processor)
+                    }
+                    (None, Input::Control(control)) => {
                         self.on_control_input::<C, _>(&mut editor, control, processor)
                     }
-                    Input::Char(text) => self.on_text_input(&mut editor, text),
+                    (None, Input::Char(text)) => self.on_text_input(&mut editor, text),
                 })
                 .unwrap_or(Ok(()));
 
             self.editor = Some(editor);
             self.input_generator = Some(input_generator);
-            result
+            Ok(result?)
         } else {
             Ok(())
         }
     }
 
-    /// Set new prompt to use in CLI
-    ///
-    /// Changes will apply immediately and current line
-    /// will be replaced by new prompt and input
-    pub fn set_prompt(&mut self, prompt: &'static str) -> Result<(), E> {
-        self.prompt = prompt;
-        self.clear_line(false)?;
+    /// Same as `process_byte`, but given state is passed explicitly
+    /// to the processor, so processor doesn't need to capture it
+    pub fn process_byte_with_state<C: Autocomplete + Help, S, P: StatefulProcessor<S, W, E>>(
+        &mut self,
+        b: u8,
+        state: &mut S,
+        processor: &mut P,
+    ) -> Result<(), CliError<E>> {
+        self.process_byte::<C, _>(b, &mut WithState { processor, state })
+    }
 
-        if let Some(editor) = self.editor.as_mut() {
-            self.writer.flush_str(editor.text())?;
+    /// Process all given bytes.
+    ///
+    /// Same as calling `process_byte` for each byte, but runs of printable
+    /// chars (for example, pasted text) are inserted and echoed at once
+    pub fn process_slice<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        mut bytes: &[u8],
+        processor: &mut P,
+    ) -> Result<(), CliError<E>> {
+        if self.is_processing() {
+            return Err(CliError::Busy);
+        }
+        while let Some((&b, rest)) = bytes.split_first() {
+            let pasted = self.process_text(bytes, processor)?;
+            if pasted > 0 {
+                bytes = bytes.get(pasted..).unwrap_or_default();
+            } else {
+                self.process_byte::<C, _>(b, processor)?;
+                bytes = rest;
+            }
         }
-
         Ok(())
     }
 
-    pub fn write(
+    /// Insert and echo run of printable chars at the start of given bytes at once.
+    ///
+    /// Returns how many bytes were processed. If run can't be processed
+    /// as a whole (for example, cursor is not at the end of input),
+    /// nothing is processed and bytes must be processed one by one
+    fn process_text<P: CommandProcessor<W, E>>(
         &mut self,
-        f: impl FnOnce(&mut Writer<'_, W, E>) -> Result<(), E>,
-    ) -> Result<(), E> {
-        self.clear_line(true)?;
-
-        let mut cli_writer = Writer::new(&mut self.writer);
+        bytes: &[u8],
+        processor: &mut P,
+    ) -> Result<usize, E> {
+        let secret = self.is_secret() || self.is_searching();
+        let (Some(editor), Some(input_generator)) =
+            (self.editor.as_mut(), self.input_generator.as_mut())
+        else {
+            return Ok(0);
+        };
+        let pasting = self.paste_start.is_some();
+        if self.paging.is_some() || secret || (editor.cursor() < editor.len() && !pasting) {
+            return Ok(0);
+        }
+        // when buffer is full, rest of the bytes are dropped one by one
+        let bytes = bytes.get(..editor.free()).unwrap_or(bytes);
+        let Some(text) = input_generator.accept_text(bytes) else {
+            return Ok(0);
+        };
 
-        f(&mut cli_writer)?;
+        for (i, &b) in text.as_bytes().iter().enumerate() {
+            self.writer.received(b);
+            if let Some(c) = text.get(i..=i) {
+                processor.on_input(Input::Char(c));
+            }
+        }
 
-        // we should write back input that was there before writing
-        if cli_writer.is_dirty() {
-            self.writer.write_str(codes::CRLF)?;
+        // input must be shown together with prompt
+        if self.prompt_hidden {
+            self.write_prompt()?;
         }
-        self.writer.write_str(self.prompt)?;
+
         if let Some(editor) = self.editor.as_mut() {
-            self.writer.flush_str(editor.text())?;
+            if let Some(inserted) = editor.insert(text).filter(|_| !pasting) {
+                self.writer.flush_str(inserted)?;
+            }
         }
+        Ok(text.len())
+    }
 
-        Ok(())
+    /// Process at most `max_bytes` of given bytes and return how many were processed.
+    ///
+    /// Processing also stops right after the byte, that caused a command
+    /// or an event to be passed to processor. So with cooperative scheduling
+    /// CLI can be polled in small steps even when large text is pasted.
+    /// Unprocessed bytes should be passed in next call
+    pub fn poll_slice<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        bytes: &[u8],
+        max_bytes: usize,
+        processor: &mut P,
+    ) -> Result<usize, CliError<E>> {
+        let mut processor = Dispatched {
+            processor,
+            dispatched: false,
+        };
+        let mut processed = 0;
+        for &b in bytes.iter().take(max_bytes) {
+            self.process_byte::<C, _>(b, &mut processor)?;
+            processed += 1;
+            if processor.dispatched {
+                break;
+            }
+        }
+        Ok(processed)
     }
 
-    fn clear_line(&mut self, clear_prompt: bool) -> Result<(), E> {
-        self.writer.write_str("\r")?;
-        self.writer.write_bytes(codes::CLEAR_LINE)?;
+    /// Whether input is being processed right now.
+    ///
+    /// This can be true only if cli is accessed from inside command processor
+    /// (for example, through raw pointer or captured in interrupt handler).
+    /// Such access corrupts cli state, so all output during processing
+    /// must go through `CliHandle`. Methods, that process input or write output,
+    /// return `CliError::Busy` on such access.
+    pub fn is_processing(&self) -> bool {
+        // editor is taken out of cli for the duration of processing
+        self.editor.is_none()
+    }
 
-        if !clear_prompt {
-            self.writer.write_str(self.prompt)?;
-        }
+    /// Whether remote side asked to pause output with XOFF.
+    ///
+    /// Always false if flow control is not enabled in builder.
+    /// While output is paused, input is still processed, but all output
+    /// (echo, prompt, output of commands and of `write`) is discarded.
+    /// Prompt and current input are written again when XON is received.
+    /// Application should hold its own output until then
+    /// (`write_queued` keeps bytes queued by itself)
+    pub fn is_output_paused(&self) -> bool {
+        self.writer.paused
+    }
 
-        self.writer.flush()
+    /// Send XOFF to the remote side, so it stops sending input.
+    ///
+    /// Can be used when application input queue is near full.
+    /// It's sent even if output is paused
+    pub fn pause_input(&mut self) -> Result<(), E> {
+        self.writer.flush_control(codes::XOFF)
     }
 
-    fn on_text_input(&mut self, editor: &mut Editor<CommandBuffer>, text: &str) -> Result<(), E> {
-        let is_inside = editor.cursor() < editor.len();
-        if let Some(c) = editor.insert(text) {
-            if is_inside {
-                // text is always one char
-                debug_assert_eq!(c.chars().count(), 1);
-                self.writer.write_bytes(codes::INSERT_CHAR)?;
-            }
-            self.writer.flush_str(c)?;
-        }
-        Ok(())
+    /// Send XON to the remote side, so it resumes sending input
+    pub fn resume_input(&mut self) -> Result<(), E> {
+        self.writer.flush_control(codes::XON)
     }
 
-    fn on_control_input<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
-        &mut self,
-        editor: &mut Editor<CommandBuffer>,
-        control: ControlInput,
-        processor: &mut P,
-    ) -> Result<(), E> {
-        match control {
-            ControlInput::Enter => {
-                self.writer.write_str(codes::CRLF)?;
+    /// Notify cli that host is connected (for example, DTR of USB CDC
+    /// line state was set), so output is resumed.
+    ///
+    /// Banner, prompt and current input are written again, because
+    /// host most likely missed them. Does nothing if host is already connected
+    pub fn on_connect(&mut self) -> Result<(), E> {
+        if !self.writer.muted {
+            return Ok(());
+        }
+        self.writer.muted = false;
 
-                #[cfg(feature = "history")]
-                self.history.push(editor.text());
-                let text = editor.text_mut();
+        if let Some(rows) = self.scroll_region {
+            self.writer
+                .write_csi(&[1, rows - 1], codes::SET_SCROLL_REGION)?;
+        }
+        self.begin_output()?;
+        self.write_banner()?;
+        if self.prompt_suspended {
+            self.prompt_hidden = true;
+            return self.writer.flush();
+        }
+        self.write_prompt()
+    }
 
-                let tokens = Tokens::new(text);
-                self.process_input::<C, _>(tokens, processor)?;
+    /// Notify cli that host is disconnected, so all output
+    /// (including output of commands) is discarded until `on_connect` is called.
+    ///
+    /// Without it writes to disconnected port either block or fill up
+    /// endpoint buffers and host receives garbled output after reconnect
+    pub fn on_disconnect(&mut self) {
+        self.writer.muted = true;
+    }
 
-                editor.clear();
+    /// Whether output is written to the host (`on_disconnect` wasn't called
+    /// or `on_connect` was called after it)
+    pub fn is_connected(&self) -> bool {
+        !self.writer.muted
+    }
 
-                self.writer.flush_str(self.prompt)?;
-            }
-            ControlInput::Tab => {
-                #[cfg(feature = "autocomplete")]
-                self.process_autocomplete::<C>(editor)?;
-            }
-            ControlInput::Backspace => {
-                if editor.move_left() {
-                    editor.remove();
-                    self.writer.flush_bytes(codes::CURSOR_BACKWARD)?;
-                    self.writer.flush_bytes(codes::DELETE_CHAR)?;
-                }
-            }
-            ControlInput::Down =>
-            {
-                #[cfg(feature = "history")]
-                self.navigate_history(editor, NavigateHistory::Newer)?
-            }
-            ControlInput::Up =>
-            {
-                #[cfg(feature = "history")]
-                self.navigate_history(editor, NavigateHistory::Older)?
+    /// Reset session state: current input, partially received escape sequence,
+    /// paged output, history navigation (or search) and paused output are dropped.
+    /// With `clear_history` all commands are also removed from history.
+    ///
+    /// Current line is then replaced with fresh prompt. Useful after
+    /// terminal is reconnected or when link had protocol errors.
+    /// Locked cli stays locked and echo mode is kept
+    pub fn reset(&mut self, clear_history: bool) -> Result<(), CliError<E>> {
+        if self.is_processing() {
+            return Err(CliError::Busy);
+        }
+        self.paging = None;
+        self.paste_start = None;
+        self.writer.paused = false;
+        if let Some(editor) = self.editor.as_mut() {
+            editor.clear();
+        }
+        if let Some(input_generator) = self.input_generator.as_mut() {
+            input_generator.reset();
+        }
+        #[cfg(feature = "history")]
+        {
+            self.search = None;
+            self.history.deselect();
+            if clear_history {
+                self.history.clear();
             }
-            ControlInput::Forward => self.navigate_input(editor, NavigateInput::Forward)?,
-            ControlInput::Back => self.navigate_input(editor, NavigateInput::Backward)?,
         }
+        #[cfg(not(feature = "history"))]
+        let _ = clear_history;
+        Ok(self.redraw_line()?)
+    }
 
-        Ok(())
+    /// Whether password is required before commands are accepted
+    /// (see `CliBuilder::auth`)
+    pub fn is_locked(&self) -> bool {
+        self.locked
     }
 
-    fn navigate_input(
-        &mut self,
-        editor: &mut Editor<CommandBuffer>,
-        dir: NavigateInput,
-    ) -> Result<(), E> {
-        match dir {
-            NavigateInput::Backward if editor.move_left() => {
-                self.writer.flush_bytes(codes::CURSOR_BACKWARD)?;
-            }
-            NavigateInput::Forward if editor.move_right() => {
-                self.writer.flush_bytes(codes::CURSOR_FORWARD)?;
-            }
-            _ => return Ok(()),
+    /// Lock cli, so password is required again before commands are accepted.
+    ///
+    /// Current input is discarded and password prompt is shown.
+    /// Does nothing if authentication is not enabled in builder
+    pub fn lock(&mut self) -> Result<(), E> {
+        if self.auth.is_none() || self.locked {
+            return Ok(());
         }
-        Ok(())
+        self.locked = true;
+        self.paging = None;
+        #[cfg(feature = "history")]
+        {
+            self.search = None;
+        }
+        if let Some(editor) = self.editor.as_mut() {
+            editor.clear();
+        }
+        #[cfg(feature = "history")]
+        self.history.deselect();
+        self.redraw_line()
     }
 
-    #[cfg(feature = "history")]
-    fn navigate_history(
-        &mut self,
-        editor: &mut Editor<CommandBuffer>,
-        dir: NavigateHistory,
-    ) -> Result<(), E> {
-        let history_elem = match dir {
-            NavigateHistory::Older => self.history.next_older(),
-            NavigateHistory::Newer => self.history.next_newer().or(Some("")),
-        };
-        if let Some(element) = history_elem {
-            editor.clear();
-            editor.insert(element);
-            self.clear_line(false)?;
+    /// Returns how typed chars are echoed
+    pub fn echo(&self) -> EchoMode {
+        self.echo
+    }
 
-            self.writer.flush_str(editor.text())?;
+    /// Set how typed chars are echoed (for example, to read a secret
+    /// with next command). Editor still keeps real input, only output is changed.
+    ///
+    /// While echo is not `EchoMode::Normal`, input can't be edited in the middle,
+    /// autocompleted or navigated through history and it is not stored in history.
+    /// Current input is redrawn with new mode
+    pub fn set_echo(&mut self, echo: EchoMode) -> Result<(), E> {
+        if self.echo == echo {
+            return Ok(());
         }
-        Ok(())
+        self.echo = echo;
+        #[cfg(feature = "history")]
+        {
+            self.search = None;
+        }
+        // redrawn input leaves cursor at the end
+        if let Some(editor) = self.editor.as_mut() {
+            editor.move_to_end();
+        }
+        #[cfg(feature = "history")]
+        self.history.deselect();
+        self.redraw_line()
     }
 
-    #[cfg(feature = "autocomplete")]
-    fn process_autocomplete<C: Autocomplete>(
-        &mut self,
+    /// Returns current prompt
+    pub fn prompt(&self) -> &'static str {
+        self.active_prompt().text()
+    }
+
+    /// Returns number of terminal columns, that current prompt occupies
+    /// (color codes are not counted)
+    pub fn prompt_width(&self) -> usize {
+        self.active_prompt().width()
+    }
+
+    /// Returns true if degraded prompt is shown instead of normal one
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// Returns prompt, that is currently shown
+    fn active_prompt(&self) -> Prompt {
+        match self.degraded_prompt {
+            Some(prompt) if self.degraded => prompt,
+            _ => self.prompt,
+        }
+    }
+
+    /// Returns raw bytes of history: all commands from oldest to newest,
+    /// each terminated with null byte. They can be stored (for example, in flash)
+    /// and restored later with `CliBuilder::history_init`.
+    ///
+    /// Empty if `history` feature is disabled
+    pub fn history_bytes(&self) -> &[u8] {
+        #[cfg(feature = "history")]
+        {
+            self.history.as_bytes()
+        }
+        #[cfg(not(feature = "history"))]
+        {
+            &[]
+        }
+    }
+
+    /// Returns memory usage of history buffer.
+    ///
+    /// All values are zero if `history` feature is disabled
+    pub fn history_usage(&self) -> HistoryUsage {
+        #[cfg(feature = "history")]
+        {
+            self.history.usage()
+        }
+        #[cfg(not(feature = "history"))]
+        {
+            HistoryUsage::default()
+        }
+    }
+
+    /// Returns writer, that receives all output of cli
+    #[cfg(feature = "async")]
+    pub(crate) fn output(&mut self) -> &mut W {
+        &mut self.writer.inner
+    }
+
+    /// Returns text, that is currently typed after prompt
+    /// (but not yet submitted)
+    pub fn current_input(&self) -> &str {
+        self.editor
+            .as_ref()
+            .map(|editor| editor.text())
+            .unwrap_or("")
+    }
+
+    /// Returns text, that is shown right after prompt
+    pub fn prompt_suffix(&self) -> &'static str {
+        self.prompt_suffix
+    }
+
+    /// Switch between normal and degraded prompt
+    /// (given with `CliBuilder::degraded_prompt`), for example,
+    /// when link to some subsystem is lost and when it's restored.
+    ///
+    /// Changes will apply immediately the same way as with `set_prompt`
+    pub fn set_degraded(&mut self, degraded: bool) -> Result<(), E> {
+        self.degraded = degraded;
+        self.redraw_line()
+    }
+
+    /// Set new prompt to use in CLI.
+    /// When degraded prompt is shown, change is visible only after
+    /// switching back to normal prompt
+    ///
+    /// Changes will apply immediately and current line
+    /// will be replaced by new prompt and input
+    pub fn set_prompt(&mut self, prompt: impl Into<Prompt>) -> Result<(), E> {
+        self.prompt = prompt.into();
+        self.redraw_line()
+    }
+
+    /// Set text, that is shown right after prompt (for example, current mode
+    /// or namespace), so it can be changed without formatting whole prompt.
+    ///
+    /// Changes will apply immediately the same way as with `set_prompt`
+    pub fn set_prompt_suffix(&mut self, suffix: &'static str) -> Result<(), E> {
+        self.prompt_suffix = suffix;
+        self.redraw_line()
+    }
+
+    fn redraw_line(&mut self) -> Result<(), E> {
+        if self.paging.is_some() || self.prompt_hidden {
+            // prompt will be shown after paging is finished
+            // or when prompt is resumed
+            return Ok(());
+        }
+        self.clear_line(false)?;
+        self.write_input()?;
+        self.writer.flush()
+    }
+
+    /// Enable or disable printing of command help after
+    /// missing or unexpected argument errors
+    pub fn set_help_on_error(&mut self, enabled: bool) {
+        self.help_on_error = enabled;
+    }
+
+    /// Enable or disable machine-readable output of key-value pairs
+    pub fn set_machine_mode(&mut self, enabled: bool) {
+        self.machine_mode = enabled;
+    }
+
+    /// Enable or disable printing of each command name and its arguments
+    /// before command is passed to processor
+    pub fn set_trace_commands(&mut self, enabled: bool) {
+        self.trace_commands = enabled;
+    }
+
+    pub fn write(
+        &mut self,
+        f: impl FnOnce(&mut Writer<'_, W, E>) -> Result<(), E>,
+    ) -> Result<(), CliError<E>> {
+        // use CliHandle::writer while processing input
+        if self.is_processing() {
+            return Err(CliError::Busy);
+        }
+
+        if self.scroll_region.is_some() {
+            // prompt is not touched, so input can continue after output
+            self.writer.write_bytes(codes::SAVE_CURSOR)?;
+            self.begin_output()?;
+        } else if !self.prompt_hidden {
+            self.clear_line(true)?;
+        }
+
+        let mut cli_writer = self.make_writer();
+
+        f(&mut cli_writer)?;
+
+        // we should write back input that was there before writing
+        if cli_writer.is_dirty() {
+            self.writer.write_str(codes::CRLF)?;
+        }
+        if self.scroll_region.is_some() {
+            return Ok(self.writer.flush_bytes(codes::RESTORE_CURSOR)?);
+        }
+        if self.prompt_suspended {
+            self.prompt_hidden = true;
+            return Ok(self.writer.flush()?);
+        }
+        Ok(self.write_prompt()?)
+    }
+
+    /// Write all bytes, queued from interrupt context, above the prompt.
+    /// Does nothing if queue is empty (or output is paused, see `is_output_paused`),
+    /// so can be called on each iteration of main loop
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn write_queued<const N: usize>(
+        &mut self,
+        reader: &mut InterruptReader<'_, N>,
+    ) -> Result<(), CliError<E>> {
+        if reader.is_empty() || self.is_output_paused() {
+            return Ok(());
+        }
+        self.write(|writer| {
+            loop {
+                let bytes = reader.peek();
+                if bytes.is_empty() {
+                    break;
+                }
+                let len = bytes.len();
+                // newlines are translated, but the rest is written as is
+                for (i, line) in bytes.split(|&b| b == codes::LINE_FEED).enumerate() {
+                    if i > 0 {
+                        writer.write_str("\n")?;
+                    }
+                    writer.write_raw(line)?;
+                }
+                reader.consume(len);
+            }
+            Ok(())
+        })
+    }
+
+    /// Stop writing prompt back after each call to `write`,
+    /// so burst of writes (like streamed logs) doesn't cause flicker.
+    /// Prompt is written back by `resume_prompt`
+    /// (or when next input byte is processed)
+    pub fn suspend_prompt(&mut self) {
+        self.prompt_suspended = true;
+    }
+
+    /// Write prompt (and current input) back if it was cleared
+    /// while prompt was suspended
+    pub fn resume_prompt(&mut self) -> Result<(), E> {
+        self.prompt_suspended = false;
+        if self.prompt_hidden {
+            self.write_prompt()?;
+        }
+        Ok(())
+    }
+
+    /// Write banner (if any) on its own lines
+    fn write_banner(&mut self) -> Result<(), E> {
+        if self.banner.is_empty() {
+            return Ok(());
+        }
+        let mut writer = Writer::tapped(&mut self.writer);
+        writer.write_str(self.banner)?;
+        if writer.is_dirty() {
+            self.writer.write_str(codes::CRLF)?;
+        }
+        Ok(())
+    }
+
+    /// Write prompt together with its suffix
+    /// (or password prompt when cli is locked)
+    fn write_prompt_text(&mut self) -> Result<(), E> {
+        if let Some(auth) = self.auth.filter(|_| self.locked) {
+            return self.writer.write_str(auth.prompt);
+        }
+        #[cfg(feature = "history")]
+        if self.search.is_some() {
+            return self.writer.write_str(SEARCH_PROMPT);
+        }
+        self.writer.write_str(self.active_prompt().text())?;
+        self.writer.write_str(self.prompt_suffix)
+    }
+
+    fn write_prompt(&mut self) -> Result<(), E> {
+        self.prompt_hidden = false;
+        self.end_output()?;
+        if self.paging.is_some() {
+            return self.writer.flush_str(MORE_PROMPT);
+        }
+        self.write_prompt_text()?;
+        self.write_input()?;
+        self.writer.flush()
+    }
+
+    /// Write current input (hidden if it's a password)
+    /// or search query together with matched history element
+    fn write_input(&mut self) -> Result<(), E> {
+        let Some(editor) = self.editor.take() else {
+            return Ok(());
+        };
+        #[cfg(feature = "history")]
+        let res = match self.search {
+            Some(search) => self.write_search(editor.text(), search.matched),
+            None => self.write_input_text(editor.text()),
+        };
+        #[cfg(not(feature = "history"))]
+        let res = self.write_input_text(editor.text());
+        self.editor = Some(editor);
+        res
+    }
+
+    /// Write given input text according to current echo mode
+    fn write_input_text(&mut self, text: &str) -> Result<(), E> {
+        match self.echo_mode() {
+            EchoMode::Normal => self.writer.write_str(text),
+            EchoMode::Hidden => Ok(()),
+            EchoMode::Masked(mask) => {
+                let mut buf = [0; 4];
+                let mask = mask.encode_utf8(&mut buf);
+                for _ in text.chars() {
+                    self.writer.write_str(mask)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Echo mode of current input (password is always hidden or masked)
+    fn echo_mode(&self) -> EchoMode {
+        match (self.locked, self.secret_mask) {
+            (false, _) => self.echo,
+            (true, Some(mask)) => EchoMode::Masked(mask),
+            (true, None) => EchoMode::Hidden,
+        }
+    }
+
+    /// Whether current input is not echoed as is
+    fn is_secret(&self) -> bool {
+        self.echo_mode() != EchoMode::Normal
+    }
+
+    /// Whether history is being searched (see `ControlInput::ReverseSearch`)
+    fn is_searching(&self) -> bool {
+        #[cfg(feature = "history")]
+        {
+            self.search.is_some()
+        }
+        #[cfg(not(feature = "history"))]
+        {
+            false
+        }
+    }
+
+    /// Prepare for writing output lines. Prompt is cleared
+    /// or (when prompt is pinned) cursor is moved to the bottom of scroll region
+    fn begin_output(&mut self) -> Result<(), E> {
+        if let Some(rows) = self.scroll_region {
+            self.writer
+                .write_csi(&[rows - 1, 1], codes::CURSOR_POSITION)
+        } else {
+            self.clear_line(true)
+        }
+    }
+
+    /// Finish writing output lines, so prompt can be written after it
+    fn end_output(&mut self) -> Result<(), E> {
+        if let Some(rows) = self.scroll_region {
+            self.writer.write_csi(&[rows, 1], codes::CURSOR_POSITION)?;
+            self.writer.write_bytes(codes::CLEAR_LINE)?;
+        }
+        Ok(())
+    }
+
+    fn clear_line(&mut self, clear_prompt: bool) -> Result<(), E> {
+        self.writer.write_str("\r")?;
+        self.writer.write_bytes(codes::CLEAR_LINE)?;
+
+        if !clear_prompt {
+            self.write_prompt_text()?;
+        }
+
+        self.writer.flush()
+    }
+
+    fn on_text_input(&mut self, editor: &mut Editor<CommandBuffer>, text: &str) -> Result<(), E> {
+        if self.paste_start.is_some() {
+            // pasted text is shown at once when paste ends
+            editor.insert(text);
+            return Ok(());
+        }
+        let is_inside = editor.cursor() < editor.len();
+        if let Some(c) = editor.insert(text) {
+            if is_inside {
+                // text is one char, possibly preceded by U+FFFD
+                let width = c.chars().map(width::char_width).sum();
+                self.write_repeated(codes::INSERT_CHAR, width)?;
+            }
+            self.writer.flush_str(c)?;
+        }
+        Ok(())
+    }
+
+    fn insert_tab(&mut self, editor: &mut Editor<CommandBuffer>) -> Result<(), E> {
+        match self.tab_insert {
+            TabInsert::Nothing => {}
+            TabInsert::Tab => {
+                let is_inside = editor.cursor() < editor.len();
+                if editor.insert("\t").is_some() {
+                    if is_inside {
+                        self.writer.write_bytes(codes::INSERT_CHAR)?;
+                    }
+                    self.writer.flush_str(" ")?;
+                }
+            }
+            TabInsert::Spaces(count) => {
+                for _ in 0..count {
+                    self.on_text_input(editor, " ")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_control_input<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        control: ControlInput,
+        processor: &mut P,
+    ) -> Result<(), E> {
+        match control {
+            ControlInput::Enter => {
+                if self.scroll_region.is_some() {
+                    // entered command is kept above pinned prompt
+                    self.begin_output()?;
+                    self.write_prompt_text()?;
+                    self.write_input_text(editor.text())?;
+                }
+                self.writer.write_str(codes::CRLF)?;
+
+                if !self.is_secret() {
+                    self.process_inline_event(CliEvent::LineReceived(editor.text()), processor)?;
+                }
+
+                // secret input is not stored in history
+                #[cfg(feature = "history")]
+                let pushed = !self.is_secret() && self.history.push(editor.text());
+                let text = editor.text_mut();
+
+                self.parse_failed = false;
+                let mut start = 0;
+                let more = loop {
+                    let end = text
+                        .get(start..)
+                        .filter(|_| self.command_separator)
+                        .and_then(Tokens::command_end)
+                        .map_or(text.len(), |end| start + end);
+                    // input is tokenized in place only when tokens can't be borrowed
+                    // (quoted tokens contain escaped chars), then line is not available
+                    let segment = text.get_mut(start..end).unwrap_or_default();
+                    let (tokens, line) = match Tokens::from_line(segment) {
+                        Some(tokens) => (tokens, Some(&*segment)),
+                        None => (Tokens::new(segment), None),
+                    };
+                    #[cfg(feature = "async")]
+                    let len = tokens.clone().into_raw().len();
+                    let more = self.process_input::<C, _>(tokens, line, processor)?;
+                    #[cfg(feature = "async")]
+                    if let Some(deferred) = self.deferred.as_mut() {
+                        // tokenized command is kept in editor until async processor is done with it
+                        deferred.command = Tokenized {
+                            borrowed: line.is_some(),
+                            start,
+                            end,
+                            len,
+                        };
+                        #[cfg(feature = "history")]
+                        {
+                            deferred.pushed = pushed;
+                        }
+                        // commands after deferred one are not processed
+                        break false;
+                    }
+                    // commands after paged one are not processed
+                    if more || self.locked || end == text.len() {
+                        break more;
+                    }
+                    start = end + 1;
+                };
+                #[cfg(feature = "history")]
+                if pushed && self.parse_failed && self.history_valid_only {
+                    // command is pushed before processing since tokenization
+                    // modifies input in place, so invalid one is removed afterwards
+                    self.history.remove_newest();
+                }
+                #[cfg(feature = "async")]
+                if self.deferred.is_some() {
+                    return Ok(());
+                }
+                self.end_output()?;
+                editor.clear();
+                if more {
+                    return self.writer.flush_str(MORE_PROMPT);
+                }
+
+                self.write_prompt_text()?;
+                self.writer.flush()?;
+            }
+            ControlInput::Cancel => {
+                // abandoned input is kept on screen, followed by ^C
+                if self.scroll_region.is_some() {
+                    self.begin_output()?;
+                } else {
+                    self.writer.write_str("\r")?;
+                }
+                self.write_prompt_text()?;
+                self.writer.write_str(editor.text())?;
+                self.writer.write_str("^C")?;
+                self.writer.write_str(codes::CRLF)?;
+                self.end_output()?;
+
+                editor.clear();
+                // Ctrl+C also leaves bracketed paste
+                self.paste_start = None;
+                #[cfg(feature = "history")]
+                self.history.deselect();
+
+                self.write_prompt_text()?;
+                self.writer.flush()?;
+            }
+            ControlInput::Tab => {
+                // inside quoted argument tab is a part of the payload
+                let quoted = Tokens::is_quoted(editor.text_range(..editor.cursor()));
+                if quoted || cfg!(not(feature = "autocomplete")) {
+                    self.insert_tab(editor)?;
+                } else {
+                    #[cfg(feature = "autocomplete")]
+                    self.process_autocomplete::<C>(editor)?;
+                }
+            }
+            ControlInput::Backspace => {
+                if editor.move_left() {
+                    let width = editor.width_at_cursor();
+                    editor.remove();
+                    self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+                    self.write_repeated(codes::DELETE_CHAR, width)?;
+                    self.writer.flush()?;
+                }
+            }
+            ControlInput::BackWord => self.navigate_input(editor, NavigateInput::BackWord)?,
+            ControlInput::DeleteToEnd => {
+                let start = editor.cursor();
+                editor.move_to_end();
+                editor.remove_range(start, editor.len());
+                self.writer.flush_bytes(codes::CLEAR_LINE_END)?;
+            }
+            ControlInput::DeleteToStart => {
+                let end = editor.cursor();
+                let width = editor.move_to_start();
+                self.delete_before_cursor(editor, end, width)?;
+            }
+            ControlInput::DeleteWord => {
+                let end = editor.cursor();
+                let width = editor.move_word_left();
+                self.delete_before_cursor(editor, end, width)?;
+            }
+            ControlInput::Delete => {
+                if editor.cursor() < editor.len() {
+                    let width = editor.width_at_cursor();
+                    editor.remove();
+                    self.write_repeated(codes::DELETE_CHAR, width)?;
+                    self.writer.flush()?;
+                }
+            }
+            ControlInput::Down =>
+            {
+                #[cfg(feature = "history")]
+                self.navigate_history(editor, NavigateHistory::Newer)?
+            }
+            ControlInput::Up =>
+            {
+                #[cfg(feature = "history")]
+                self.navigate_history(editor, NavigateHistory::Older)?
+            }
+            ControlInput::End => self.navigate_input(editor, NavigateInput::End)?,
+            ControlInput::Forward => self.navigate_input(editor, NavigateInput::Forward)?,
+            ControlInput::ForwardWord => self.navigate_input(editor, NavigateInput::ForwardWord)?,
+            ControlInput::FunctionKey(n) => {
+                self.process_event(editor, CliEvent::FunctionKey(n), processor)?
+            }
+            ControlInput::Home => self.navigate_input(editor, NavigateInput::Start)?,
+            ControlInput::PasteEnd => self.end_paste(editor)?,
+            ControlInput::PasteStart => self.paste_start = Some(editor.cursor()),
+            ControlInput::ReverseSearch =>
+            {
+                #[cfg(feature = "history")]
+                self.start_search(editor)?
+            }
+            ControlInput::Back => self.navigate_input(editor, NavigateInput::Backward)?,
+            ControlInput::XOff | ControlInput::XOn => self.on_flow_control(editor, control)?,
+        }
+
+        Ok(())
+    }
+
+    /// Process input, that is not echoed as is (password or input with changed echo mode).
+    ///
+    /// Only appending chars, backspace and cancel are supported,
+    /// so hidden input is never edited in the middle
+    fn on_secret_input<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        input: Input<'_>,
+        processor: &mut P,
+    ) -> Result<(), E> {
+        match input {
+            Input::Char(text) => {
+                if let Some(inserted) = editor.insert(text) {
+                    self.write_input_text(inserted)?;
+                    self.writer.flush()?;
+                }
+            }
+            Input::Control(ControlInput::Backspace) => {
+                if editor.move_left() {
+                    editor.remove();
+                    let width = match self.echo_mode() {
+                        EchoMode::Masked(mask) => width::char_width(mask),
+                        _ => 0,
+                    };
+                    self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+                    self.write_repeated(codes::DELETE_CHAR, width)?;
+                    self.writer.flush()?;
+                }
+            }
+            Input::Control(ControlInput::Enter) if !self.locked => {
+                self.on_control_input::<C, _>(editor, ControlInput::Enter, processor)?;
+            }
+            Input::Control(ControlInput::Enter) => {
+                if self.scroll_region.is_some() {
+                    // entered (hidden) password is kept above pinned prompt
+                    self.begin_output()?;
+                    self.write_prompt_text()?;
+                    self.write_input_text(editor.text())?;
+                }
+                self.writer.write_str(codes::CRLF)?;
+
+                let accepted = self
+                    .auth
+                    .map(|auth| (auth.check)(editor.text()))
+                    .unwrap_or(true);
+                editor.clear();
+                if accepted {
+                    self.locked = false;
+                } else {
+                    if self.bell_on_error {
+                        self.writer.write_bytes(&[codes::BELL])?;
+                    }
+                    self.writer.write_str("error: wrong password")?;
+                    self.writer.write_str(codes::CRLF)?;
+                }
+                self.end_output()?;
+                self.write_prompt_text()?;
+                self.writer.flush()?;
+            }
+            Input::Control(ControlInput::Cancel) => {
+                if self.scroll_region.is_some() {
+                    self.begin_output()?;
+                } else {
+                    self.writer.write_str("\r")?;
+                }
+                self.write_prompt_text()?;
+                self.write_input_text(editor.text())?;
+                self.writer.write_str("^C")?;
+                self.writer.write_str(codes::CRLF)?;
+                self.end_output()?;
+
+                editor.clear();
+                self.write_prompt_text()?;
+                self.writer.flush()?;
+            }
+            Input::Control(_) => {}
+        }
+        Ok(())
+    }
+
+    fn on_flow_control(
+        &mut self,
+        editor: &Editor<CommandBuffer>,
+        control: ControlInput,
+    ) -> Result<(), E> {
+        let paused = control == ControlInput::XOff;
+        if !self.flow_control || self.writer.paused == paused {
+            return Ok(());
+        }
+        self.writer.paused = paused;
+        if paused || self.prompt_hidden {
+            return Ok(());
+        }
+
+        // echo was discarded while output was paused, so whole line is written again
+        self.begin_output()?;
+        self.end_output()?;
+        if self.paging.is_some() {
+            return self.writer.flush_str(MORE_PROMPT);
+        }
+        #[cfg(feature = "history")]
+        if self.search.is_some() {
+            return self.write_search_line(editor);
+        }
+        self.write_prompt_text()?;
+        self.write_input_text(editor.text())?;
+        let after_cursor = editor.text_range(editor.cursor()..);
+        let width = match self.echo_mode() {
+            EchoMode::Normal => after_cursor.chars().map(width::char_width).sum(),
+            EchoMode::Hidden => 0,
+            EchoMode::Masked(mask) => width::char_width(mask) * after_cursor.chars().count(),
+        };
+        self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+        if self.paste_start.is_some() {
+            // pasted text is already shown
+            self.paste_start = Some(editor.cursor());
+        }
+        self.writer.flush()
+    }
+
+    fn on_paging_input(&mut self, paging: Paging<W, E>, input: Input<'_>) -> Result<(), E> {
+        self.begin_output()?;
+
+        if !matches!(
+            input,
+            Input::Char("q") | Input::Control(ControlInput::Cancel)
+        ) {
+            // next page is written by the same source, command is not processed again
+            let mut writer = self.make_writer();
+            let mut pager = Pager::new(&mut writer, paging.lines);
+            (paging.source)(&mut pager, paging.context, paging.next)?;
+            let more = pager.has_more();
+            if writer.is_dirty() {
+                self.writer.write_str(codes::CRLF)?;
+            }
+            self.end_output()?;
+            if more {
+                self.paging = Some(Paging {
+                    next: paging.next + paging.lines,
+                    ..paging
+                });
+                return self.writer.flush_str(MORE_PROMPT);
+            }
+        } else {
+            self.end_output()?;
+        }
+
+        self.write_prompt_text()?;
+        self.writer.flush()
+    }
+
+    fn navigate_input(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        dir: NavigateInput,
+    ) -> Result<(), E> {
+        match dir {
+            NavigateInput::Backward if editor.move_left() => {
+                let width = editor.width_at_cursor();
+                self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+            }
+            NavigateInput::Forward => {
+                let width = editor.width_at_cursor();
+                if editor.move_right() {
+                    self.write_repeated(codes::CURSOR_FORWARD, width)?;
+                }
+            }
+            NavigateInput::BackWord => {
+                let width = editor.move_word_left();
+                self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+            }
+            NavigateInput::End => {
+                let width = editor.move_to_end();
+                self.write_repeated(codes::CURSOR_FORWARD, width)?;
+            }
+            NavigateInput::ForwardWord => {
+                let width = editor.move_word_right();
+                self.write_repeated(codes::CURSOR_FORWARD, width)?;
+            }
+            NavigateInput::Start => {
+                let width = editor.move_to_start();
+                self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+            }
+            _ => return Ok(()),
+        }
+        self.writer.flush()
+    }
+
+    /// Show text, that was inserted by bracketed paste, with a single write
+    fn end_paste(&mut self, editor: &Editor<CommandBuffer>) -> Result<(), E> {
+        let Some(start) = self.paste_start.take() else {
+            return Ok(());
+        };
+        if editor.cursor() == editor.len() {
+            // text was pasted at the end, so it's just appended
+            return self.writer.flush_str(editor.text_range(start..));
+        }
+        self.writer.write_str("\r")?;
+        self.write_prompt_text()?;
+        self.writer.write_str(editor.text())?;
+        self.writer.write_bytes(codes::CLEAR_LINE_END)?;
+        let width = editor
+            .text_range(editor.cursor()..)
+            .chars()
+            .map(width::char_width)
+            .sum();
+        self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+        self.writer.flush()
+    }
+
+    /// Remove input from cursor (which was already moved back by given width)
+    /// to given end
+    fn delete_before_cursor(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        end: usize,
+        width: usize,
+    ) -> Result<(), E> {
+        editor.remove_range(editor.cursor(), end);
+        self.write_repeated(codes::CURSOR_BACKWARD, width)?;
+        self.write_repeated(codes::DELETE_CHAR, width)?;
+        self.writer.flush()
+    }
+
+    fn write_repeated(&mut self, bytes: &[u8], count: usize) -> Result<(), E> {
+        for _ in 0..count {
+            self.writer.write_bytes(bytes)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "history")]
+    fn navigate_history(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        dir: NavigateHistory,
+    ) -> Result<(), E> {
+        let history_elem = match dir {
+            NavigateHistory::Older => {
+                if !self.history.is_selected() {
+                    // input that was being edited is restored when leaving history
+                    self.history.stash(editor.text());
+                }
+                self.history.next_older()
+            }
+            // current input is kept when no element was selected
+            NavigateHistory::Newer if self.history.is_selected() => {
+                match self.history.next_newer() {
+                    Some(element) => Some(element),
+                    None => Some(self.history.take_stashed()),
+                }
+            }
+            NavigateHistory::Newer => None,
+        };
+        if let Some(element) = history_elem {
+            editor.clear();
+            editor.insert(element);
+
+            // line is overwritten in place and only the rest of previous text
+            // is cleared, so line is not blanked before redraw
+            // and whole redraw is sent with a single flush
+            self.writer.write_str("\r")?;
+            self.write_prompt_text()?;
+            self.writer.write_str(editor.text())?;
+            self.writer.flush_bytes(codes::CLEAR_LINE_END)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "history")]
+    fn start_search(&mut self, editor: &mut Editor<CommandBuffer>) -> Result<(), E> {
+        // input that was being edited is restored when search is cancelled
+        self.history.deselect();
+        self.history.stash(editor.text());
+        editor.clear();
+        self.search = Some(Search { matched: None });
+        self.write_search_line(editor)
+    }
+
+    /// Process input while searching in history.
+    ///
+    /// Chars and backspace change query, Ctrl+R finds older match and Ctrl+C
+    /// cancels search. Any other control input leaves search with matched element
+    /// in editor and then is processed as usual (so Enter sends it)
+    #[cfg(feature = "history")]
+    fn on_search_input<C: Autocomplete + Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        editor: &mut Editor<CommandBuffer>,
+        input: Input<'_>,
+        processor: &mut P,
+    ) -> Result<(), E> {
+        let matched = self.search.and_then(|search| search.matched);
+        match input {
+            Input::Char(text) => {
+                editor.insert(text);
+                self.search = Some(Search {
+                    matched: self.history.search_older(editor.text(), usize::MAX),
+                });
+            }
+            Input::Control(ControlInput::Backspace) => {
+                if editor.move_left() {
+                    editor.remove();
+                }
+                self.search = Some(Search {
+                    matched: self.history.search_older(editor.text(), usize::MAX),
+                });
+            }
+            Input::Control(ControlInput::ReverseSearch) => {
+                let before = matched.unwrap_or(usize::MAX);
+                if let Some(older) = self.history.search_older(editor.text(), before) {
+                    self.search = Some(Search {
+                        matched: Some(older),
+                    });
+                }
+            }
+            Input::Control(ControlInput::Cancel) => {
+                self.search = None;
+                editor.clear();
+                editor.insert(self.history.take_stashed());
+            }
+            Input::Control(control) => {
+                self.search = None;
+                editor.clear();
+                if let Some(matched) = matched {
+                    editor.insert(self.history.element_at(matched));
+                }
+                self.history.deselect();
+                self.write_search_line(editor)?;
+                return self.on_control_input::<C, _>(editor, control, processor);
+            }
+        }
+        self.write_search_line(editor)
+    }
+
+    /// Overwrite current line with prompt and input (or search query with match)
+    #[cfg(feature = "history")]
+    fn write_search_line(&mut self, editor: &Editor<CommandBuffer>) -> Result<(), E> {
+        self.writer.write_str("\r")?;
+        self.write_prompt_text()?;
+        match self.search {
+            Some(search) => self.write_search(editor.text(), search.matched)?,
+            None => self.writer.write_str(editor.text())?,
+        }
+        self.writer.flush_bytes(codes::CLEAR_LINE_END)
+    }
+
+    /// Write search query and matched element of history
+    #[cfg(feature = "history")]
+    fn write_search(&mut self, query: &str, matched: Option<usize>) -> Result<(), E> {
+        self.writer.write_str(query)?;
+        self.writer.write_str("': ")?;
+        if let Some(matched) = matched {
+            let element = self.history.element_at(matched);
+            self.writer.write_str(element)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn process_autocomplete<C: Autocomplete>(
+        &mut self,
         editor: &mut Editor<CommandBuffer>,
     ) -> Result<(), E> {
         let initial_cursor = editor.cursor();
         editor.autocompletion(|request, autocompletion| {
             C::autocomplete(request.clone(), autocompletion);
+            #[cfg(feature = "builtins")]
+            let user_completed = autocompletion.autocompleted().is_some();
             match request {
-                Request::CommandName(name) if "help".starts_with(name) => {
+                Request::CommandName(name) if self.help_command && "help".starts_with(name) => {
                     // SAFETY: "help" starts with name, so name cannot be longer
                     let autocompleted = unsafe { "help".get_unchecked(name.len()..) };
                     autocompletion.merge_autocompletion(autocompleted)
                 }
                 _ => {}
             }
+            // user commands shadow built-ins with the same prefix
+            #[cfg(feature = "builtins")]
+            if let (Request::CommandName(name), false) = (&request, user_completed) {
+                let sleep = self.delay.is_some().then_some(builtins::SLEEP);
+                for builtin in Some(builtins::ECHO).into_iter().chain(sleep) {
+                    if let Some(autocompleted) = builtin.strip_prefix(name) {
+                        autocompletion.merge_autocompletion(autocompleted)
+                    }
+                }
+            }
+            if let Some(hook) = self.autocomplete_hook {
+                hook(request, autocompletion);
+            }
         });
         if editor.cursor() > initial_cursor {
             let autocompleted = editor.text_range(initial_cursor..);
@@ -355,98 +1944,357 @@ where
         Ok(())
     }
 
-    fn process_command<P: CommandProcessor<W, E>>(
+    /// Returns true if command has more output on next page
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn process_command<C: Help, P: CommandProcessor<W, E>>(
         &mut self,
         command: RawCommand<'_>,
         handler: &mut P,
-    ) -> Result<(), E> {
-        let cli_writer = Writer::new(&mut self.writer);
-        let mut handle = CliHandle::new(cli_writer);
+    ) -> Result<bool, E> {
+        let history_usage = self.history_usage();
+        let mut handle = CliHandle::new(self.make_writer(), history_usage);
 
-        let res = handler.process(&mut handle, command);
+        let res = handler.process(&mut handle, command.clone());
 
-        if let Some(prompt) = handle.new_prompt {
-            self.prompt = prompt;
-        }
-        if handle.writer.is_dirty() {
-            self.writer.write_str(codes::CRLF)?;
+        let handle = handle.into_changes();
+        self.apply_handle(&handle)?;
+        #[cfg(feature = "async")]
+        if handle.defer {
+            self.deferred = Some(Deferred::default());
         }
         self.writer.flush()?;
 
+        let processed = self.process_result::<C, _>(res, command, handler)?;
+        // paged output is kept until key is pressed
+        self.paging = handle.paging.filter(|_| processed && !self.locked);
+        Ok(self.paging.is_some())
+    }
+
+    /// Process command, that was deferred to async processor
+    #[cfg(feature = "async")]
+    pub(crate) async fn process_deferred<C: Help, P: AsyncCommandProcessor<W, E>>(
+        &mut self,
+        processor: &mut P,
+    ) -> Result<(), E> {
+        let Some(deferred) = self.deferred.take() else {
+            return Ok(());
+        };
+        let Some(mut editor) = self.editor.take() else {
+            return Ok(());
+        };
+
+        let result = self
+            .process_deferred_command::<C, _>(&editor, deferred, processor)
+            .await;
+
+        editor.clear();
+        self.editor = Some(editor);
+        result?;
+
+        self.end_output()?;
+        self.write_prompt_text()?;
+        self.writer.flush()
+    }
+
+    #[cfg(feature = "async")]
+    async fn process_deferred_command<C: Help, P: AsyncCommandProcessor<W, E>>(
+        &mut self,
+        editor: &Editor<CommandBuffer>,
+        deferred: Deferred,
+        processor: &mut P,
+    ) -> Result<(), E> {
+        // SAFETY: editor still contains command, that was tokenized
+        let tokens = unsafe { deferred.command.tokens(editor.text()) };
+        let Some(command) = RawCommand::from_tokens(&tokens) else {
+            return Ok(());
+        };
+        let command = command.with_line(deferred.command.line(editor.text()));
+
+        let history_usage = self.history_usage();
+        let mut handle = CliHandle::new(self.make_writer(), history_usage);
+
+        let res = processor.process(&mut handle, command.clone()).await;
+
+        let handle = handle.into_changes();
+        self.apply_handle(&handle)?;
+        self.writer.flush()?;
+
+        self.process_result::<C, _>(res, command, &mut Deferring::new(processor))?;
+        #[cfg(feature = "history")]
+        if deferred.pushed && self.parse_failed && self.history_valid_only {
+            self.history.remove_newest();
+        }
+        Ok(())
+    }
+
+    /// Report error returned by command processor.
+    /// Returns true if command was processed successfully
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn process_result<'a, C: Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        res: Result<(), ProcessError<'a, E>>,
+        command: RawCommand<'a>,
+        handler: &mut P,
+    ) -> Result<bool, E> {
         match res {
-            Err(ProcessError::ParseError(err)) => self.process_error(err),
+            Err(ProcessError::ParseError(err)) => {
+                // user commands take precedence, so built-ins are tried only for unknown commands
+                #[cfg(feature = "builtins")]
+                if matches!(err, ParseError::UnknownCommand) {
+                    if let Some(builtin) = Builtin::parse(&command, self.delay.is_some()) {
+                        self.process_builtin(builtin, handler)?;
+                        return Ok(false);
+                    }
+                }
+                if !self.process_inline_event(CliEvent::InvalidCommand(command.clone()), handler)? {
+                    // command was handled by application
+                    return Ok(false);
+                }
+                #[cfg(feature = "help")]
+                let show_help = matches!(
+                    err,
+                    ParseError::MissingRequiredArgument { .. }
+                        | ParseError::MissingSubcommand { .. }
+                        | ParseError::UnexpectedArgument { .. }
+                        | ParseError::UnexpectedLongOption { .. }
+                        | ParseError::UnexpectedShortOption { .. }
+                );
+                self.process_error(err, handler)?;
+                #[cfg(feature = "help")]
+                if show_help && self.help_on_error {
+                    self.process_help::<C, _>(HelpRequest::Command(command), handler)?;
+                }
+                Ok(false)
+            }
             Err(ProcessError::WriteError(err)) => Err(err),
-            Ok(()) => Ok(()),
+            Ok(()) => Ok(true),
         }
     }
 
+    /// Returns true if processed command has more output on next page
     #[allow(clippy::extra_unused_type_parameters)]
     fn process_input<C: Help, P: CommandProcessor<W, E>>(
         &mut self,
         tokens: Tokens<'_>,
+        line: Option<&str>,
         handler: &mut P,
-    ) -> Result<(), E> {
+    ) -> Result<bool, E> {
+        if let Some(max) = self.max_tokens {
+            // only first token after the limit is checked,
+            // so even very long input is rejected quickly
+            if tokens.iter().nth(max).is_some() {
+                self.process_error(ParseError::TooManyTokens { max }, handler)?;
+                return Ok(false);
+            }
+        }
+
         if let Some(command) = RawCommand::from_tokens(&tokens) {
+            let command = command.with_line(line);
             #[cfg(feature = "help")]
-            if let Some(request) = HelpRequest::from_command(&command) {
-                return self.process_help::<C>(request);
+            let request = if self.help_command && command.name() == "help" {
+                HelpRequest::from_command(&command)
+            } else {
+                HelpRequest::from_options(&command, self.help_short)
+            };
+            #[cfg(feature = "help")]
+            if let Some(request) = request {
+                if self.process_inline_event(CliEvent::Help(request.clone()), handler)? {
+                    self.process_help::<C, _>(request, handler)?;
+                }
+                return Ok(false);
             }
 
-            self.process_command(command, handler)?;
+            if self.trace_commands {
+                self.process_trace(&command)?;
+            }
+
+            return self.process_command::<C, _>(command, handler);
         };
 
-        Ok(())
+        Ok(false)
     }
 
-    fn process_error(&mut self, error: ParseError<'_>) -> Result<(), E> {
-        self.writer.write_str("error: ")?;
-        match error {
-            ParseError::MissingRequiredArgument { name } => {
-                self.writer.write_str("missing required argument: ")?;
-                self.writer.write_str(name)?;
-            }
-            ParseError::ParseValueError { value, expected } => {
-                self.writer.write_str("failed to parse '")?;
-                self.writer.write_str(value)?;
-                self.writer.write_str("', expected ")?;
-                self.writer.write_str(expected)?;
-            }
-            ParseError::UnexpectedArgument { value } => {
-                self.writer.write_str("unexpected argument: ")?;
-                self.writer.write_str(value)?;
-            }
-            ParseError::UnexpectedLongOption { name } => {
-                self.writer.write_str("unexpected option: -")?;
-                self.writer.write_str("-")?;
-                self.writer.write_str(name)?;
+    #[cfg(feature = "builtins")]
+    fn process_builtin<P: CommandProcessor<W, E>>(
+        &mut self,
+        builtin: Result<Builtin<'_>, ParseError<'_>>,
+        handler: &mut P,
+    ) -> Result<(), E> {
+        match builtin {
+            Ok(Builtin::Echo(tokens)) => {
+                for (i, token) in tokens.enumerate() {
+                    if i > 0 {
+                        self.writer.write_str(" ")?;
+                    }
+                    self.writer.write_str(token)?;
+                }
+                self.writer.flush_str(codes::CRLF)
             }
-            ParseError::UnexpectedShortOption { name } => {
-                let mut buf = [0; 4];
-                let buf = utils::encode_utf8(name, &mut buf);
-                self.writer.write_str("unexpected option: -")?;
-                self.writer.write_str(buf)?;
+            Ok(Builtin::Sleep(ms)) => {
+                if let Some(delay) = self.delay {
+                    delay(ms);
+                }
+                Ok(())
             }
-            ParseError::UnknownCommand => {
-                self.writer.write_str("unknown command")?;
+            Err(err) => self.process_error(err, handler),
+        }
+    }
+
+    fn process_trace(&mut self, command: &RawCommand<'_>) -> Result<(), E> {
+        self.write_trace("name", command.name())?;
+        for arg in command.args().args() {
+            match arg {
+                Arg::DoubleDash => {
+                    self.writer.write_str("trace: --")?;
+                    self.writer.write_str(codes::CRLF)?;
+                }
+                Arg::LongOption(name) => self.write_trace("long", name)?,
+                Arg::ShortOption(name) => {
+                    let mut buf = [0; 4];
+                    let name = utils::encode_utf8(name, &mut buf);
+                    self.write_trace("short", name)?
+                }
+                Arg::Value(value) => self.write_trace("value", value)?,
             }
         }
-        self.writer.flush_str(codes::CRLF)
+        self.writer.flush()
+    }
+
+    fn write_trace(&mut self, key: &str, value: &str) -> Result<(), E> {
+        self.writer.write_str("trace: ")?;
+        self.writer.write_str(key)?;
+        // quotes show exact bounds of each token
+        self.writer.write_str("=\"")?;
+        self.writer.write_str(value)?;
+        self.writer.write_str("\"")?;
+        self.writer.write_str(codes::CRLF)
+    }
+
+    fn process_event<P: CommandProcessor<W, E>>(
+        &mut self,
+        editor: &Editor<CommandBuffer>,
+        event: CliEvent<'_>,
+        handler: &mut P,
+    ) -> Result<(), E> {
+        self.begin_output()?;
+
+        let history_usage = self.history_usage();
+        let mut handle = CliHandle::new(self.make_writer(), history_usage);
+
+        let res = handler.on_event(&mut handle, event);
+
+        let handle = handle.into_changes();
+        self.apply_handle(&handle)?;
+        res?;
+
+        // write back input that was there before event
+        self.end_output()?;
+        self.write_prompt_text()?;
+        self.writer.flush_str(editor.text())
+    }
+
+    /// Process event, that is emitted while input line is processed
+    /// (so prompt is not written back after it).
+    /// Returns true if built-in output (help or error) should be written
+    fn process_inline_event<P: CommandProcessor<W, E>>(
+        &mut self,
+        event: CliEvent<'_>,
+        handler: &mut P,
+    ) -> Result<bool, E> {
+        let history_usage = self.history_usage();
+        let mut handle = CliHandle::new(self.make_writer(), history_usage);
+
+        let res = handler.on_event(&mut handle, event);
+
+        let handle = handle.into_changes();
+        self.apply_handle(&handle)?;
+        res?;
+        self.writer.flush()?;
+
+        Ok(!handle.skip_builtin)
+    }
+
+    /// Writer for output of processor (or of external code),
+    /// configured the same way as cli
+    fn make_writer(&mut self) -> Writer<'_, W, E> {
+        Writer::tapped(&mut self.writer)
+            .with_colors(self.colors)
+            .with_help_short(self.help_short)
+            .with_machine_mode(self.machine_mode)
+    }
+
+    /// Apply changes, that were requested through handle, and end output
+    /// of processor with new line (if it wasn't ended already)
+    fn apply_handle(&mut self, handle: &HandleChanges<W, E>) -> Result<(), E> {
+        if let Some(prompt) = handle.prompt {
+            self.prompt = prompt;
+        }
+        if let Some(degraded) = handle.degraded {
+            self.degraded = degraded;
+        }
+        if let Some(suffix) = handle.prompt_suffix {
+            self.prompt_suffix = suffix;
+        }
+        if let Some(echo) = handle.echo {
+            self.echo = echo;
+        }
+        if handle.lock && self.auth.is_some() {
+            self.locked = true;
+        }
+        self.machine_mode = handle.machine_mode;
+        if handle.dirty {
+            self.writer.write_str(codes::CRLF)?;
+        }
+        Ok(())
+    }
+
+    fn process_error<P: CommandProcessor<W, E>>(
+        &mut self,
+        error: ParseError<'_>,
+        handler: &mut P,
+    ) -> Result<(), E> {
+        self.parse_failed = true;
+        if self.parse_error_events {
+            self.process_inline_event(CliEvent::ParseError(error), handler)?;
+            return Ok(());
+        }
+        self.write_error(error)
+    }
+
+    fn write_error(&mut self, error: ParseError<'_>) -> Result<(), E> {
+        if self.bell_on_error {
+            self.writer.write_bytes(&[codes::BELL])?;
+        }
+        if let Some(handler) = self.error_handler {
+            write_handled_error(DynWriter::erased(&mut self.writer), handler, error)?;
+            return self.writer.flush();
+        }
+        write_parse_error(&mut self.writer, error)
     }
 
     #[cfg(feature = "help")]
-    fn process_help<C: Help>(&mut self, request: HelpRequest<'_>) -> Result<(), E> {
-        let mut writer = Writer::new(&mut self.writer);
+    fn process_help<C: Help, P: CommandProcessor<W, E>>(
+        &mut self,
+        request: HelpRequest<'_>,
+        handler: &mut P,
+    ) -> Result<(), E> {
+        // help is written through erased writer, so generated help code
+        // is not duplicated for each writer type
+        let mut writer = DynWriter::erased(&mut self.writer)
+            .with_colors(self.colors)
+            .with_help_short(self.help_short)
+            .with_machine_mode(self.machine_mode);
 
-        match request {
-            HelpRequest::All => C::list_commands(&mut writer)?,
+        let unknown = match request {
+            HelpRequest::All => {
+                C::list_commands(&mut writer)?;
+                false
+            }
             HelpRequest::Command(command) => {
-                match C::command_help(&mut |_| Ok(()), command.clone(), &mut writer) {
-                    Err(HelpError::UnknownCommand) => {
-                        writer.write_str("error: ")?;
-                        writer.write_str("unknown command")?;
-                    }
+                match C::command_help(&mut |_| Ok(()), command, &mut writer) {
+                    Err(HelpError::UnknownCommand) => true,
                     Err(HelpError::WriteError(err)) => return Err(err),
-                    Ok(()) => {}
+                    Ok(()) => false,
                 }
             }
         };
@@ -456,6 +2304,113 @@ where
         }
         self.writer.flush()?;
 
+        if unknown {
+            self.process_error(ParseError::UnknownCommand, handler)?;
+        }
+
         Ok(())
     }
 }
+
+/// Writer, that keeps error of underlying writer,
+/// since `core::fmt::Write` can't return it
+struct ErrorCapture<'w, 'a, E: Error> {
+    error: Option<E>,
+    writer: &'w mut DynWriter<'a, E>,
+}
+
+impl<E: Error> core::fmt::Write for ErrorCapture<'_, '_, E> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.writer.write_str(s).map_err(|err| {
+            self.error = Some(err);
+            core::fmt::Error
+        })
+    }
+}
+
+/// Write parse error with handler, given by application
+fn write_handled_error<E: Error>(
+    mut writer: DynWriter<'_, E>,
+    handler: ErrorHandler,
+    error: ParseError<'_>,
+) -> Result<(), E> {
+    let mut capture = ErrorCapture {
+        error: None,
+        writer: &mut writer,
+    };
+    // formatting errors, that are not caused by writer, are ignored
+    let _ = handler(&mut capture, error);
+    if let Some(err) = capture.error {
+        return Err(err);
+    }
+    if writer.is_dirty() {
+        writer.write_str(codes::CRLF)?;
+    }
+    Ok(())
+}
+
+/// Write parse error through erased writer, so this code
+/// is not duplicated for each writer type
+fn write_parse_error<E: Error>(
+    writer: &mut dyn Write<Error = E>,
+    error: ParseError<'_>,
+) -> Result<(), E> {
+    writer.write_str("error: ")?;
+    match error {
+        ParseError::LineUnavailable { name } => {
+            writer.write_str("original line is not available for ")?;
+            writer.write_str(name)?;
+        }
+        ParseError::MissingRequiredArgument { name } => {
+            writer.write_str("missing required argument: ")?;
+            writer.write_str(name)?;
+        }
+        ParseError::MissingSubcommand { expected } => {
+            writer.write_str("missing subcommand")?;
+            for (i, name) in expected.iter().enumerate() {
+                writer.write_str(if i == 0 { ", expected one of: " } else { ", " })?;
+                writer.write_str(name)?;
+            }
+        }
+        ParseError::ParseValueError {
+            name,
+            value,
+            expected,
+        } => {
+            writer.write_str("failed to parse '")?;
+            if let Some(name) = name {
+                writer.write_str(name)?;
+                writer.write_str(" ")?;
+            }
+            writer.write_str(value)?;
+            writer.write_str("', expected ")?;
+            writer.write_str(expected)?;
+        }
+        ParseError::TooManyTokens { max } => {
+            let mut buf = [0; 5];
+            let max = utils::encode_u16(max.try_into().unwrap_or(u16::MAX), &mut buf);
+            writer.write_str("too many tokens, at most ")?;
+            writer.write_str(max)?;
+            writer.write_str(" allowed")?;
+        }
+        ParseError::UnexpectedArgument { value } => {
+            writer.write_str("unexpected argument: ")?;
+            writer.write_str(value)?;
+        }
+        ParseError::UnexpectedLongOption { name } => {
+            writer.write_str("unexpected option: -")?;
+            writer.write_str("-")?;
+            writer.write_str(name)?;
+        }
+        ParseError::UnexpectedShortOption { name } => {
+            let mut buf = [0; 4];
+            let buf = utils::encode_utf8(name, &mut buf);
+            writer.write_str("unexpected option: -")?;
+            writer.write_str(buf)?;
+        }
+        ParseError::UnknownCommand => {
+            writer.write_str("unknown command")?;
+        }
+    }
+    writer.flush_str(codes::CRLF)
+}
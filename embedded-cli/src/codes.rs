@@ -1,14 +1,77 @@
+/// End of text (ETX), sent by terminal when Ctrl+C is pressed
+pub const END_OF_TEXT: u8 = 0x03;
+pub const BELL: u8 = 0x07;
 pub const BACKSPACE: u8 = 0x08;
 pub const TABULATION: u8 = 0x09;
 pub const LINE_FEED: u8 = 0x0A;
+/// Vertical tab (VT), sent by terminal when Ctrl+K is pressed
+pub const VERTICAL_TAB: u8 = 0x0B;
 pub const CARRIAGE_RETURN: u8 = 0x0D;
+/// Resume transmission (DC1), used by software flow control
+pub const XON: u8 = 0x11;
+/// Device control 2 (DC2), sent by terminal when Ctrl+R is pressed
+pub const DEVICE_CONTROL_2: u8 = 0x12;
+/// Pause transmission (DC3), used by software flow control
+pub const XOFF: u8 = 0x13;
+/// Negative acknowledge (NAK), sent by terminal when Ctrl+U is pressed
+pub const NEGATIVE_ACKNOWLEDGE: u8 = 0x15;
+/// End of transmission block (ETB), sent by terminal when Ctrl+W is pressed
+pub const END_OF_TRANSMISSION_BLOCK: u8 = 0x17;
 pub const ESCAPE: u8 = 0x1B;
+/// Single byte (8-bit) control sequence introducer, equivalent of `Esc[`
+pub const CSI_8BIT: u8 = 0x9B;
 
 pub const CRLF: &str = "\r\n";
 
 // escape sequence reference: https://ecma-international.org/publications-and-standards/standards/ecma-48
+/// Control sequence introducer, prefix of all CSI sequences
+pub const CSI: &[u8] = b"\x1B[";
+/// Single shift 3, prefix of cursor and function keys
+/// sent by terminals in application cursor keys mode (for example, `Esc O A`)
+pub const SS3: &[u8] = b"\x1BO";
+
+/// Move cursor one row up (also sent by terminal when Up key is pressed)
+pub const CURSOR_UP: &[u8] = b"\x1B[A";
+/// Move cursor one row down (also sent by terminal when Down key is pressed)
+pub const CURSOR_DOWN: &[u8] = b"\x1B[B";
+/// Move cursor one column forward (also sent by terminal when Right key is pressed)
 pub const CURSOR_FORWARD: &[u8] = b"\x1B[C";
+/// Move cursor one column backward (also sent by terminal when Left key is pressed)
 pub const CURSOR_BACKWARD: &[u8] = b"\x1B[D";
+/// Clear whole line, cursor position does not change
 pub const CLEAR_LINE: &[u8] = b"\x1B[2K";
+/// Clear line from cursor to the end of line
+pub const CLEAR_LINE_END: &[u8] = b"\x1B[K";
+/// Clear line from start of line to cursor (inclusive)
+pub const CLEAR_LINE_START: &[u8] = b"\x1B[1K";
+/// Insert blank char at cursor, shifting rest of line to the right
 pub const INSERT_CHAR: &[u8] = b"\x1B[@";
+/// Delete char at cursor, shifting rest of line to the left
 pub const DELETE_CHAR: &[u8] = b"\x1B[P";
+/// Save cursor position (DECSC)
+pub const SAVE_CURSOR: &[u8] = b"\x1B7";
+/// Restore cursor position, saved by `SAVE_CURSOR` (DECRC)
+pub const RESTORE_CURSOR: &[u8] = b"\x1B8";
+/// Enable bracketed paste mode, so terminal wraps pasted text
+/// with `PASTE_START` and `PASTE_END`
+pub const BRACKETED_PASTE_ENABLE: &[u8] = b"\x1B[?2004h";
+/// Disable bracketed paste mode
+pub const BRACKETED_PASTE_DISABLE: &[u8] = b"\x1B[?2004l";
+/// Sent by terminal before pasted text (in bracketed paste mode)
+pub const PASTE_START: &[u8] = b"\x1B[200~";
+/// Sent by terminal after pasted text (in bracketed paste mode)
+pub const PASTE_END: &[u8] = b"\x1B[201~";
+/// Numeric parameter of `PASTE_START` CSI sequence
+pub const PASTE_START_PARAM: u8 = 200;
+/// Numeric parameter of `PASTE_END` CSI sequence
+pub const PASTE_END_PARAM: u8 = 201;
+/// Final byte of CSI sequence, that moves cursor to given row and column
+pub const CURSOR_POSITION: u8 = b'H';
+/// Final byte of CSI sequence, that sets top and bottom margins of scroll region
+pub const SET_SCROLL_REGION: u8 = b'r';
+
+// select graphic rendition (colors)
+pub const SGR_RESET: &[u8] = b"\x1B[0m";
+pub const SGR_FG_RED: &[u8] = b"\x1B[31m";
+pub const SGR_FG_GREEN: &[u8] = b"\x1B[32m";
+pub const SGR_FG_YELLOW: &[u8] = b"\x1B[33m";
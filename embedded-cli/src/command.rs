@@ -3,7 +3,7 @@ use core::marker::PhantomData;
 use embedded_io::Write;
 
 use crate::{
-    arguments::ArgList,
+    arguments::{Arg, ArgList, ArgsWithOffsets},
     cli::CliHandle,
     service::{Autocomplete, CommandProcessor, FromRaw, Help, ParseError, ProcessError},
     token::Tokens,
@@ -26,6 +26,9 @@ pub struct RawCommand<'a> {
     ///
     /// In `set led 1 1` arguments is `led 1 1`
     args: ArgList<'a>,
+
+    /// Line, that was typed (before tokenization)
+    line: Option<&'a str>,
 }
 
 impl<'a> RawCommand<'a> {
@@ -38,21 +41,83 @@ impl<'a> RawCommand<'a> {
         Some(RawCommand {
             name,
             args: ArgList::new(tokens),
+            line: None,
         })
     }
 
+    /// Attach original line, from which this command was tokenized
+    pub(crate) fn with_line(self, line: Option<&'a str>) -> Self {
+        Self { line, ..self }
+    }
+
+    /// Create raw command from text input, split into tokens
+    /// the same way as input typed into `Cli`.
+    /// Input is modified in place, so no extra memory is needed.
+    ///
+    /// Returns `None` if input doesn't contain any tokens.
+    /// Useful to test `FromRaw` implementations and handlers without `Cli`
+    pub fn from_input(input: &'a mut str) -> Option<Self> {
+        Self::from_tokens(&Tokens::new(input))
+    }
+
     pub fn new(name: &'a str, args: ArgList<'a>) -> Self {
-        Self { name, args }
+        Self {
+            name,
+            args,
+            line: None,
+        }
     }
 
     pub fn args(&self) -> ArgList<'a> {
         self.args.clone()
     }
 
+    /// Returns iterator over arguments with byte offsets inside typed line,
+    /// so errors can point to the offending token.
+    ///
+    /// Available only when original line is known (see `line`)
+    pub fn args_with_offsets(&self) -> Option<ArgsWithOffsets<'a>> {
+        self.line.map(|line| self.args.args_with_offsets(line))
+    }
+
+    /// Returns line exactly as it was typed (including quotes and extra spaces).
+    /// Subcommands return the whole line of their parent command.
+    ///
+    /// Available only for commands, that are created by `Cli` from input,
+    /// when quoted arguments don't contain escaped chars (such input
+    /// is modified in place when it's split into tokens)
+    pub fn line(&self) -> Option<&'a str> {
+        self.line
+    }
+
+    /// Returns rest of the line exactly as it was typed, starting from argument
+    /// with given index (as they are returned by `args().args()`).
+    ///
+    /// Available only when original line is known (see `line`)
+    pub fn line_from(&self, index: usize) -> Option<&'a str> {
+        let line = self.line?;
+        let (offset, _) = self.args_with_offsets()?.nth(index)?;
+        line.get(offset..).map(str::trim_end)
+    }
+
     pub fn name(&self) -> &'a str {
         self.name
     }
 
+    /// Returns command, that is formed from arguments of this command,
+    /// if first argument is a value (it becomes a name of returned command).
+    ///
+    /// In `net set 1` subcommand is `set 1`
+    pub fn subcommand(&self) -> Option<RawCommand<'a>> {
+        let mut args = self.args.args();
+        match args.next() {
+            Some(Arg::Value(name)) => {
+                Some(RawCommand::new(name, args.into_args()).with_line(self.line))
+            }
+            _ => None,
+        }
+    }
+
     pub fn processor<
         W: Write<Error = E>,
         E: embedded_io::Error,
@@ -92,6 +157,68 @@ impl<'a> RawCommand<'a> {
     }
 }
 
+/// Raw command, that stores its name and arguments in internal buffer of `N` bytes.
+///
+/// Each argument is stored as a separate token, so no quoting or escaping is needed.
+/// Useful in tests to create commands without `Cli`:
+/// ```
+/// use embedded_cli::command::OwnedCommand;
+///
+/// let command = OwnedCommand::<32>::new("set").arg("led").arg("on and off");
+/// let raw = command.as_raw();
+///
+/// assert_eq!(raw.name(), "set");
+/// assert_eq!(raw.args().args().count(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedCommand<const N: usize = 64> {
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> OwnedCommand<N> {
+    /// Create new command with given name and no arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if name doesn't fit into buffer
+    pub fn new(name: &str) -> Self {
+        let mut command = Self {
+            buffer: [0; N],
+            len: 0,
+        };
+        command.push(name);
+        command
+    }
+
+    /// Add argument to the command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if argument doesn't fit into buffer
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.push("\0");
+        self.push(arg);
+        self
+    }
+
+    pub fn as_raw(&self) -> RawCommand<'_> {
+        // SAFETY: buffer is filled only with complete str slices
+        let tokens = unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.len]) };
+        // name is always stored, so there is at least one token
+        let mut iter = Tokens::from_raw(tokens, false).iter();
+        let name = iter.next().unwrap_or_default();
+        RawCommand::new(name, ArgList::new(iter.into_tokens()))
+    }
+
+    fn push(&mut self, text: &str) {
+        let end = self.len + text.len();
+        assert!(end <= N, "command doesn't fit into buffer");
+        self.buffer[self.len..end].copy_from_slice(text.as_bytes());
+        self.len = end;
+    }
+}
+
 impl<'a> Autocomplete for RawCommand<'a> {
     #[cfg(feature = "autocomplete")]
     fn autocomplete(_: Request<'_>, _: &mut Autocompletion<'_>) {
@@ -106,7 +233,7 @@ impl<'a> Help for RawCommand<'a> {
     }
 
     #[cfg(feature = "help")]
-    fn list_commands<W: Write<Error = E>, E: embedded_io::Error>(
+    fn list_commands<W: Write<Error = E> + ?Sized, E: embedded_io::Error>(
         _: &mut crate::writer::Writer<'_, W, E>,
     ) -> Result<(), E> {
         // noop
@@ -115,7 +242,7 @@ impl<'a> Help for RawCommand<'a> {
 
     #[cfg(feature = "help")]
     fn command_help<
-        W: Write<Error = E>,
+        W: Write<Error = E> + ?Sized,
         E: embedded_io::Error,
         F: FnMut(&mut crate::writer::Writer<'_, W, E>) -> Result<(), E>,
     >(
@@ -138,7 +265,11 @@ impl<'a> FromRaw<'a> for RawCommand<'a> {
 mod tests {
     use rstest::rstest;
 
-    use crate::{arguments::ArgList, command::RawCommand, token::Tokens};
+    use crate::{
+        arguments::{Arg, ArgList},
+        command::{OwnedCommand, RawCommand},
+        token::Tokens,
+    };
 
     #[rstest]
     #[case("set led 1", "set", "led 1")]
@@ -155,10 +286,7 @@ mod tests {
 
         assert_eq!(
             RawCommand::from_tokens(&input_tokens).unwrap(),
-            RawCommand {
-                name: name,
-                args: ArgList::new(arg_tokens)
-            }
+            RawCommand::new(name, ArgList::new(arg_tokens))
         );
     }
 
@@ -172,4 +300,107 @@ mod tests {
 
         assert!(RawCommand::from_tokens(&tokens).is_none());
     }
+
+    #[rstest]
+    #[case("echo  hello   \"big\" world ", 0, Some("hello   \"big\" world"))]
+    #[case("echo -n hello world", 1, Some("hello world"))]
+    #[case("echo -n hello", 2, None)]
+    fn line_from(#[case] line: &str, #[case] index: usize, #[case] expected: Option<&str>) {
+        let mut input = line.as_bytes().to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+        let tokens = Tokens::new(input);
+        let command = RawCommand::from_tokens(&tokens)
+            .unwrap()
+            .with_line(Some(line));
+
+        assert_eq!(command.line_from(index), expected);
+        assert_eq!(command.with_line(None).line_from(index), None);
+    }
+
+    #[rstest]
+    #[case("net set 1", Some(("set", "1")))]
+    #[case("net get", Some(("get", "")))]
+    #[case("net", None)]
+    #[case("net --help", None)]
+    fn subcommand(#[case] input: &str, #[case] expected: Option<(&str, &str)>) {
+        let mut input = input.as_bytes().to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+        let tokens = Tokens::new(input);
+        let command = RawCommand::from_tokens(&tokens).unwrap();
+
+        let subcommand = command.subcommand();
+
+        match expected {
+            Some((name, args)) => {
+                let mut args = args.as_bytes().to_vec();
+                let args = core::str::from_utf8_mut(&mut args).unwrap();
+                let arg_tokens = Tokens::new(args);
+                assert_eq!(
+                    subcommand,
+                    Some(RawCommand::new(name, ArgList::new(arg_tokens)))
+                );
+            }
+            None => assert!(subcommand.is_none()),
+        }
+    }
+
+    #[rstest]
+    #[case("set led 1", "set", &["led", "1"])]
+    #[case("  get  \"led 2\" ", "get", &["led 2"])]
+    #[case("get", "get", &[])]
+    fn from_input(#[case] input: &str, #[case] name: &str, #[case] args: &[&str]) {
+        let mut input = input.as_bytes().to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+
+        let command = RawCommand::from_input(input).unwrap();
+
+        assert_eq!(command.name(), name);
+        assert_eq!(
+            command.args().args().collect::<std::vec::Vec<_>>(),
+            args.iter()
+                .map(|&a| Arg::Value(a))
+                .collect::<std::vec::Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_input_empty() {
+        let mut input = std::string::String::from("   ");
+
+        assert!(RawCommand::from_input(&mut input).is_none());
+    }
+
+    #[test]
+    fn owned() {
+        let command = OwnedCommand::<32>::new("set")
+            .arg("led 1")
+            .arg("--force")
+            .arg("-v");
+        let raw = command.as_raw();
+
+        assert_eq!(raw.name(), "set");
+        assert_eq!(
+            raw.args().args().collect::<std::vec::Vec<_>>(),
+            std::vec![
+                Arg::Value("led 1"),
+                Arg::LongOption("force"),
+                Arg::ShortOption('v')
+            ]
+        );
+    }
+
+    #[test]
+    fn owned_without_args() {
+        let command = OwnedCommand::<8>::new("get");
+        let raw = command.as_raw();
+
+        assert_eq!(raw.name(), "get");
+        assert_eq!(raw.args().args().count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn owned_overflow() {
+        let _ = OwnedCommand::<8>::new("get").arg("too long");
+    }
 }
@@ -0,0 +1,197 @@
+//! Writers for Cortex-M debug channels.
+//!
+//! During board bring-up UART might not work yet, but debug probe is already
+//! connected. Cli output can then be sent through ITM stimulus port
+//! (with `itm` feature) or through semihosting console (with `semihosting` feature).
+//! Input bytes still have to be given to `Cli::process_byte` from somewhere else
+//! (for example, from RTT or from a script).
+//!
+//! ```no_run
+//! use embedded_cli::{cli::CliBuilder, debug::ItmWriter};
+//!
+//! // SAFETY: ITM registers are not used by anything else
+//! let writer = unsafe { ItmWriter::new(0) };
+//! let mut cli = CliBuilder::default().writer(writer).build().unwrap();
+//! ```
+//!
+//! Both writers depend only on core registers and instructions,
+//! so there is no dependency on `cortex-m` crate.
+
+#[cfg(feature = "itm")]
+use core::convert::Infallible;
+
+use embedded_io::{ErrorType, Write};
+
+/// Base address of ITM stimulus ports
+#[cfg(feature = "itm")]
+const ITM_STIM: usize = 0xE000_0000;
+
+/// Address of ITM trace enable register
+#[cfg(feature = "itm")]
+const ITM_TER: usize = 0xE000_0E00;
+
+/// Writer to one of 32 ITM stimulus ports.
+///
+/// When port is not enabled by debugger, all bytes are dropped,
+/// so writer never blocks without debug probe.
+#[cfg(feature = "itm")]
+#[derive(Debug)]
+pub struct ItmWriter {
+    stim: *mut u32,
+    ter: *const u32,
+    mask: u32,
+}
+
+#[cfg(feature = "itm")]
+impl ItmWriter {
+    /// Create writer to given stimulus port (only 5 lower bits are used).
+    ///
+    /// # Safety
+    /// Must be called only on Cortex-M core with ITM unit
+    /// and stimulus port must not be written from anywhere else.
+    pub unsafe fn new(port: u8) -> Self {
+        let port = port & 0x1F;
+        Self::from_registers(
+            (ITM_STIM + 4 * port as usize) as *mut u32,
+            ITM_TER as *const u32,
+            port,
+        )
+    }
+
+    unsafe fn from_registers(stim: *mut u32, ter: *const u32, port: u8) -> Self {
+        Self {
+            stim,
+            ter,
+            mask: 1 << port,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        // SAFETY: pointer is valid as guaranteed by caller of `new`
+        unsafe { self.ter.read_volatile() & self.mask != 0 }
+    }
+}
+
+#[cfg(feature = "itm")]
+impl ErrorType for ItmWriter {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "itm")]
+impl Write for ItmWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if !self.is_enabled() {
+            return Ok(buf.len());
+        }
+        for &b in buf {
+            // SAFETY: pointer is valid as guaranteed by caller of `new`.
+            // Reading stimulus port returns 1 when its FIFO can accept data
+            unsafe {
+                while self.stim.read_volatile() & 1 == 0 {}
+                (self.stim as *mut u8).write_volatile(b);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Writer to host console through semihosting.
+///
+/// Each write halts the core until debugger handles it, so it is slow
+/// and should be used only for bring-up.
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+#[derive(Debug)]
+pub struct SemihostingWriter {
+    handle: usize,
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+impl SemihostingWriter {
+    const SYS_OPEN: usize = 0x01;
+    const SYS_WRITE: usize = 0x05;
+
+    /// Open host console for writing.
+    ///
+    /// # Safety
+    /// Debugger must be attached and semihosting must be enabled,
+    /// otherwise core is halted (or hard fault is raised)
+    pub unsafe fn new() -> Result<Self, embedded_io::ErrorKind> {
+        let name = b":tt\0";
+        // mode 4 is "w"
+        let args = [name.as_ptr() as usize, 4, name.len() - 1];
+        let handle = syscall(Self::SYS_OPEN, args.as_ptr() as usize);
+        if handle == usize::MAX {
+            return Err(embedded_io::ErrorKind::NotConnected);
+        }
+        Ok(Self { handle })
+    }
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+impl ErrorType for SemihostingWriter {
+    type Error = embedded_io::ErrorKind;
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+impl Write for SemihostingWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let args = [self.handle, buf.as_ptr() as usize, buf.len()];
+        // SAFETY: semihosting is available, since writer was created
+        // returns number of bytes that were not written
+        let left = unsafe { syscall(Self::SYS_WRITE, args.as_ptr() as usize) };
+        if left > buf.len() {
+            return Err(embedded_io::ErrorKind::Other);
+        }
+        Ok(buf.len() - left)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+unsafe fn syscall(op: usize, args: usize) -> usize {
+    let ret;
+    core::arch::asm!(
+        "bkpt #0xab",
+        inout("r0") op => ret,
+        in("r1") args,
+        options(nostack, preserves_flags),
+    );
+    ret
+}
+
+#[cfg(all(test, feature = "itm"))]
+mod tests {
+    use embedded_io::Write;
+
+    use crate::debug::ItmWriter;
+
+    #[test]
+    fn itm_write() {
+        // FIFO is ready
+        let mut stim = 1u32;
+        let ter = 1u32 << 3;
+        let mut writer =
+            unsafe { ItmWriter::from_registers(&mut stim as *mut u32, &ter as *const u32, 3) };
+
+        assert_eq!(writer.write(b"a").unwrap(), 1);
+        assert_eq!(stim.to_ne_bytes()[0], b'a');
+    }
+
+    #[test]
+    fn itm_disabled_port() {
+        let mut stim = 0u32;
+        let ter = 1u32 << 3;
+        let mut writer =
+            unsafe { ItmWriter::from_registers(&mut stim as *mut u32, &ter as *const u32, 2) };
+
+        assert_eq!(writer.write(b"abc").unwrap(), 3);
+        assert_eq!(stim, 0);
+    }
+}
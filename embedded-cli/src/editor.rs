@@ -1,4 +1,4 @@
-use crate::{buffer::Buffer, utils};
+use crate::{buffer::Buffer, utils, width};
 use core::{
     fmt::Debug,
     ops::{Bound, RangeBounds},
@@ -99,6 +99,11 @@ impl<B: Buffer> Editor<B> {
         self.cursor
     }
 
+    /// Returns how many more bytes can be inserted
+    pub fn free(&self) -> usize {
+        self.buffer.len() - self.valid
+    }
+
     pub fn insert(&mut self, text: &str) -> Option<&str> {
         let remaining = self.buffer.len() - self.valid;
         let chars = utils::char_count(text);
@@ -127,35 +132,132 @@ impl<B: Buffer> Editor<B> {
         Some(unsafe { core::str::from_utf8_unchecked(text) })
     }
 
+    /// Returns how many terminal columns are occupied by char at cursor.
+    /// If cursor is at the end of input, returns 0
+    pub fn width_at_cursor(&self) -> usize {
+        let text = self.text();
+        utils::char_byte_index(text, self.cursor)
+            // SAFETY: pos is at char boundary
+            .and_then(|pos| utils::char_pop_front(unsafe { text.get_unchecked(pos..) }))
+            .map(|(c, _)| width::char_width(c))
+            .unwrap_or(0)
+    }
+
     pub fn len(&self) -> usize {
         utils::char_count(self.text())
     }
 
+    /// Moves cursor to the start of previous grapheme
     pub fn move_left(&mut self) -> bool {
         if self.cursor > 0 {
             self.cursor -= 1;
+            while self.cursor > 0 && self.is_joined_at(self.cursor) {
+                self.cursor -= 1;
+            }
             true
         } else {
             false
         }
     }
 
+    /// Moves cursor to the start of next grapheme
     pub fn move_right(&mut self) -> bool {
-        if self.cursor < self.len() {
+        let len = self.len();
+        if self.cursor < len {
             self.cursor += 1;
+            while self.cursor < len && self.is_joined_at(self.cursor) {
+                self.cursor += 1;
+            }
             true
         } else {
             false
         }
     }
 
-    /// Removes char at cursor position
+    /// Moves cursor to the start of input.
+    /// Returns how many terminal columns cursor moved by
+    pub fn move_to_start(&mut self) -> usize {
+        let mut width = 0;
+        while self.move_left() {
+            width += self.width_at_cursor();
+        }
+        width
+    }
+
+    /// Moves cursor to the end of input.
+    /// Returns how many terminal columns cursor moved by
+    pub fn move_to_end(&mut self) -> usize {
+        let mut width = 0;
+        while self.cursor < self.len() {
+            width += self.width_at_cursor();
+            self.move_right();
+        }
+        width
+    }
+
+    /// Moves cursor to the start of previous word (spaces before cursor are skipped).
+    /// Returns how many terminal columns cursor moved by
+    pub fn move_word_left(&mut self) -> usize {
+        let mut width = 0;
+        for space in [true, false] {
+            while self.cursor > 0 && self.is_space_at(self.cursor - 1) == space {
+                self.move_left();
+                width += self.width_at_cursor();
+            }
+        }
+        width
+    }
+
+    /// Moves cursor to the end of next word (spaces after cursor are skipped).
+    /// Returns how many terminal columns cursor moved by
+    pub fn move_word_right(&mut self) -> usize {
+        let mut width = 0;
+        let len = self.len();
+        for space in [true, false] {
+            while self.cursor < len && self.is_space_at(self.cursor) == space {
+                width += self.width_at_cursor();
+                self.move_right();
+            }
+        }
+        width
+    }
+
+    fn is_space_at(&self, index: usize) -> bool {
+        let text = self.text();
+        utils::char_byte_index(text, index)
+            .map(|pos| text.as_bytes()[pos] == b' ')
+            .unwrap_or(false)
+    }
+
+    /// Returns true if char at given index is a part of
+    /// the same grapheme as previous char
+    fn is_joined_at(&self, index: usize) -> bool {
+        let text = self.text();
+        utils::char_byte_index(text, index - 1)
+            // SAFETY: pos is at char boundary
+            .map(|pos| unsafe { text.get_unchecked(pos..) }.chars())
+            .and_then(|mut chars| Some((chars.next()?, chars.next()?)))
+            .map(|(prev, c)| width::is_joined(prev, c))
+            .unwrap_or(false)
+    }
+
+    /// Removes grapheme at cursor position
     pub fn remove(&mut self) {
         let cursor_pos = utils::char_byte_index(self.text(), self.cursor);
         let next_pos = if let Some(cursor_pos) = cursor_pos {
             // SAFETY: cursor_pos is at char boundary
             let text = unsafe { self.text().get_unchecked(cursor_pos..) };
-            utils::char_byte_index(text, 1).map(|s| s + cursor_pos)
+            let mut chars = text.chars();
+            let mut prev = chars.next();
+            let mut grapheme_len = 1;
+            for c in chars {
+                match prev {
+                    Some(p) if width::is_joined(p, c) => grapheme_len += 1,
+                    _ => break,
+                }
+                prev = Some(c);
+            }
+            utils::char_byte_index(text, grapheme_len).map(|s| s + cursor_pos)
         } else {
             None
         };
@@ -175,6 +277,21 @@ impl<B: Buffer> Editor<B> {
         }
     }
 
+    /// Removes chars from `start` (inclusive) to `end` (exclusive)
+    /// and moves cursor to `start`
+    pub fn remove_range(&mut self, start: usize, end: usize) {
+        let text = self.text();
+        let Some(from) = utils::char_byte_index(text, start) else {
+            return;
+        };
+        let to = utils::char_byte_index(text, end).unwrap_or(self.valid);
+        if to > from {
+            self.buffer.as_slice_mut().copy_within(to..self.valid, from);
+            self.valid -= to - from;
+        }
+        self.cursor = start;
+    }
+
     pub fn text(&self) -> &str {
         // SAFETY: buffer stores only valid utf-8 bytes 0..valid range
         unsafe {
@@ -192,7 +309,6 @@ impl<B: Buffer> Editor<B> {
     }
 
     /// Returns text in subrange of this editor. start is including, end is exclusive
-    #[allow(dead_code)]
     pub fn text_range(&self, range: impl RangeBounds<usize>) -> &str {
         let (start, num_chars) = match (range.start_bound(), range.end_bound()) {
             (Bound::Included(start), Bound::Included(end)) => {
@@ -216,7 +332,8 @@ impl<B: Buffer> Editor<B> {
             }
             (Bound::Included(start), Bound::Unbounded) => (*start, None),
             (Bound::Unbounded, Bound::Unbounded) => (0, None),
-            (Bound::Excluded(_), _) => unreachable!(),
+            // range syntax never creates excluded start bound
+            (Bound::Excluded(_), _) => return "",
         };
 
         let text = self.text();
@@ -394,6 +511,44 @@ mod tests {
         assert_eq!(editor.text(), "");
     }
 
+    #[test]
+    fn move_by_words() {
+        let mut editor = Editor::new([0; 128]);
+
+        editor.insert("set  led жз");
+        assert_eq!(editor.move_word_left(), 2);
+        assert_eq!(editor.cursor, 9);
+        assert_eq!(editor.move_word_left(), 4);
+        assert_eq!(editor.cursor, 5);
+        assert_eq!(editor.move_word_left(), 5);
+        assert_eq!(editor.cursor, 0);
+        assert_eq!(editor.move_word_left(), 0);
+
+        assert_eq!(editor.move_word_right(), 3);
+        assert_eq!(editor.cursor, 3);
+        assert_eq!(editor.move_word_right(), 5);
+        assert_eq!(editor.cursor, 8);
+        assert_eq!(editor.move_to_end(), 3);
+        assert_eq!(editor.move_word_right(), 0);
+        assert_eq!(editor.move_to_start(), 11);
+    }
+
+    #[rstest]
+    #[case(0, 3, "  led 佐佗")]
+    #[case(4, 9, "set 佐佗")]
+    #[case(9, 11, "set  led ")]
+    #[case(9, 20, "set  led ")]
+    #[case(3, 3, "set  led 佐佗")]
+    fn remove_range(#[case] start: usize, #[case] end: usize, #[case] expected: &str) {
+        let mut editor = Editor::new([0; 128]);
+
+        editor.insert("set  led 佐佗");
+        editor.remove_range(start, end);
+
+        assert_eq!(editor.text(), expected);
+        assert_eq!(editor.cursor, start);
+    }
+
     #[rstest]
     #[case(1, "adbc佐佗")]
     #[case(2, "adbc佐𑿌")]
@@ -415,6 +570,63 @@ mod tests {
         assert_eq!(editor.text(), expected);
     }
 
+    #[test]
+    fn width_at_cursor() {
+        let mut editor = Editor::new([0; 128]);
+
+        editor.insert("a佐б");
+        assert_eq!(editor.width_at_cursor(), 0);
+
+        editor.move_left();
+        assert_eq!(editor.width_at_cursor(), 1);
+
+        editor.move_left();
+        if cfg!(feature = "wide-chars") {
+            assert_eq!(editor.width_at_cursor(), 2);
+        } else {
+            assert_eq!(editor.width_at_cursor(), 1);
+        }
+
+        editor.move_left();
+        assert_eq!(editor.width_at_cursor(), 1);
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn grapheme_editing() {
+        let mut editor = Editor::new([0; 128]);
+
+        editor.insert("ae\u{0301}\u{0302}b");
+        assert_eq!(editor.cursor(), 5);
+
+        assert!(editor.move_left());
+        assert_eq!(editor.cursor(), 4);
+        assert!(editor.move_left());
+        assert_eq!(editor.cursor(), 1);
+        assert_eq!(editor.width_at_cursor(), 1);
+
+        assert!(editor.move_right());
+        assert_eq!(editor.cursor(), 4);
+
+        editor.move_left();
+        editor.remove();
+        assert_eq!(editor.text(), "ab");
+        assert_eq!(editor.cursor(), 1);
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn grapheme_zwj_remove() {
+        let mut editor = Editor::new([0; 128]);
+
+        editor.insert("a👩\u{200D}💻");
+        assert!(editor.move_left());
+        assert_eq!(editor.cursor(), 1);
+
+        editor.remove();
+        assert_eq!(editor.text(), "a");
+    }
+
     #[rstest]
     #[case(.., "adbc佐佗𑿌")]
     #[case(..2, "ad")]
@@ -430,6 +642,7 @@ mod tests {
     #[case(..0, "")]
     #[case(1..=0, "")]
     #[case(5..=5, "佗")]
+    #[allow(clippy::reversed_empty_ranges)]
     fn text_range(#[case] range: impl RangeBounds<usize>, #[case] expected: &str) {
         let mut editor = Editor::new([0; 128]);
 
@@ -0,0 +1,197 @@
+//! Deterministic harness, that passes arbitrary bytes through the whole
+//! input pipeline: input decoding, line editing, autocompletion,
+//! history, tokenization and argument parsing.
+//!
+//! Requires `std`, so it should be used only in host tools
+//! (for example, as a target for `cargo fuzz`).
+
+use core::convert::Infallible;
+use std::{string::String, vec::Vec};
+
+use embedded_io::{ErrorType, Write};
+
+use crate::{
+    arguments::Arg,
+    builder::CliBuilder,
+    cli::{CliEvent, CliHandle},
+    command::RawCommand,
+    service::{CommandProcessor, ProcessError},
+};
+
+/// Owned version of `Arg`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FuzzArg {
+    DoubleDash,
+    LongOption(String),
+    ShortOption(char),
+    Value(String),
+}
+
+impl<'a> From<Arg<'a>> for FuzzArg {
+    fn from(arg: Arg<'a>) -> Self {
+        match arg {
+            Arg::DoubleDash => FuzzArg::DoubleDash,
+            Arg::LongOption(name) => FuzzArg::LongOption(name.into()),
+            Arg::ShortOption(name) => FuzzArg::ShortOption(name),
+            Arg::Value(value) => FuzzArg::Value(value.into()),
+        }
+    }
+}
+
+/// Observable result of processing input
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// Command was entered with given parsed arguments
+    Command { name: String, args: Vec<FuzzArg> },
+
+    /// Function key was pressed
+    FunctionKey(u8),
+}
+
+/// Writer, that discards all output
+#[derive(Debug)]
+struct Sink;
+
+impl ErrorType for Sink {
+    type Error = Infallible;
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Recorder {
+    events: Vec<Event>,
+}
+
+impl CommandProcessor<Sink, Infallible> for Recorder {
+    fn process<'a>(
+        &mut self,
+        _: &mut CliHandle<'_, Sink, Infallible>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, Infallible>> {
+        self.events.push(Event::Command {
+            name: raw.name().into(),
+            args: raw.args().args().map(FuzzArg::from).collect(),
+        });
+        Ok(())
+    }
+
+    fn on_event(
+        &mut self,
+        _: &mut CliHandle<'_, Sink, Infallible>,
+        event: CliEvent<'_>,
+    ) -> Result<(), Infallible> {
+        if let CliEvent::FunctionKey(key) = event {
+            self.events.push(Event::FunctionKey(key));
+        }
+        Ok(())
+    }
+}
+
+/// Create new cli with small buffers and pass all given bytes to it.
+///
+/// Returns all commands and events in order they were processed.
+/// Same bytes always produce same events, so any panic
+/// can be reproduced by calling this function with the same input.
+pub fn feed(bytes: &[u8]) -> Vec<Event> {
+    let mut cli = CliBuilder::default()
+        .writer(Sink)
+        .command_buffer([0; 64])
+        .history_buffer([0; 128])
+        .build()
+        .unwrap();
+
+    let mut recorder = Recorder::default();
+    for &byte in bytes {
+        // cli is not accessed from recorder, so it's never busy, and sink can't fail
+        let _ = cli.process_byte::<RawCommand<'_>, _>(byte, &mut recorder);
+    }
+    recorder.events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use crate::fuzz::{feed, Event, FuzzArg};
+
+    #[test]
+    fn commands_are_recorded() {
+        assert_eq!(
+            feed(b"get -v --level 3 -- -x\r\x1B[11~set\x1B[A\r"),
+            vec![
+                Event::Command {
+                    name: "get".into(),
+                    args: vec![
+                        FuzzArg::ShortOption('v'),
+                        FuzzArg::LongOption("level".into()),
+                        FuzzArg::Value("3".into()),
+                        FuzzArg::DoubleDash,
+                        FuzzArg::Value("-x".into()),
+                    ],
+                },
+                Event::FunctionKey(1),
+                Event::Command {
+                    name: "get".into(),
+                    args: vec![
+                        FuzzArg::ShortOption('v'),
+                        FuzzArg::LongOption("level".into()),
+                        FuzzArg::Value("3".into()),
+                        FuzzArg::DoubleDash,
+                        FuzzArg::Value("-x".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn odd_bytes_dont_panic() {
+        let mut bytes = vec![];
+        for i in 0..=255u8 {
+            bytes.extend_from_slice(&[i, b'a', 0x1B, b'[', i, b'\t', 0xD0, i]);
+        }
+        bytes.extend_from_slice(&[b'a'; 200]);
+        bytes.push(b'\r');
+        feed(&bytes);
+    }
+
+    #[test]
+    fn random_bytes_dont_panic() {
+        // bytes that are interesting to input decoder and editor
+        const ALPHABET: &[u8] =
+            b"ab -\"'\\\t\r\n\x08\x7F\x1B[O~1;5ABCDHF\xD0\xB0\xF0\x9F\x98\x80\xCC\x81\x11\x13";
+
+        // xorshift, so failures are reproducible
+        let mut state = 0x2545_F491_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        for _ in 0..500 {
+            let len = next() % 300;
+            let bytes: std::vec::Vec<u8> = (0..len)
+                .map(|_| {
+                    let r = next();
+                    if r % 8 == 0 {
+                        r as u8
+                    } else {
+                        ALPHABET[(r as usize >> 3) % ALPHABET.len()]
+                    }
+                })
+                .collect();
+            feed(&bytes);
+        }
+    }
+}
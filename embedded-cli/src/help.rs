@@ -1,4 +1,19 @@
-use crate::{arguments::Arg, command::RawCommand};
+use crate::{
+    arguments::{Arg, ArgsIter},
+    command::RawCommand,
+};
+
+#[cfg(feature = "help")]
+use crate::{
+    service::{Help, HelpError},
+    writer::Writer,
+};
+#[cfg(feature = "help")]
+use embedded_io::{Error, Write};
+
+/// Max length of command path, that can be passed to `render`
+#[cfg(feature = "help")]
+pub const MAX_PATH_LEN: usize = 64;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum HelpRequest<'a> {
@@ -23,14 +38,52 @@ impl<'a> HelpRequest<'a> {
                 None => Some(HelpRequest::All),
                 _ => None,
             }
-        }
-        // check if any other option is -h or --help
-        else if args.any(|arg| arg == Arg::LongOption("help") || arg == Arg::ShortOption('h')) {
+        } else if Self::has_help_option(args, Some('h')) {
             Some(HelpRequest::Command(command.clone()))
         } else {
             None
         }
     }
+
+    /// Tries to create new help request from --help option
+    /// or given short option (usually -h) of raw command
+    pub fn from_options(command: &RawCommand<'a>, short: Option<char>) -> Option<Self> {
+        Self::has_help_option(command.args().args(), short)
+            .then(|| HelpRequest::Command(command.clone()))
+    }
+
+    /// Whether any of given args is --help or given short option.
+    ///
+    /// Args after `--` are always values, so `cmd -- --help` is not a help request
+    fn has_help_option(mut args: ArgsIter<'_>, short: Option<char>) -> bool {
+        args.any(|arg| match arg {
+            Arg::LongOption(name) => name == "help",
+            Arg::ShortOption(name) => Some(name) == short,
+            _ => false,
+        })
+    }
+}
+
+/// Write help of command with given path (for example, `net config`)
+/// as if user typed `net config --help`.
+///
+/// Can be used in command handlers to show usage of other command.
+/// Path must not be longer than `MAX_PATH_LEN` bytes, otherwise
+/// (or if there is no such command) `HelpError::UnknownCommand` is returned
+#[cfg(feature = "help")]
+pub fn render<C: Help, W: Write<Error = E> + ?Sized, E: Error>(
+    command_path: &str,
+    writer: &mut Writer<'_, W, E>,
+) -> Result<(), HelpError<E>> {
+    let mut buf = [0; MAX_PATH_LEN];
+    let path = buf
+        .get_mut(..command_path.len())
+        .ok_or(HelpError::UnknownCommand)?;
+    path.copy_from_slice(command_path.as_bytes());
+    let path = core::str::from_utf8_mut(path).map_err(|_| HelpError::UnknownCommand)?;
+    let command = RawCommand::from_input(path).ok_or(HelpError::UnknownCommand)?;
+
+    C::command_help(&mut |_| Ok(()), command, writer)
 }
 
 #[cfg(test)]
@@ -71,6 +124,8 @@ mod tests {
     #[case("cmd1")]
     #[case("cmd1 help")]
     #[case("--help")]
+    #[case("cmd1 -v -- --help -h")]
+    #[case("cmd1 -- -h")]
     fn parsing_err(#[case] input: &str) {
         let mut input = input.as_bytes().to_vec();
         let input = core::str::from_utf8_mut(&mut input).unwrap();
@@ -80,4 +135,24 @@ mod tests {
 
         assert!(res.is_none());
     }
+
+    #[rstest]
+    #[case("cmd -h", Some('h'), true)]
+    #[case("cmd -h", Some('?'), false)]
+    #[case("cmd -v?", Some('?'), true)]
+    #[case("cmd -h", None, false)]
+    #[case("cmd -h --help", None, true)]
+    #[case("cmd -- -h --help", Some('h'), false)]
+    #[case("cmd -h -- --help", Some('h'), true)]
+    fn parsing_short(#[case] input: &str, #[case] short: Option<char>, #[case] expected: bool) {
+        let mut input = input.as_bytes().to_vec();
+        let input = core::str::from_utf8_mut(&mut input).unwrap();
+        let tokens = Tokens::new(input);
+        let command = RawCommand::from_tokens(&tokens).unwrap();
+
+        assert_eq!(
+            HelpRequest::from_options(&command, short).is_some(),
+            expected
+        );
+    }
 }
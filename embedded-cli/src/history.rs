@@ -1,4 +1,4 @@
-use crate::{buffer::Buffer, utils};
+use crate::{buffer::Buffer, cli::HistoryUsage, utils};
 
 #[derive(Debug)]
 pub struct History<B: Buffer> {
@@ -11,6 +11,10 @@ pub struct History<B: Buffer> {
     /// Index of first byte of currently selected element
     cursor: Option<usize>,
 
+    /// Length of stashed text, that is stored right after
+    /// last element (without null terminator)
+    stashed: Option<usize>,
+
     /// How many bytes of buffer are already used by elements
     used: usize,
 }
@@ -20,10 +24,76 @@ impl<B: Buffer> History<B> {
         Self {
             buffer,
             cursor: None,
+            stashed: None,
             used: 0,
         }
     }
 
+    /// Create history from buffer, that already contains elements
+    /// in first `len` bytes (as returned by `as_bytes`).
+    /// If those bytes are not valid history, it starts empty
+    pub fn from_raw(buffer: B, len: usize) -> Self {
+        let mut history = Self::new(buffer);
+        if history
+            .buffer
+            .as_slice()
+            .get(..len)
+            .is_some_and(Self::is_valid)
+        {
+            history.used = len;
+        }
+        history
+    }
+
+    /// Whether given bytes are valid history: null terminated
+    /// non-empty UTF-8 elements
+    pub fn is_valid(bytes: &[u8]) -> bool {
+        match bytes.split_last() {
+            None => true,
+            Some((&0, elements)) => elements
+                .split(|b| b == &0)
+                .all(|element| !element.is_empty() && core::str::from_utf8(element).is_ok()),
+            Some(_) => false,
+        }
+    }
+
+    /// Raw bytes of all elements (from oldest to newest), each terminated with null byte
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer.as_slice()[..self.used]
+    }
+
+    /// Memory usage of buffer
+    pub fn usage(&self) -> HistoryUsage {
+        HistoryUsage {
+            // every element is null terminated
+            entries: self.buffer.as_slice()[..self.used]
+                .iter()
+                .filter(|b| b == &&0)
+                .count(),
+            free: self.buffer.len() - self.used,
+            used: self.used,
+        }
+    }
+
+    /// Remove all elements
+    pub fn clear(&mut self) {
+        self.cursor = None;
+        self.stashed = None;
+        self.used = 0;
+    }
+
+    /// Stop navigation, so next navigation starts from newest element.
+    /// Stashed text is dropped
+    pub fn deselect(&mut self) {
+        self.cursor = None;
+        self.stashed = None;
+    }
+
+    /// Whether some element of history is currently selected
+    pub fn is_selected(&self) -> bool {
+        self.cursor.is_some()
+    }
+
     /// Return next element from history, that is newer, than currently selected.
     /// Return None if there is no newer elements
     pub fn next_newer(&mut self) -> Option<&str> {
@@ -83,12 +153,82 @@ impl<B: Buffer> History<B> {
         Some(element)
     }
 
+    /// Find newest element, that starts before given position and contains given text.
+    /// Return start position of found element (see `element_at`)
+    pub fn search_older(&self, text: &str, before: usize) -> Option<usize> {
+        if text.is_empty() {
+            return None;
+        }
+        let mut found = None;
+        let mut start = 0;
+        for element in self.buffer.as_slice()[..self.used].split(|b| b == &0) {
+            if start >= before.min(self.used) {
+                break;
+            }
+            if element
+                .windows(text.len())
+                .any(|window| window == text.as_bytes())
+            {
+                found = Some(start);
+            }
+            start += element.len() + 1;
+        }
+        found
+    }
+
+    /// Return element, that starts at given position
+    pub fn element_at(&self, start: usize) -> &str {
+        let bytes = &self.buffer.as_slice()[start.min(self.used)..self.used];
+        let len = bytes.iter().position(|b| b == &0).unwrap_or(bytes.len());
+
+        // SAFETY: elements are copied from str and null byte is not a part of any char
+        unsafe { core::str::from_utf8_unchecked(&bytes[..len]) }
+    }
+
+    /// Stash given text (usually current input) so it can be restored
+    /// after navigating back from history.
+    /// Oldest elements are removed if there is not enough space for text
+    pub fn stash(&mut self, text: &str) {
+        self.stashed = None;
+        if text.is_empty() || text.len() > self.buffer.len() {
+            return;
+        }
+
+        self.reserve(text.len());
+
+        // SAFETY: we ensured that buffer contains len bytes after self.used position
+        // and two buffers do not overlap since mutable reference to buffer is exclusive
+        unsafe {
+            utils::copy_nonoverlapping(
+                text.as_bytes(),
+                &mut self.buffer.as_slice_mut()[self.used..],
+                text.len(),
+            );
+        }
+        self.stashed = Some(text.len());
+    }
+
+    /// Take previously stashed text.
+    /// Return empty string if nothing was stashed
+    pub fn take_stashed(&mut self) -> &str {
+        let len = self.stashed.take().unwrap_or(0);
+
+        // SAFETY: stashed text was copied from str
+        unsafe {
+            core::str::from_utf8_unchecked(&self.buffer.as_slice()[self.used..self.used + len])
+        }
+    }
+
     /// Push given text to history. Text must not contain any null bytes. Otherwise
     /// text is not pushed to history and just ignored.
-    pub fn push(&mut self, text: &str) {
+    /// Return true if text was pushed
+    pub fn push(&mut self, text: &str) -> bool {
+        // stashed text is stored after elements and will be overwritten
+        self.stashed = None;
+
         // extra byte is added to text len since we need to null terminate it
         if text.as_bytes().contains(&0) || text.len() + 1 > self.buffer.len() || text.is_empty() {
-            return;
+            return false;
         }
 
         self.cursor = None;
@@ -100,7 +240,7 @@ impl<B: Buffer> History<B> {
                 // element already is added and is newest among others
                 // so we have nothing to do
                 self.cursor = None;
-                return;
+                return true;
             }
             _ => {}
         }
@@ -121,11 +261,47 @@ impl<B: Buffer> History<B> {
         }
         self.cursor = None;
 
+        self.reserve(text.len() + 1);
+
+        // now we have enough space after self.used to insert element
+        let null_pos = self.used + text.len();
+        // SAFETY: we ensured that buffer contains len + 1 bytes after self.used position
+        // and two buffers do not overlap since mutable reference to buffer is exclusive
+        unsafe {
+            utils::copy_nonoverlapping(
+                text.as_bytes(),
+                &mut self.buffer.as_slice_mut()[self.used..],
+                text.len(),
+            );
+        }
+        self.buffer.as_slice_mut()[null_pos] = 0;
+        self.used += text.len() + 1;
+        true
+    }
+
+    /// Remove newest element from history
+    pub fn remove_newest(&mut self) {
+        self.cursor = None;
+        self.stashed = None;
+        if self.used == 0 {
+            return;
+        }
+        // last used byte is null terminator of newest element
+        self.used = self.buffer.as_slice()[..self.used - 1]
+            .iter()
+            .rposition(|b| b == &0)
+            .map(|pos| pos + 1)
+            .unwrap_or(0);
+    }
+
+    /// Remove old elements until there is at least given amount
+    /// of free bytes after last element. Given len must not exceed buffer len
+    fn reserve(&mut self, len: usize) {
         // remove old commands to free space if its not enough
-        if self.buffer.len() < self.used + text.len() + 1 {
+        if self.buffer.len() < self.used + len {
             // self.used is at least 2 bytes (1 for element and 1 for null terminator)
             // how many bytes we should free, this is at least 1 byte
-            let required = self.used + text.len() + 1 - self.buffer.len();
+            let required = self.used + len - self.buffer.len();
             if required >= self.used {
                 self.used = 0;
             } else {
@@ -149,26 +325,14 @@ impl<B: Buffer> History<B> {
                 }
             }
         }
-
-        // now we have enough space after self.used to insert element
-        let null_pos = self.used + text.len();
-        // SAFETY: we ensured that buffer contains len + 1 bytes after self.used position
-        // and two buffers do not overlap since mutable reference to buffer is exclusive
-        unsafe {
-            utils::copy_nonoverlapping(
-                text.as_bytes(),
-                &mut self.buffer.as_slice_mut()[self.used..],
-                text.len(),
-            );
-        }
-        self.buffer.as_slice_mut()[null_pos] = 0;
-        self.used += text.len() + 1;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::history::History;
+    use rstest::rstest;
+
+    use crate::{cli::HistoryUsage, history::History};
 
     #[test]
     fn empty() {
@@ -178,6 +342,17 @@ mod tests {
         assert_eq!(history.next_older(), None);
     }
 
+    #[test]
+    fn zero_size() {
+        let mut history = History::new([0; 0]);
+
+        history.push("abc");
+
+        assert_eq!(history.next_newer(), None);
+        assert_eq!(history.next_older(), None);
+        assert!(!history.is_selected());
+    }
+
     #[test]
     fn text_with_nulls() {
         let mut history = History::new([0; 64]);
@@ -188,6 +363,64 @@ mod tests {
         assert_eq!(history.next_older(), None);
     }
 
+    #[test]
+    fn raw_bytes() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.push("de");
+        assert_eq!(history.as_bytes(), b"abc\0de\0");
+
+        let mut buffer = [0; 32];
+        buffer[..7].copy_from_slice(history.as_bytes());
+        let mut history = History::from_raw(buffer, 7);
+        assert_eq!(history.next_older(), Some("de"));
+        assert_eq!(history.next_older(), Some("abc"));
+        assert_eq!(history.next_older(), None);
+    }
+
+    #[rstest]
+    #[case(b"", true)]
+    #[case(b"abc\0", true)]
+    #[case(b"abc\0d\0", true)]
+    #[case(b"abc", false)]
+    #[case(b"\0", false)]
+    #[case(b"abc\0\0", false)]
+    #[case(b"ab\xFF\0", false)]
+    fn raw_bytes_validation(#[case] bytes: &[u8], #[case] valid: bool) {
+        let mut buffer = [0; 8];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        let history = History::from_raw(buffer, bytes.len());
+
+        assert_eq!(History::<[u8; 8]>::is_valid(bytes), valid);
+        assert_eq!(history.as_bytes(), if valid { bytes } else { b"" });
+    }
+
+    #[test]
+    fn raw_bytes_too_long() {
+        let history = History::from_raw([0; 4], 5);
+
+        assert_eq!(history.as_bytes(), b"");
+    }
+
+    #[test]
+    fn search() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.push("def");
+        history.push("xbc");
+
+        let found = history.search_older("bc", 32).unwrap();
+        assert_eq!(history.element_at(found), "xbc");
+        let found = history.search_older("bc", found).unwrap();
+        assert_eq!(history.element_at(found), "abc");
+        assert_eq!(history.search_older("bc", found), None);
+
+        assert_eq!(history.search_older("g", 32), None);
+        assert_eq!(history.search_older("", 32), None);
+    }
+
     #[test]
     fn navigation() {
         let mut history = History::new([0; 32]);
@@ -223,6 +456,116 @@ mod tests {
         assert_eq!(history.next_older(), None);
     }
 
+    #[test]
+    fn stash() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.push("def");
+        history.stash("gh");
+
+        assert_eq!(history.next_older(), Some("def"));
+        assert_eq!(history.next_older(), Some("abc"));
+        assert_eq!(history.next_newer(), Some("def"));
+        assert_eq!(history.next_newer(), None);
+        assert_eq!(history.take_stashed(), "gh");
+        assert_eq!(history.take_stashed(), "");
+    }
+
+    #[test]
+    fn stash_when_full() {
+        let mut history = History::new([0; 10]);
+
+        history.push("abc");
+        history.push("def");
+        history.stash("ghi");
+
+        assert_eq!(history.next_older(), Some("def"));
+        assert_eq!(history.next_older(), None);
+        assert_eq!(history.next_newer(), None);
+        assert_eq!(history.take_stashed(), "ghi");
+    }
+
+    #[test]
+    fn stash_overwritten_by_push() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.stash("gh");
+        history.push("def");
+
+        assert_eq!(history.take_stashed(), "");
+        assert_eq!(history.next_older(), Some("def"));
+        assert_eq!(history.next_older(), Some("abc"));
+    }
+
+    #[test]
+    fn deselect() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.push("def");
+        history.stash("gh");
+
+        assert_eq!(history.next_older(), Some("def"));
+        assert_eq!(history.next_older(), Some("abc"));
+
+        history.deselect();
+
+        assert!(!history.is_selected());
+        assert_eq!(history.take_stashed(), "");
+        assert_eq!(history.next_older(), Some("def"));
+    }
+
+    #[test]
+    fn remove_newest() {
+        let mut history = History::new([0; 32]);
+
+        history.push("abc");
+        history.push("def");
+        history.remove_newest();
+
+        assert_eq!(history.next_older(), Some("abc"));
+        assert_eq!(history.next_older(), None);
+
+        history.remove_newest();
+        history.remove_newest();
+
+        assert_eq!(history.next_older(), None);
+
+        history.push("ghi");
+
+        assert_eq!(history.next_older(), Some("ghi"));
+        assert_eq!(history.next_older(), None);
+    }
+
+    #[test]
+    fn usage() {
+        let mut history = History::new([0; 16]);
+
+        assert_eq!(
+            history.usage(),
+            HistoryUsage {
+                entries: 0,
+                free: 16,
+                used: 0
+            }
+        );
+
+        history.push("abc");
+        history.push("defg");
+        history.stash("hi");
+
+        assert_eq!(
+            history.usage(),
+            HistoryUsage {
+                entries: 2,
+                free: 7,
+                used: 9
+            }
+        );
+    }
+
     #[test]
     fn overflow_small() {
         let mut history = History::new([0; 12]);
@@ -5,81 +5,402 @@ use crate::{codes, utf8::Utf8Accum};
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ControlInput {
     Backspace,
+    /// Cursor should be moved to the start of previous word (Alt+B)
+    BackWord,
+    /// Current input should be abandoned (Ctrl+C)
+    Cancel,
+    /// Char at cursor should be removed
+    Delete,
+    /// Input from cursor to the end should be removed (Ctrl+K)
+    DeleteToEnd,
+    /// Input from the start to cursor should be removed (Ctrl+U)
+    DeleteToStart,
+    /// Word before cursor should be removed (Ctrl+W)
+    DeleteWord,
     Down,
+    /// Cursor should be moved to the end of input
+    End,
     Enter,
     Back,
     Forward,
+    /// Cursor should be moved to the end of next word (Alt+F)
+    ForwardWord,
+    /// Function key with number from 1 to 12
+    FunctionKey(u8),
+    /// Cursor should be moved to the start of input
+    Home,
+    /// Bracketed paste has ended (`codes::PASTE_END`)
+    PasteEnd,
+    /// Bracketed paste has started (`codes::PASTE_START`).
+    /// Until it ends, only chars are generated
+    /// and line breaks or tabs are generated as spaces
+    PasteStart,
+    /// History should be searched backwards for typed text (Ctrl+R)
+    ReverseSearch,
     Tab,
     Up,
+    /// Remote side asks to pause sending output
+    XOff,
+    /// Remote side asks to resume sending output
+    XOn,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Input<'a> {
     Control(ControlInput),
 
     /// Input is a single utf8 char.
+    /// It is preceded by U+FFFD, when it interrupted
+    /// invalid sequence and replacement is enabled.
     /// Slice is used to skip conversion from char to byte slice
     Char(&'a str),
 }
 
+/// Key (or sequence), decoded from input bytes, that can be bound to an action with `KeyMap`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Key {
+    /// Control byte, named by the char that is pressed together with Ctrl.
+    /// For example, `Ctrl('a')` is 0x01 and `Ctrl('?')` is DEL (0x7F)
+    Ctrl(char),
+
+    /// Esc followed by printable ascii char (sent by terminals for Alt+char)
+    Alt(char),
+
+    /// CSI sequence with its first numeric parameter (0 if not given)
+    /// and final byte. For example, `Esc[3~` is `Csi(3, b'~')`
+    Csi(u8, u8),
+
+    /// SS3 sequence with its final byte. For example, `EscOH` is `Ss3(b'H')`
+    Ss3(u8),
+}
+
+impl Key {
+    /// Returns key of given control byte (C0 or DEL)
+    fn from_control(byte: u8) -> Option<Self> {
+        match byte {
+            0x7F => Some(Key::Ctrl('?')),
+            byte if byte < 0x20 => Some(Key::Ctrl((byte + 0x40).to_ascii_lowercase() as char)),
+            _ => None,
+        }
+    }
+}
+
+/// Table of key bindings, that are checked before built-in ones,
+/// so keys can be rebound or new shortcuts can be added.
+/// Empty by default, so all keys have their usual meaning
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct KeyMap {
+    bindings: &'static [(Key, ControlInput)],
+}
+
+impl KeyMap {
+    /// Create key map with given bindings.
+    /// If key is given multiple times, first binding is used
+    ///
+    /// ```
+    /// use embedded_cli::input::{ControlInput, Key, KeyMap};
+    ///
+    /// const KEYMAP: KeyMap = KeyMap::new(&[
+    ///     // terminal sends DEL when backspace is pressed
+    ///     (Key::Ctrl('?'), ControlInput::Backspace),
+    ///     (Key::Ctrl('a'), ControlInput::Home),
+    ///     (Key::Ctrl('e'), ControlInput::End),
+    /// ]);
+    /// ```
+    pub const fn new(bindings: &'static [(Key, ControlInput)]) -> Self {
+        Self { bindings }
+    }
+
+    /// Returns action, that is bound to given key
+    pub fn get(&self, key: Key) -> Option<ControlInput> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == &key)
+            .map(|&(_, action)| action)
+    }
+}
+
 bitflags! {
     #[derive(Debug)]
     struct Flags: u8 {
         const CSI_STARTED = 1;
+        const SS3_STARTED = 1 << 1;
+        const CSI_PARAM_END = 1 << 2;
+        const PASTE = 1 << 3;
     }
 }
 
 #[derive(Debug)]
 pub struct InputGenerator {
+    /// First numeric parameter of CSI sequence
+    csi_param: u8,
     flags: Flags,
+    keymap: KeyMap,
     last_byte: u8,
+    /// Number of bytes received since bracketed paste started
+    paste_len: usize,
+    /// Paste is ended when more bytes are received, so lost paste end
+    /// doesn't leave input without echo forever
+    paste_limit: usize,
     utf8: Utf8Accum,
 }
 
+impl Default for InputGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InputGenerator {
     pub fn new() -> Self {
         // last byte matters only when its Esc, \r or \n, so can set it to just 0
         Self {
+            csi_param: 0,
             flags: Flags::empty(),
+            keymap: KeyMap::new(&[]),
             last_byte: 0,
+            paste_len: 0,
+            paste_limit: usize::MAX,
             utf8: Utf8Accum::default(),
         }
     }
 
+    /// Emit U+FFFD replacement char for invalid utf-8 input
+    /// instead of silently dropping it
+    pub fn with_utf8_replacement(self, replace_invalid: bool) -> Self {
+        Self {
+            utf8: self.utf8.with_replacement(replace_invalid),
+            ..self
+        }
+    }
+
+    /// Check given key bindings before built-in ones
+    pub fn with_keymap(self, keymap: KeyMap) -> Self {
+        Self { keymap, ..self }
+    }
+
+    /// End bracketed paste after given number of pasted bytes
+    pub fn with_paste_limit(self, paste_limit: usize) -> Self {
+        Self {
+            paste_limit,
+            ..self
+        }
+    }
+
+    /// Drop partially received escape sequence or utf-8 char
+    /// and leave bracketed paste
+    pub fn reset(&mut self) {
+        self.csi_param = 0;
+        self.flags = Flags::empty();
+        self.last_byte = 0;
+        self.paste_len = 0;
+        self.utf8.reset();
+    }
+
     pub fn accept(&mut self, byte: u8) -> Option<Input<'_>> {
+        if !self.flags.contains(Flags::PASTE) {
+            return self.decode(byte);
+        }
+        // Esc, that doesn't start a sequence, and too long paste end the paste,
+        // so input is not stuck when paste end was lost
+        let escaped = self.last_byte == codes::ESCAPE;
+        if escaped && !matches!(byte, b'[' | b'O') {
+            self.last_byte = byte;
+            self.flags.remove(Flags::PASTE);
+            return Some(Input::Control(ControlInput::PasteEnd));
+        }
+        if !escaped
+            && byte != codes::ESCAPE
+            && !self
+                .flags
+                .intersects(Flags::CSI_STARTED | Flags::SS3_STARTED)
+        {
+            if self.paste_len >= self.paste_limit {
+                self.last_byte = byte;
+                self.flags.remove(Flags::PASTE);
+                return Some(Input::Control(ControlInput::PasteEnd));
+            }
+            self.paste_len += 1;
+            // Ctrl+C abandons pasted text together with the rest of input
+            if self.is_cancel(byte) {
+                self.flags.remove(Flags::PASTE);
+            }
+        }
+        // pasted text is never executed, so it's inserted as a single line
+        match self.decode(byte)? {
+            Input::Control(ControlInput::Enter | ControlInput::Tab) => Some(Input::Char(" ")),
+            Input::Control(
+                control @ (ControlInput::Cancel
+                | ControlInput::PasteEnd
+                | ControlInput::XOff
+                | ControlInput::XOn),
+            ) => Some(Input::Control(control)),
+            Input::Control(_) => None,
+            Input::Char(text) => Some(Input::Char(text)),
+        }
+    }
+
+    /// Whether given single byte is decoded as Cancel
+    fn is_cancel(&self, byte: u8) -> bool {
+        match Key::from_control(byte).and_then(|key| self.keymap.get(key)) {
+            Some(control) => control == ControlInput::Cancel,
+            None => byte == codes::END_OF_TEXT,
+        }
+    }
+
+    fn decode(&mut self, byte: u8) -> Option<Input<'_>> {
         let last_byte = self.last_byte;
         self.last_byte = byte;
         if self.flags.contains(Flags::CSI_STARTED) {
             self.process_csi(byte).map(Input::Control)
-        } else if last_byte == codes::ESCAPE && byte == b'[' {
+        } else if self.flags.contains(Flags::SS3_STARTED) {
+            self.process_ss3(byte).map(Input::Control)
+        } else if codes::CSI == [last_byte, byte] {
             self.flags.set(Flags::CSI_STARTED, true);
             None
+        } else if byte == codes::CSI_8BIT && !self.utf8.is_partial() {
+            // same byte is valid continuation octet of utf-8 char,
+            // so it's a CSI only when no utf-8 char is being received
+            self.flags.set(Flags::CSI_STARTED, true);
+            None
+        } else if codes::SS3 == [last_byte, byte] {
+            // terminals in application cursor keys mode send SS3 sequences
+            self.flags.set(Flags::SS3_STARTED, true);
+            None
+        } else if let Some(control) = (last_byte == codes::ESCAPE && byte.is_ascii_graphic())
+            .then(|| self.keymap.get(Key::Alt(byte as char)))
+            .flatten()
+        {
+            Some(Input::Control(control))
+        } else if last_byte == codes::ESCAPE && matches!(byte, b'b' | b'f') {
+            // Alt+key is sent as Esc followed by key
+            let control = if byte == b'b' {
+                ControlInput::BackWord
+            } else {
+                ControlInput::ForwardWord
+            };
+            Some(Input::Control(control))
         } else {
             self.process_single(byte, last_byte)
         }
     }
 
+    /// Accept longest run of printable ascii chars at the start of given bytes at once.
+    ///
+    /// Returns None if run is empty or if some sequence is being received,
+    /// so bytes must be accepted one by one
+    pub fn accept_text<'b>(&mut self, bytes: &'b [u8]) -> Option<&'b str> {
+        if self
+            .flags
+            .intersects(Flags::CSI_STARTED | Flags::SS3_STARTED)
+            || self.utf8.is_partial()
+            || self.last_byte == codes::ESCAPE
+        {
+            return None;
+        }
+        let pasting = self.flags.contains(Flags::PASTE);
+        let bytes = if pasting {
+            let remaining = self.paste_limit.saturating_sub(self.paste_len);
+            bytes.get(..remaining).unwrap_or(bytes)
+        } else {
+            bytes
+        };
+        let len = bytes
+            .iter()
+            .position(|b| !(0x20..0x7F).contains(b))
+            .unwrap_or(bytes.len());
+        let text = bytes.get(..len)?;
+        self.last_byte = *text.last()?;
+        if pasting {
+            self.paste_len += len;
+        }
+        // SAFETY: text consists only of ascii chars
+        Some(unsafe { core::str::from_utf8_unchecked(text) })
+    }
+
     fn process_csi(&mut self, byte: u8) -> Option<ControlInput> {
-        // skip all parameter bytes and process only last byte in CSI sequence
+        // only first numeric parameter is used, all other parameter bytes are skipped
         if (0x40..=0x7E).contains(&byte) {
-            self.flags.set(Flags::CSI_STARTED, false);
-            let control = match byte {
-                b'A' => ControlInput::Up,
-                b'B' => ControlInput::Down,
-                b'C' => ControlInput::Forward,
-                b'D' => ControlInput::Back,
+            self.flags.remove(Flags::CSI_STARTED | Flags::CSI_PARAM_END);
+            let param = self.csi_param;
+            self.csi_param = 0;
+            // paste markers can't be rebound, otherwise pasted text would be executed
+            match (byte, param) {
+                (b'~', codes::PASTE_START_PARAM) => {
+                    self.flags.insert(Flags::PASTE);
+                    self.paste_len = 0;
+                    return Some(ControlInput::PasteStart);
+                }
+                (b'~', codes::PASTE_END_PARAM) => {
+                    self.flags.remove(Flags::PASTE);
+                    return Some(ControlInput::PasteEnd);
+                }
+                _ => {}
+            }
+            if let Some(control) = self.keymap.get(Key::Csi(param, byte)) {
+                return Some(control);
+            }
+            let control = match (byte, param) {
+                (b'A', _) => ControlInput::Up,
+                (b'B', _) => ControlInput::Down,
+                (b'C', _) => ControlInput::Forward,
+                (b'D', _) => ControlInput::Back,
+                (b'F', _) | (b'~', 4 | 8) => ControlInput::End,
+                (b'H', _) | (b'~', 1 | 7) => ControlInput::Home,
+                (b'~', 3) => ControlInput::Delete,
+                // F1-F4 with modifiers
+                (b'P'..=b'S', _) => ControlInput::FunctionKey(byte - b'P' + 1),
+                (b'~', 11..=15) => ControlInput::FunctionKey(param - 10),
+                (b'~', 17..=21) => ControlInput::FunctionKey(param - 11),
+                (b'~', 23..=24) => ControlInput::FunctionKey(param - 12),
                 _ => return None,
             };
             Some(control)
         } else {
+            if byte.is_ascii_digit() && !self.flags.contains(Flags::CSI_PARAM_END) {
+                self.csi_param = self
+                    .csi_param
+                    .saturating_mul(10)
+                    .saturating_add(byte - b'0');
+            } else {
+                self.flags.insert(Flags::CSI_PARAM_END);
+            }
             None
         }
     }
 
+    fn process_ss3(&mut self, byte: u8) -> Option<ControlInput> {
+        // SS3 sequence always consists of single byte after introducer
+        self.flags.set(Flags::SS3_STARTED, false);
+        if let Some(control) = self.keymap.get(Key::Ss3(byte)) {
+            return Some(control);
+        }
+        let control = match byte {
+            b'A' => ControlInput::Up,
+            b'B' => ControlInput::Down,
+            b'C' => ControlInput::Forward,
+            b'D' => ControlInput::Back,
+            b'F' => ControlInput::End,
+            b'H' => ControlInput::Home,
+            b'P'..=b'S' => ControlInput::FunctionKey(byte - b'P' + 1),
+            _ => return None,
+        };
+        Some(control)
+    }
+
     fn process_single(&mut self, byte: u8, last_byte: u8) -> Option<Input<'_>> {
+        if byte < 0x20 {
+            // control byte interrupts any partially received char
+            self.utf8.reset();
+        }
+        if let Some(control) = Key::from_control(byte).and_then(|key| self.keymap.get(key)) {
+            return Some(Input::Control(control));
+        }
         let control = match byte {
             codes::BACKSPACE => ControlInput::Backspace,
 
+            codes::END_OF_TEXT => ControlInput::Cancel,
+
             // ignore \r if \n already received (and converted to Enter)
             codes::CARRIAGE_RETURN if last_byte != codes::LINE_FEED => ControlInput::Enter,
 
@@ -88,6 +409,18 @@ impl InputGenerator {
 
             codes::TABULATION => ControlInput::Tab,
 
+            codes::VERTICAL_TAB => ControlInput::DeleteToEnd,
+
+            codes::NEGATIVE_ACKNOWLEDGE => ControlInput::DeleteToStart,
+
+            codes::DEVICE_CONTROL_2 => ControlInput::ReverseSearch,
+
+            codes::END_OF_TRANSMISSION_BLOCK => ControlInput::DeleteWord,
+
+            codes::XOFF => ControlInput::XOff,
+
+            codes::XON => ControlInput::XOn,
+
             // process only non control ascii chars (and utf8)
             byte if byte >= 0x20 => return self.utf8.push_byte(byte).map(Input::Char),
 
@@ -101,7 +434,9 @@ impl InputGenerator {
 mod tests {
     use rstest::rstest;
 
-    use super::{ControlInput, Input, InputGenerator};
+    use crate::codes;
+
+    use super::{ControlInput, Input, InputGenerator, Key, KeyMap};
 
     #[rstest]
     #[case(b"\x1B[A", ControlInput::Up)]
@@ -109,6 +444,28 @@ mod tests {
     #[case(b"\x1B[24B", ControlInput::Down)]
     #[case(b"\x1B[C", ControlInput::Forward)]
     #[case(b"\x1B[D", ControlInput::Back)]
+    #[case(b"\x1B[3~", ControlInput::Delete)]
+    #[case(b"\x1B[H", ControlInput::Home)]
+    #[case(b"\x1B[1~", ControlInput::Home)]
+    #[case(b"\x1B[7~", ControlInput::Home)]
+    #[case(b"\x1B[F", ControlInput::End)]
+    #[case(b"\x1B[4~", ControlInput::End)]
+    #[case(b"\x1B[8~", ControlInput::End)]
+    #[case(b"\x1B[11~", ControlInput::FunctionKey(1))]
+    #[case(b"\x1B[15~", ControlInput::FunctionKey(5))]
+    #[case(b"\x1B[17~", ControlInput::FunctionKey(6))]
+    #[case(b"\x1B[21~", ControlInput::FunctionKey(10))]
+    #[case(b"\x1B[23~", ControlInput::FunctionKey(11))]
+    #[case(b"\x1B[24;5~", ControlInput::FunctionKey(12))]
+    #[case(b"\x1B[1;2P", ControlInput::FunctionKey(1))]
+    #[case(b"\x1B[1;2S", ControlInput::FunctionKey(4))]
+    #[case(b"\x9BA", ControlInput::Up)]
+    #[case(b"\x9BD", ControlInput::Back)]
+    #[case(b"\x9B24~", ControlInput::FunctionKey(12))]
+    #[case(codes::CURSOR_UP, ControlInput::Up)]
+    #[case(codes::CURSOR_DOWN, ControlInput::Down)]
+    #[case(codes::CURSOR_FORWARD, ControlInput::Forward)]
+    #[case(codes::CURSOR_BACKWARD, ControlInput::Back)]
     fn process_csi_control(#[case] bytes: &[u8], #[case] expected: ControlInput) {
         let mut accum = InputGenerator::new();
 
@@ -123,10 +480,51 @@ mod tests {
     }
 
     #[rstest]
+    #[case(b"\x1BOA", ControlInput::Up)]
+    #[case(b"\x1BOB", ControlInput::Down)]
+    #[case(b"\x1BOC", ControlInput::Forward)]
+    #[case(b"\x1BOD", ControlInput::Back)]
+    #[case(b"\x1BOH", ControlInput::Home)]
+    #[case(b"\x1BOF", ControlInput::End)]
+    #[case(b"\x1BOP", ControlInput::FunctionKey(1))]
+    #[case(b"\x1BOS", ControlInput::FunctionKey(4))]
+    fn process_ss3_control(#[case] bytes: &[u8], #[case] expected: ControlInput) {
+        let mut accum = InputGenerator::new();
+
+        for &b in &bytes[..bytes.len() - 1] {
+            assert_eq!(accum.accept(b), None);
+        }
+
+        assert_eq!(
+            accum.accept(*bytes.last().unwrap()),
+            Some(Input::Control(expected))
+        );
+        assert_eq!(accum.accept(b'A'), Some(Input::Char("A")));
+    }
+
+    #[rstest]
+    #[case(b"\x1Bb", ControlInput::BackWord)]
+    #[case(b"\x1Bf", ControlInput::ForwardWord)]
+    fn process_alt_control(#[case] bytes: &[u8], #[case] expected: ControlInput) {
+        let mut accum = InputGenerator::new();
+
+        assert_eq!(accum.accept(bytes[0]), None);
+        assert_eq!(accum.accept(bytes[1]), Some(Input::Control(expected)));
+        assert_eq!(accum.accept(b'b'), Some(Input::Char("b")));
+    }
+
+    #[rstest]
+    #[case(0x03, ControlInput::Cancel)]
     #[case(0x08, ControlInput::Backspace)]
     #[case(b'\t', ControlInput::Tab)]
     #[case(b'\r', ControlInput::Enter)]
     #[case(b'\n', ControlInput::Enter)]
+    #[case(0x0B, ControlInput::DeleteToEnd)]
+    #[case(0x12, ControlInput::ReverseSearch)]
+    #[case(0x15, ControlInput::DeleteToStart)]
+    #[case(0x17, ControlInput::DeleteWord)]
+    #[case(0x11, ControlInput::XOn)]
+    #[case(0x13, ControlInput::XOff)]
     fn process_c0_control(#[case] byte: u8, #[case] expected: ControlInput) {
         assert_eq!(
             InputGenerator::new().accept(byte),
@@ -134,6 +532,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn process_utf8_with_csi_byte() {
+        let mut accum = InputGenerator::new();
+
+        // second octet of this char is same as 8-bit CSI
+        let bytes = "ś".as_bytes();
+        assert_eq!(bytes[1], 0x9B);
+
+        assert_eq!(accum.accept(bytes[0]), None);
+        assert_eq!(accum.accept(bytes[1]), Some(Input::Char("ś")));
+        assert_eq!(accum.accept(b'A'), Some(Input::Char("A")));
+    }
+
     #[test]
     fn process_crlf() {
         let mut accum = InputGenerator::new();
@@ -177,6 +588,103 @@ mod tests {
         assert_eq!(accum.accept(b'b'), Some(Input::Char("b")));
         assert_eq!(accum.accept(0x1B), None);
         assert_eq!(accum.accept(b'['), None);
+        assert_eq!(accum.accept(b'2'), None);
+        assert_eq!(accum.accept(b'0'), None);
+        assert_eq!(
+            accum.accept(b'~'),
+            Some(Input::Control(ControlInput::FunctionKey(9)))
+        );
+        assert_eq!(accum.accept(0x1B), None);
+        assert_eq!(accum.accept(b'['), None);
         assert_eq!(accum.accept(b'B'), Some(Input::Control(ControlInput::Down)));
     }
+
+    #[test]
+    fn process_bracketed_paste() {
+        let mut accum = InputGenerator::new();
+
+        let (&last, start) = codes::PASTE_START.split_last().unwrap();
+        for &b in start {
+            assert_eq!(accum.accept(b), None);
+        }
+        assert_eq!(
+            accum.accept(last),
+            Some(Input::Control(ControlInput::PasteStart))
+        );
+        assert_eq!(accum.accept(b'a'), Some(Input::Char("a")));
+        assert_eq!(accum.accept(b'\r'), Some(Input::Char(" ")));
+        assert_eq!(accum.accept(b'\n'), None);
+        assert_eq!(accum.accept(b'\t'), Some(Input::Char(" ")));
+        assert_eq!(accum.accept(codes::DEVICE_CONTROL_2), None);
+        assert_eq!(accum.accept_text(b"bc\x1B"), Some("bc"));
+        let (&last, end) = codes::PASTE_END.split_last().unwrap();
+        for &b in end {
+            assert_eq!(accum.accept(b), None);
+        }
+        assert_eq!(
+            accum.accept(last),
+            Some(Input::Control(ControlInput::PasteEnd))
+        );
+        assert_eq!(
+            accum.accept(b'\r'),
+            Some(Input::Control(ControlInput::Enter))
+        );
+    }
+
+    #[rstest]
+    #[case(b"\x1Bx", ControlInput::PasteEnd)]
+    #[case(b"\x03", ControlInput::Cancel)]
+    #[case(b"abcd", ControlInput::PasteEnd)]
+    fn process_unterminated_paste(#[case] bytes: &[u8], #[case] expected: ControlInput) {
+        let mut accum = InputGenerator::new().with_paste_limit(3);
+
+        for &b in codes::PASTE_START {
+            accum.accept(b);
+        }
+        let (&last, bytes) = bytes.split_last().unwrap();
+        for &b in bytes {
+            assert_ne!(accum.accept(b), Some(Input::Control(expected)));
+        }
+        assert_eq!(accum.accept(last), Some(Input::Control(expected)));
+
+        // paste is left, so line breaks are not replaced
+        assert_eq!(
+            accum.accept(b'\r'),
+            Some(Input::Control(ControlInput::Enter))
+        );
+    }
+
+    #[rstest]
+    #[case(b"\x7F", ControlInput::Backspace)]
+    #[case(b"\x01", ControlInput::Home)]
+    #[case(b"\x08", ControlInput::DeleteWord)]
+    #[case(b"\x1Bd", ControlInput::DeleteToEnd)]
+    #[case(b"\x1B[2~", ControlInput::Delete)]
+    #[case(b"\x1B[A", ControlInput::Down)]
+    #[case(b"\x1BOA", ControlInput::Down)]
+    fn keymap_bindings(#[case] bytes: &[u8], #[case] expected: ControlInput) {
+        const KEYMAP: KeyMap = KeyMap::new(&[
+            (Key::Ctrl('?'), ControlInput::Backspace),
+            (Key::Ctrl('a'), ControlInput::Home),
+            (Key::Ctrl('h'), ControlInput::DeleteWord),
+            (Key::Alt('d'), ControlInput::DeleteToEnd),
+            (Key::Csi(2, b'~'), ControlInput::Delete),
+            (Key::Csi(0, b'A'), ControlInput::Down),
+            (Key::Ss3(b'A'), ControlInput::Down),
+        ]);
+        let mut accum = InputGenerator::new().with_keymap(KEYMAP);
+
+        let (last, bytes) = bytes.split_last().unwrap();
+        for &b in bytes {
+            assert_eq!(accum.accept(b), None);
+        }
+        assert_eq!(accum.accept(*last), Some(Input::Control(expected)));
+
+        // other keys are not affected
+        assert_eq!(accum.accept(b'a'), Some(Input::Char("a")));
+        assert_eq!(
+            accum.accept(codes::END_OF_TEXT),
+            Some(Input::Control(ControlInput::Cancel))
+        );
+    }
 }
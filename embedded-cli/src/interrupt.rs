@@ -0,0 +1,298 @@
+//! Output from interrupt context (or panic handler).
+//!
+//! Cli can't be used from interrupt, since it might be processing input
+//! at that moment. Instead, bytes are pushed to `InterruptQueue` through
+//! `InterruptWriter` and are later written above the prompt from the main loop
+//! with `Cli::write_queued`:
+//!
+//! ```
+//! use embedded_cli::interrupt::InterruptQueue;
+//!
+//! static QUEUE: InterruptQueue<128> = InterruptQueue::new();
+//!
+//! fn on_interrupt() {
+//!     // SAFETY: queue is written only from this interrupt
+//!     let mut writer = unsafe { QUEUE.writer() };
+//!     ufmt::uwrite!(writer, "overcurrent detected\n").unwrap();
+//! }
+//! # on_interrupt();
+//! ```
+//!
+//! Queue is lock-free, but it needs atomic compare-and-swap, so it's available
+//! only on targets with pointer-sized atomics (`target_has_atomic = "ptr"`,
+//! so not on AVR or thumbv6m). Queue capacity must be a power of two.
+//! When queue is full, extra bytes are dropped and writer never blocks.
+
+use core::{
+    cell::UnsafeCell,
+    convert::Infallible,
+    fmt::Debug,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use embedded_io::{ErrorType, Write};
+use ufmt::uWrite;
+
+/// Single producer, single consumer queue of bytes,
+/// that can be stored in a static.
+/// Capacity `N` must be a power of two (checked at compile time)
+pub struct InterruptQueue<const N: usize> {
+    buffer: UnsafeCell<[u8; N]>,
+
+    /// Total number of bytes read (only modified by reader)
+    head: AtomicUsize,
+
+    /// Some bytes were dropped since queue was full
+    overflow: AtomicBool,
+
+    /// Total number of bytes written (only modified by writer)
+    tail: AtomicUsize,
+}
+
+// SAFETY: writer only modifies free part of the buffer and reader
+// only reads filled part of it. Boundaries are published with release stores.
+// There is at most one writer and one reader as required by `writer` and `reader`
+unsafe impl<const N: usize> Sync for InterruptQueue<N> {}
+
+impl<const N: usize> Debug for InterruptQueue<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InterruptQueue")
+            .field("len", &self.len())
+            .field("overflow", &self.overflow.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl<const N: usize> Default for InterruptQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> InterruptQueue<N> {
+    /// Head and tail are free-running counters, so they map to the same
+    /// buffer index after wrap only if capacity is a power of two
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "capacity of InterruptQueue must be a power of two"
+    );
+
+    pub const fn new() -> Self {
+        let () = Self::POWER_OF_TWO;
+        Self {
+            buffer: UnsafeCell::new([0; N]),
+            head: AtomicUsize::new(0),
+            overflow: AtomicBool::new(false),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns how many bytes are waiting to be written by cli
+    pub fn len(&self) -> usize {
+        self.tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns writer, that pushes bytes to this queue.
+    ///
+    /// # Safety
+    ///
+    /// Only one writer may be used at a time: contexts that write
+    /// to the same queue must not preempt each other
+    /// (e.g. use separate queue per interrupt priority).
+    pub unsafe fn writer(&self) -> InterruptWriter<'_, N> {
+        InterruptWriter { queue: self }
+    }
+
+    /// Returns reader, that is passed to `Cli::write_queued`.
+    ///
+    /// # Safety
+    ///
+    /// Only one reader may be used at a time (usually it is created
+    /// once in the main loop).
+    pub unsafe fn reader(&self) -> InterruptReader<'_, N> {
+        InterruptReader { queue: self }
+    }
+}
+
+/// Writing half of `InterruptQueue`
+pub struct InterruptWriter<'a, const N: usize> {
+    queue: &'a InterruptQueue<N>,
+}
+
+impl<'a, const N: usize> Debug for InterruptWriter<'a, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InterruptWriter")
+            .field("queue", self.queue)
+            .finish()
+    }
+}
+
+impl<'a, const N: usize> InterruptWriter<'a, N> {
+    /// Push as many bytes to queue as fit. Other bytes are dropped.
+    /// Returns number of pushed bytes
+    pub fn push(&mut self, bytes: &[u8]) -> usize {
+        let queue = self.queue;
+        let head = queue.head.load(Ordering::Acquire);
+        let tail = queue.tail.load(Ordering::Relaxed);
+        let free = N - tail.wrapping_sub(head);
+        let len = bytes.len().min(free);
+        if len < bytes.len() {
+            queue.overflow.store(true, Ordering::Relaxed);
+        }
+
+        let buffer = queue.buffer.get() as *mut u8;
+        for (i, &b) in bytes[..len].iter().enumerate() {
+            // SAFETY: index is inside buffer and points to its free part,
+            // that reader doesn't access until tail is published
+            unsafe { buffer.add(tail.wrapping_add(i) % N).write(b) };
+        }
+        queue.tail.store(tail.wrapping_add(len), Ordering::Release);
+        len
+    }
+}
+
+impl<'a, const N: usize> ErrorType for InterruptWriter<'a, N> {
+    type Error = Infallible;
+}
+
+impl<'a, const N: usize> Write for InterruptWriter<'a, N> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        // bytes that don't fit are dropped, so writer never blocks
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize> uWrite for InterruptWriter<'a, N> {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Infallible> {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize> core::fmt::Write for InterruptWriter<'a, N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Reading half of `InterruptQueue`
+pub struct InterruptReader<'a, const N: usize> {
+    queue: &'a InterruptQueue<N>,
+}
+
+impl<'a, const N: usize> Debug for InterruptReader<'a, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InterruptReader")
+            .field("queue", self.queue)
+            .finish()
+    }
+}
+
+impl<'a, const N: usize> InterruptReader<'a, N> {
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns true if some bytes were dropped since last call
+    pub fn take_overflow(&mut self) -> bool {
+        // flag is cleared atomically, so overflow, that happens meanwhile, is not lost
+        self.queue.overflow.swap(false, Ordering::Relaxed)
+    }
+
+    /// Returns contiguous part of queued bytes.
+    /// Bytes are removed from queue with `consume`
+    pub(crate) fn peek(&self) -> &[u8] {
+        let queue = self.queue;
+        let tail = queue.tail.load(Ordering::Acquire);
+        let head = queue.head.load(Ordering::Relaxed);
+        let len = tail.wrapping_sub(head);
+        if len == 0 {
+            return &[];
+        }
+        let start = head % N;
+        let len = len.min(N - start);
+        let buffer = queue.buffer.get() as *const u8;
+        // SAFETY: range is inside buffer and points to its filled part,
+        // that writer doesn't modify until head is moved past it
+        unsafe { core::slice::from_raw_parts(buffer.add(start), len) }
+    }
+
+    pub(crate) fn consume(&mut self, len: usize) {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        self.queue
+            .head
+            .store(head.wrapping_add(len), Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::Ordering;
+
+    use embedded_io::Write;
+
+    use super::InterruptQueue;
+
+    #[test]
+    fn push_wrapped() {
+        let queue = InterruptQueue::<8>::new();
+        let mut writer = unsafe { queue.writer() };
+        let mut reader = unsafe { queue.reader() };
+
+        assert_eq!(writer.push(b"abcdef"), 6);
+        assert_eq!(reader.peek(), b"abcdef");
+        reader.consume(4);
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(writer.push(b"ghijk"), 5);
+        assert_eq!(reader.peek(), b"efgh");
+        reader.consume(4);
+        assert_eq!(reader.peek(), b"ijk");
+        reader.consume(3);
+        assert!(reader.is_empty());
+        assert!(!reader.take_overflow());
+    }
+
+    #[test]
+    fn counters_wrapped() {
+        let queue = InterruptQueue::<8>::new();
+        queue.head.store(usize::MAX - 2, Ordering::Relaxed);
+        queue.tail.store(usize::MAX - 2, Ordering::Relaxed);
+        let mut writer = unsafe { queue.writer() };
+        let mut reader = unsafe { queue.reader() };
+
+        assert_eq!(writer.push(b"abcdef"), 6);
+        assert_eq!(queue.len(), 6);
+        assert_eq!(reader.peek(), b"abc");
+        reader.consume(3);
+        assert_eq!(reader.peek(), b"def");
+        reader.consume(3);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn drop_on_overflow() {
+        let queue = InterruptQueue::<4>::new();
+        let mut writer = unsafe { queue.writer() };
+        let mut reader = unsafe { queue.reader() };
+
+        assert_eq!(writer.write(b"abcdef").unwrap(), 6);
+        assert_eq!(reader.peek(), b"abcd");
+        assert!(reader.take_overflow());
+        assert!(!reader.take_overflow());
+    }
+}
@@ -1,31 +1,57 @@
 #![warn(rust_2018_idioms, missing_debug_implementations)]
 #![no_std]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::expect_used,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used
+    )
+)]
 
 // std used for simpler testing
-#[cfg(test)]
+#[cfg(any(test, feature = "fuzz", feature = "metadata", feature = "owned"))]
 extern crate std;
 
 pub mod arguments;
+#[cfg(feature = "async")]
+pub mod async_cli;
 pub mod autocomplete;
 pub mod buffer;
 mod builder;
+#[cfg(feature = "builtins")]
+mod builtins;
 pub mod cli;
 pub mod codes;
 pub mod command;
+#[cfg(any(feature = "itm", all(feature = "semihosting", target_arch = "arm")))]
+pub mod debug;
 mod editor;
+// also compiled in tests, so input pipeline is stress tested by default
+#[cfg(any(test, feature = "fuzz"))]
+pub mod fuzz;
 pub mod help;
 #[cfg(feature = "history")]
 mod history;
-mod input;
+pub mod input;
+// queue needs atomic read-modify-write operations (not available on AVR and thumbv6m)
+#[cfg(target_has_atomic = "ptr")]
+pub mod interrupt;
+#[cfg(feature = "metadata")]
+pub mod metadata;
+pub mod pager;
+pub mod prompt;
 pub mod service;
 mod token;
 mod utf8;
 mod utils;
+mod width;
 pub mod writer;
 
 /// Macro available if embedded-cli is built with `features = ["macros"]`.
 #[cfg(feature = "embedded-cli-macros")]
-pub use embedded_cli_macros::{Command, CommandGroup};
+pub use embedded_cli_macros::{Command, CommandGroup, ValueEnum};
 
 // Used by generated code. Not public API.
 #[doc(hidden)]
@@ -0,0 +1,253 @@
+//! Description of all commands, that can be exported to the host
+//! (for example, to generate shell completion scripts).
+//!
+//! Requires `std`, so it should be used only in host tools
+
+use std::{string::String, vec::Vec};
+
+use crate::command::RawCommand;
+
+/// Kind of command argument
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArgKind {
+    /// Flag without value (`--verbose` or `-v`)
+    Flag,
+
+    /// Option with value (`--level 3` or `-l 3`)
+    Option,
+
+    /// Positional argument
+    Positional,
+}
+
+impl ArgKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ArgKind::Flag => "flag",
+            ArgKind::Option => "option",
+            ArgKind::Positional => "positional",
+        }
+    }
+}
+
+/// Kind of argument value, that host tools can use
+/// to suggest values (set with `#[arg(value_hint = FilePath)]`)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ValueHint {
+    /// Any value
+    #[default]
+    Other,
+
+    /// Path to file
+    FilePath,
+
+    /// Host name or address
+    Hostname,
+}
+
+impl ValueHint {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ValueHint::Other => "other",
+            ValueHint::FilePath => "file_path",
+            ValueHint::Hostname => "hostname",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArgInfo {
+    pub kind: ArgKind,
+    pub long: Option<&'static str>,
+    pub short: Option<char>,
+    pub value_name: &'static str,
+    pub value_hint: ValueHint,
+    pub required: bool,
+    pub help: Option<&'static str>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommandInfo {
+    pub name: &'static str,
+
+    /// Short help of the command (first paragraph of doc comment)
+    pub about: Option<&'static str>,
+
+    /// Full help of the command (whole doc comment)
+    pub help: Option<&'static str>,
+
+    pub args: Vec<ArgInfo>,
+
+    pub subcommands: Vec<CommandInfo>,
+}
+
+/// Provides description of all commands.
+///
+/// Implemented by `Command` and `CommandGroup` derives
+/// when `metadata` feature is enabled
+pub trait Metadata {
+    fn commands() -> Vec<CommandInfo>;
+}
+
+impl<'a> Metadata for RawCommand<'a> {
+    fn commands() -> Vec<CommandInfo> {
+        // raw command can be anything
+        Vec::new()
+    }
+}
+
+/// Serializes description of all commands to JSON array
+pub fn to_json<C: Metadata>() -> String {
+    let mut json = String::new();
+    write_commands(&mut json, &C::commands());
+    json
+}
+
+fn write_commands(json: &mut String, commands: &[CommandInfo]) {
+    json.push('[');
+    for (i, command) in commands.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"name\":");
+        write_str(json, command.name);
+        json.push_str(",\"about\":");
+        write_opt_str(json, command.about);
+        json.push_str(",\"help\":");
+        write_opt_str(json, command.help);
+        json.push_str(",\"args\":[");
+        for (i, arg) in command.args.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_arg(json, arg);
+        }
+        json.push_str("],\"subcommands\":");
+        write_commands(json, &command.subcommands);
+        json.push('}');
+    }
+    json.push(']');
+}
+
+fn write_arg(json: &mut String, arg: &ArgInfo) {
+    json.push_str("{\"kind\":");
+    write_str(json, arg.kind.as_str());
+    json.push_str(",\"long\":");
+    write_opt_str(json, arg.long);
+    json.push_str(",\"short\":");
+    match arg.short {
+        Some(short) => {
+            let mut buf = [0; 4];
+            write_str(json, short.encode_utf8(&mut buf));
+        }
+        None => json.push_str("null"),
+    }
+    json.push_str(",\"value_name\":");
+    write_str(json, arg.value_name);
+    json.push_str(",\"value_hint\":");
+    write_str(json, arg.value_hint.as_str());
+    json.push_str(",\"required\":");
+    json.push_str(if arg.required { "true" } else { "false" });
+    json.push_str(",\"help\":");
+    write_opt_str(json, arg.help);
+    json.push('}');
+}
+
+fn write_opt_str(json: &mut String, text: Option<&str>) {
+    match text {
+        Some(text) => write_str(json, text),
+        None => json.push_str("null"),
+    }
+}
+
+fn write_str(json: &mut String, text: &str) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                const HEX: &[u8; 16] = b"0123456789abcdef";
+                json.push_str("\\u00");
+                json.push(HEX[(c as usize) >> 4] as char);
+                json.push(HEX[(c as usize) & 0xF] as char);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::{to_json, ArgInfo, ArgKind, CommandInfo, Metadata, ValueHint};
+
+    struct Commands;
+
+    impl Metadata for Commands {
+        fn commands() -> vec::Vec<CommandInfo> {
+            vec![
+                CommandInfo {
+                    name: "set",
+                    about: Some("Set \"value\""),
+                    help: None,
+                    args: vec![
+                        ArgInfo {
+                            kind: ArgKind::Flag,
+                            long: Some("force"),
+                            short: Some('f'),
+                            value_name: "FORCE",
+                            value_hint: ValueHint::Other,
+                            required: false,
+                            help: None,
+                        },
+                        ArgInfo {
+                            kind: ArgKind::Positional,
+                            long: None,
+                            short: None,
+                            value_name: "FILE",
+                            value_hint: ValueHint::FilePath,
+                            required: true,
+                            help: Some("Line 1\nLine\t2\u{1}"),
+                        },
+                    ],
+                    subcommands: vec![],
+                },
+                CommandInfo {
+                    name: "net",
+                    about: None,
+                    help: None,
+                    args: vec![],
+                    subcommands: vec![CommandInfo {
+                        name: "up",
+                        about: None,
+                        help: Some("Long help"),
+                        args: vec![],
+                        subcommands: vec![],
+                    }],
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            to_json::<Commands>(),
+            concat!(
+                r#"[{"name":"set","about":"Set \"value\"","help":null,"args":["#,
+                r#"{"kind":"flag","long":"force","short":"f","value_name":"FORCE","value_hint":"other","required":false,"help":null},"#,
+                r#"{"kind":"positional","long":null,"short":null,"value_name":"FILE","value_hint":"file_path","required":true,"help":"Line 1\nLine\t2\u0001"}"#,
+                r#"],"subcommands":[]},"#,
+                r#"{"name":"net","about":null,"help":null,"args":[],"subcommands":["#,
+                r#"{"name":"up","about":null,"help":"Long help","args":[],"subcommands":[]}"#,
+                r#"]}]"#,
+            )
+        );
+    }
+}
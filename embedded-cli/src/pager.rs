@@ -0,0 +1,117 @@
+use core::fmt::Debug;
+
+use embedded_io::{Error, Write};
+use ufmt::uWrite;
+
+use crate::writer::Writer;
+
+/// Function, that writes paged output starting from the line with given index.
+///
+/// It's called with context, given to `CliHandle::pager`, and index of the
+/// first line of each page, so each page is produced on demand
+/// and command itself is processed only once.
+pub type PageSource<W, E> = fn(&mut Pager<'_, '_, W, E>, usize, usize) -> Result<(), E>;
+
+/// Writer wrapper that splits long output into pages.
+///
+/// Only lines of current page are written to the underlying writer,
+/// all other lines are discarded. If there is more output after the
+/// current page, CLI pauses with `--More--` and waits for a key.
+/// Any key (except `q`) shows next page by calling the same
+/// `PageSource` with the same context and index of the first line of that page
+/// (see `CliHandle::pager`).
+pub struct Pager<'h, 'w, W: Write<Error = E>, E: Error> {
+    /// Index of the line that is being written now (relative to current page)
+    line: usize,
+
+    /// How many lines are shown on each page
+    lines: usize,
+
+    /// Set to true when some output was discarded after current page
+    more: bool,
+
+    writer: &'h mut Writer<'w, W, E>,
+}
+
+impl<'h, 'w, W: Write<Error = E>, E: Error> Debug for Pager<'h, 'w, W, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pager")
+            .field("line", &self.line)
+            .field("lines", &self.lines)
+            .field("more", &self.more)
+            .finish()
+    }
+}
+
+impl<'h, 'w, W: Write<Error = E>, E: Error> Pager<'h, 'w, W, E> {
+    pub(crate) fn new(writer: &'h mut Writer<'w, W, E>, lines: usize) -> Self {
+        Self {
+            line: 0,
+            lines,
+            more: false,
+            writer,
+        }
+    }
+
+    /// Whether some output was discarded, so there is next page
+    pub(crate) fn has_more(&self) -> bool {
+        self.more
+    }
+
+    /// Whether current page is already full and all
+    /// further output will be discarded
+    pub fn is_full(&self) -> bool {
+        self.line >= self.lines
+    }
+
+    pub fn write_str(&mut self, mut text: &str) -> Result<(), E> {
+        while !text.is_empty() {
+            if self.is_full() {
+                self.more = true;
+                return Ok(());
+            }
+
+            let (line, newline, rest) =
+                if let Some(pos) = text.as_bytes().iter().position(|&b| b == b'\n') {
+                    // SAFETY: pos is inside text slice and points to ascii char
+                    unsafe {
+                        (
+                            text.get_unchecked(..pos),
+                            true,
+                            text.get_unchecked(pos + 1..),
+                        )
+                    }
+                } else {
+                    (text, false, "")
+                };
+
+            self.writer.write_str(line)?;
+            if newline {
+                self.writer.writeln_str("")?;
+                self.line += 1;
+            }
+            text = rest;
+        }
+        Ok(())
+    }
+
+    pub fn writeln_str(&mut self, text: &str) -> Result<(), E> {
+        self.write_str(text)?;
+        self.write_str("\n")
+    }
+}
+
+impl<'h, 'w, W: Write<Error = E>, E: Error> uWrite for Pager<'h, 'w, W, E> {
+    type Error = E;
+
+    fn write_str(&mut self, s: &str) -> Result<(), E> {
+        self.write_str(s)
+    }
+}
+
+impl<'h, 'w, W: Write<Error = E>, E: Error> core::fmt::Write for Pager<'h, 'w, W, E> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_str(s).map_err(|_| core::fmt::Error)?;
+        Ok(())
+    }
+}
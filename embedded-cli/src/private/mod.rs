@@ -1 +1,44 @@
+use core::marker::PhantomData;
+
+use crate::arguments::ValueEnum;
+
 pub use embedded_io as io;
+pub use ufmt;
+
+#[cfg(any(feature = "metadata", feature = "owned"))]
+pub use std::vec::Vec;
+
+#[cfg(feature = "owned")]
+pub use std::string::String;
+
+/// Possible values of argument type, that are listed in help.
+/// Values are known only for types, that implement `ValueEnum`.
+/// Other types fall back to `NoValues` through auto-ref:
+/// `(&PossibleValues::<T>::new()).values()`
+#[derive(Debug)]
+pub struct PossibleValues<T>(PhantomData<T>);
+
+impl<T> PossibleValues<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait EnumValues {
+    fn values(&self) -> &'static [&'static str];
+}
+
+impl<T: ValueEnum> EnumValues for PossibleValues<T> {
+    fn values(&self) -> &'static [&'static str] {
+        T::VALUES
+    }
+}
+
+pub trait NoValues {
+    fn values(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+impl<T> NoValues for &PossibleValues<T> {}
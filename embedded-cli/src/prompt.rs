@@ -0,0 +1,149 @@
+use crate::{codes, width};
+
+/// Prompt, that is shown before input.
+///
+/// Prompt can contain only printable chars and color codes (`ESC [ ... m`),
+/// so number of columns it occupies is known.
+/// Prompt is validated when created, so creating it in const context
+/// makes invalid prompt a compile error:
+///
+/// ```
+/// use embedded_cli::prompt::Prompt;
+///
+/// const PROMPT: Prompt = Prompt::new("\x1B[32mdev\x1B[0m$ ");
+///
+/// assert_eq!(PROMPT.width(), 5);
+/// ```
+///
+/// ```compile_fail
+/// use embedded_cli::prompt::Prompt;
+///
+/// const PROMPT: Prompt = Prompt::new("dev\r\n$ ");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Prompt {
+    text: &'static str,
+    width: usize,
+}
+
+impl Prompt {
+    /// Create new prompt from given text.
+    ///
+    /// # Panics
+    /// Panics if text contains control chars or escape sequences,
+    /// that are not color codes
+    pub const fn new(text: &'static str) -> Self {
+        let bytes = text.as_bytes();
+        let mut width = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == codes::ESCAPE {
+                i = color_code_end(bytes, i);
+                continue;
+            }
+            let (c, len) = decode_char(bytes, i);
+            assert!(
+                !matches!(c as u32, 0..=0x1F | 0x7F..=0x9F),
+                "prompt must not contain control chars"
+            );
+            width += width::char_width(c);
+            i += len;
+        }
+        Self { text, width }
+    }
+
+    /// Returns text of the prompt (including color codes)
+    pub const fn text(&self) -> &'static str {
+        self.text
+    }
+
+    /// Returns number of terminal columns, that prompt occupies
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl From<&'static str> for Prompt {
+    fn from(text: &'static str) -> Self {
+        Self::new(text)
+    }
+}
+
+/// Returns position right after color code, that starts at given position
+const fn color_code_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    assert!(
+        i < bytes.len() && bytes[i] == b'[',
+        "prompt must not contain escape sequences other than color codes"
+    );
+    i += 1;
+    while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b';') {
+        i += 1;
+    }
+    assert!(
+        i < bytes.len() && bytes[i] == b'm',
+        "prompt must not contain escape sequences other than color codes"
+    );
+    i + 1
+}
+
+/// Decode char of valid utf-8 text at given position.
+/// Returns char and its length in bytes
+const fn decode_char(bytes: &[u8], start: usize) -> (char, usize) {
+    let first = bytes[start];
+    let (len, mut code) = match first {
+        0..=0x7F => (1, first as u32),
+        0xC0..=0xDF => (2, (first & 0x1F) as u32),
+        0xE0..=0xEF => (3, (first & 0x0F) as u32),
+        _ => (4, (first & 0x07) as u32),
+    };
+    let mut i = 1;
+    while i < len {
+        code = (code << 6) | (bytes[start + i] & 0x3F) as u32;
+        i += 1;
+    }
+    match char::from_u32(code) {
+        Some(c) => (c, len),
+        None => (char::REPLACEMENT_CHARACTER, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::prompt::Prompt;
+
+    #[rstest]
+    #[case("$ ", 2)]
+    #[case("", 0)]
+    #[case("\x1B[32m$\x1B[0m ", 2)]
+    #[case("\x1B[1;31mdev\x1B[m> ", 5)]
+    #[case("абв> ", 5)]
+    fn prompt_width(#[case] text: &'static str, #[case] width: usize) {
+        let prompt = Prompt::new(text);
+
+        assert_eq!(prompt.text(), text);
+        assert_eq!(prompt.width(), width);
+    }
+
+    #[rstest]
+    #[case("$\r\n ")]
+    #[case("\t$ ")]
+    #[case("\x07$ ")]
+    #[case("\x1B[2K$ ")]
+    #[case("\x1B[32")]
+    #[case("\x1B7$ ")]
+    #[case("\u{9B}2K$ ")]
+    #[should_panic]
+    fn invalid(#[case] text: &'static str) {
+        Prompt::new(text);
+    }
+
+    #[test]
+    fn const_prompt() {
+        const PROMPT: Prompt = Prompt::new("\x1B[33m#\x1B[0m ");
+
+        assert_eq!(PROMPT.width(), 2);
+    }
+}
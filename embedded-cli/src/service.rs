@@ -1,6 +1,13 @@
 use embedded_io::Write;
 
-use crate::{arguments::FromArgumentError, cli::CliHandle, command::RawCommand};
+use core::fmt::Debug;
+
+use crate::{
+    arguments::FromArgumentError,
+    cli::{CliEvent, CliHandle},
+    command::RawCommand,
+    input::Input,
+};
 
 #[cfg(feature = "autocomplete")]
 use crate::autocomplete::{Autocompletion, Request};
@@ -14,19 +21,57 @@ pub enum ProcessError<'a, E: embedded_io::Error> {
     WriteError(E),
 }
 
+/// Error, that can be returned by command handler
+/// created with generated `fallible_processor`
 #[derive(Debug)]
+pub enum CommandError<E: embedded_io::Error, U> {
+    /// Output could not be written
+    WriteError(E),
+
+    /// Application error, that is reported to user as `error: ...` line
+    User(U),
+}
+
+impl<E: embedded_io::Error, U> From<E> for CommandError<E, U> {
+    fn from(value: E) -> Self {
+        Self::WriteError(value)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum ParseError<'a> {
+    /// Argument takes rest of the line as it was typed (`#[arg(trailing)]`),
+    /// but original line is not known (see `RawCommand::line`)
+    LineUnavailable {
+        /// Name of the argument. For example `<TEXT>`
+        name: &'a str,
+    },
+
     MissingRequiredArgument {
         /// Name of the argument. For example `<FILE>`, `-f <FILE>`, `--file <FILE>`
         name: &'a str,
     },
 
+    /// Required subcommand is not given
+    MissingSubcommand {
+        /// Names of valid subcommands (empty if unknown)
+        expected: &'static [&'static str],
+    },
+
     ParseValueError {
+        /// Name of the option or argument, if known.
+        /// For example `--level` or `<FILE>`
+        name: Option<&'a str>,
         value: &'a str,
         expected: &'static str,
     },
 
+    /// Input line has more tokens than allowed (see `CliBuilder::max_tokens`)
+    TooManyTokens {
+        max: usize,
+    },
+
     UnexpectedArgument {
         value: &'a str,
     },
@@ -45,6 +90,7 @@ pub enum ParseError<'a> {
 impl<'a> From<FromArgumentError<'a>> for ParseError<'a> {
     fn from(error: FromArgumentError<'a>) -> Self {
         Self::ParseValueError {
+            name: None,
             value: error.value,
             expected: error.expected,
         }
@@ -92,7 +138,7 @@ pub trait Help {
 
     #[cfg(feature = "help")]
     /// Print all commands and short description of each
-    fn list_commands<W: Write<Error = E>, E: embedded_io::Error>(
+    fn list_commands<W: Write<Error = E> + ?Sized, E: embedded_io::Error>(
         writer: &mut Writer<'_, W, E>,
     ) -> Result<(), E>;
 
@@ -102,7 +148,7 @@ pub trait Help {
     /// If help request cannot be processed by this object,
     /// Err(HelpError::UnknownCommand) must be returned
     fn command_help<
-        W: Write<Error = E>,
+        W: Write<Error = E> + ?Sized,
         E: embedded_io::Error,
         F: FnMut(&mut Writer<'_, W, E>) -> Result<(), E>,
     >(
@@ -113,6 +159,10 @@ pub trait Help {
 }
 
 pub trait FromRaw<'a>: Sized {
+    /// Names of all commands, that can be parsed.
+    /// Used to list valid subcommands, when required subcommand is missing
+    const NAMES: &'static [&'static str] = &[];
+
     /// Parse raw command into typed command
     fn parse(raw: RawCommand<'a>) -> Result<Self, ParseError<'a>>;
 }
@@ -123,6 +173,65 @@ pub trait CommandProcessor<W: Write<Error = E>, E: embedded_io::Error> {
         cli: &mut CliHandle<'_, W, E>,
         raw: RawCommand<'a>,
     ) -> Result<(), ProcessError<'a, E>>;
+
+    /// Process event, that is not a command (for example, function key press).
+    /// Events are ignored by default
+    #[allow(unused_variables)]
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Called with every decoded input (char or control key) before it is processed.
+    /// Can be used to record input for later replay or to debug terminal behavior.
+    /// Inputs are ignored by default
+    #[allow(unused_variables)]
+    fn on_input(&mut self, input: Input<'_>) {}
+
+    /// Wrap this processor, so events are processed by given closure
+    fn with_events<F>(self, f: F) -> WithEvents<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut CliHandle<'_, W, E>, CliEvent<'_>) -> Result<(), E>,
+    {
+        WithEvents { processor: self, f }
+    }
+}
+
+/// Processor, that processes events with a closure
+/// and forwards commands to the wrapped processor
+pub struct WithEvents<P, F> {
+    processor: P,
+    f: F,
+}
+
+impl<P, F> Debug for WithEvents<P, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WithEvents").finish()
+    }
+}
+
+impl<W, E, P, F> CommandProcessor<W, E> for WithEvents<P, F>
+where
+    W: Write<Error = E>,
+    E: embedded_io::Error,
+    P: CommandProcessor<W, E>,
+    F: FnMut(&mut CliHandle<'_, W, E>, CliEvent<'_>) -> Result<(), E>,
+{
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        self.processor.process(cli, raw)
+    }
+
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        (self.f)(cli, event)
+    }
+
+    fn on_input(&mut self, input: Input<'_>) {
+        self.processor.on_input(input)
+    }
 }
 
 impl<W, E, F> CommandProcessor<W, E> for F
@@ -139,3 +248,105 @@ where
         self(cli, command)
     }
 }
+
+/// Processor, that receives external state explicitly with each call
+/// instead of capturing it.
+/// Use it with `Cli::process_byte_with_state`
+pub trait StatefulProcessor<S, W: Write<Error = E>, E: embedded_io::Error> {
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        state: &mut S,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>>;
+
+    /// Process event, that is not a command (for example, function key press).
+    /// Events are ignored by default
+    #[allow(unused_variables)]
+    fn on_event(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        state: &mut S,
+        event: CliEvent<'_>,
+    ) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+impl<S, W, E, F> StatefulProcessor<S, W, E> for F
+where
+    W: Write<Error = E>,
+    E: embedded_io::Error,
+    F: for<'a> FnMut(
+        &mut CliHandle<'_, W, E>,
+        &mut S,
+        RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>>,
+{
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        state: &mut S,
+        command: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        self(cli, state, command)
+    }
+}
+
+/// Adapter, that remembers whether wrapped processor
+/// was given any command or event
+pub(crate) struct Dispatched<'p, P> {
+    pub(crate) processor: &'p mut P,
+    pub(crate) dispatched: bool,
+}
+
+impl<'p, W, E, P> CommandProcessor<W, E> for Dispatched<'p, P>
+where
+    W: Write<Error = E>,
+    E: embedded_io::Error,
+    P: CommandProcessor<W, E>,
+{
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        self.dispatched = true;
+        self.processor.process(cli, raw)
+    }
+
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        self.dispatched = true;
+        self.processor.on_event(cli, event)
+    }
+
+    fn on_input(&mut self, input: Input<'_>) {
+        self.processor.on_input(input)
+    }
+}
+
+/// Adapter, that turns stateful processor into a normal one
+/// by borrowing state for the duration of processing
+pub(crate) struct WithState<'p, P, S> {
+    pub(crate) processor: &'p mut P,
+    pub(crate) state: &'p mut S,
+}
+
+impl<'p, W, E, P, S> CommandProcessor<W, E> for WithState<'p, P, S>
+where
+    W: Write<Error = E>,
+    E: embedded_io::Error,
+    P: StatefulProcessor<S, W, E>,
+{
+    fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        self.processor.process(cli, self.state, raw)
+    }
+
+    fn on_event(&mut self, cli: &mut CliHandle<'_, W, E>, event: CliEvent<'_>) -> Result<(), E> {
+        self.processor.on_event(cli, self.state, event)
+    }
+}
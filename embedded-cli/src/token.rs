@@ -1,5 +1,8 @@
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Tokens<'a> {
+    /// Tokens are borrowed from original input (see `Tokens::from_line`)
+    /// instead of being delimited with 0
+    borrowed: bool,
     empty: bool,
     tokens: &'a str,
 }
@@ -25,20 +28,22 @@ impl<'a> Tokens<'a> {
             let byte = bytes[cursor_pos];
             match mode {
                 Mode::Space => {
+                    // previous token might be empty (`""`),
+                    // so separator is inserted after any previous token
                     if byte == b'"' {
                         mode = Mode::Quoted;
-                        empty = false;
-                        if insert > 0 {
+                        if !empty {
                             bytes[insert] = 0;
                             insert += 1;
                         }
+                        empty = false;
                     } else if byte != b' ' && byte != 0 {
                         mode = Mode::Normal;
-                        empty = false;
-                        if insert > 0 {
+                        if !empty {
                             bytes[insert] = 0;
                             insert += 1;
                         }
+                        empty = false;
                         bytes[insert] = byte;
                         insert += 1;
                     }
@@ -72,23 +77,73 @@ impl<'a> Tokens<'a> {
         // SAFETY: bytes are still a valid utf8 sequence
         // insert is inside bytes slice
         let tokens = unsafe { core::str::from_utf8_unchecked(bytes.get_unchecked(..insert)) };
-        Self { empty, tokens }
+        Self {
+            borrowed: false,
+            empty,
+            tokens,
+        }
+    }
+
+    /// Split given input into tokens the same way as `Tokens::new`,
+    /// but without modifying it, so input stays available as it was typed.
+    ///
+    /// Returns `None` if some quoted token contains escaped chars,
+    /// since such token can't be borrowed from input as is
+    pub fn from_line(input: &'a str) -> Option<Self> {
+        let mut scanner = Scanner::new();
+        for byte in input.bytes() {
+            scanner.advance(byte);
+            if scanner.escaped {
+                return None;
+            }
+        }
+        let empty = input.bytes().all(|b| b == b' ' || b == 0);
+        Some(Self {
+            borrowed: true,
+            empty,
+            tokens: input,
+        })
+    }
+
+    /// Returns true if given input ends inside quoted token
+    /// (so next char becomes a part of that token)
+    pub fn is_quoted(input: &str) -> bool {
+        let mut scanner = Scanner::new();
+        input.bytes().for_each(|byte| {
+            scanner.advance(byte);
+        });
+        scanner.quoted
+    }
+
+    /// Returns byte position of first unquoted `;` in given input,
+    /// that separates one command from the next
+    pub fn command_end(input: &str) -> Option<usize> {
+        let mut scanner = Scanner::new();
+        input
+            .bytes()
+            .position(|byte| scanner.advance(byte) && byte == b';')
     }
 
     pub fn from_raw(tokens: &'a str, is_empty: bool) -> Self {
         Self {
+            borrowed: false,
             empty: is_empty,
             tokens,
         }
     }
 
-    /// Returns raw representation of tokens (delimited with 0)
+    /// Returns raw representation of tokens (delimited with 0,
+    /// or original input if tokens are borrowed from it)
     pub fn into_raw(self) -> &'a str {
         self.tokens
     }
 
     pub fn iter(&self) -> TokensIter<'a> {
-        TokensIter::new(self.tokens, self.empty)
+        TokensIter {
+            borrowed: self.borrowed,
+            empty: self.empty,
+            tokens: self.tokens,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -96,23 +151,96 @@ impl<'a> Tokens<'a> {
     }
 }
 
+/// Tracks whether bytes of input are quoted the same way as `Tokens::new`
+#[derive(Clone, Copy, Debug)]
+struct Scanner {
+    /// Last byte was a backslash inside quoted token
+    escaped: bool,
+    quoted: bool,
+    token_start: bool,
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            escaped: false,
+            quoted: false,
+            token_start: true,
+        }
+    }
+
+    /// Process next byte of input and return true if it is outside of quoted token
+    fn advance(&mut self, byte: u8) -> bool {
+        if self.quoted {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' || byte == 0 {
+                self.quoted = false;
+                self.token_start = true;
+            }
+            false
+        } else if byte == b' ' || byte == 0 {
+            self.token_start = true;
+            true
+        } else {
+            // quote opens token only at its start
+            self.quoted = self.token_start && byte == b'"';
+            self.token_start = false;
+            !self.quoted
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TokensIter<'a> {
+    borrowed: bool,
     tokens: &'a str,
     empty: bool,
 }
 
 impl<'a> TokensIter<'a> {
     pub fn new(tokens: &'a str, empty: bool) -> Self {
-        Self { tokens, empty }
+        Self {
+            borrowed: false,
+            tokens,
+            empty,
+        }
     }
 
     pub fn into_tokens(self) -> Tokens<'a> {
+        let empty =
+            self.empty || (self.borrowed && self.tokens.bytes().all(|b| b == b' ' || b == 0));
         Tokens {
-            empty: self.empty,
+            borrowed: self.borrowed,
+            empty,
             tokens: self.tokens,
         }
     }
+
+    /// Returns next token, that is borrowed from original input
+    fn next_borrowed(&mut self) -> Option<&'a str> {
+        let bytes = self.tokens.as_bytes();
+        let Some(start) = bytes.iter().position(|&b| b != b' ' && b != 0) else {
+            self.empty = true;
+            return None;
+        };
+        let quoted = bytes.get(start) == Some(&b'"');
+        let content = if quoted { start + 1 } else { start };
+        let end = bytes
+            .get(content..)
+            .and_then(|rest| {
+                rest.iter()
+                    .position(|&b| b == 0 || if quoted { b == b'"' } else { b == b' ' })
+            })
+            .map_or(bytes.len(), |len| content + len);
+        let token = self.tokens.get(content..end);
+        // closing quote is skipped together with the token
+        let next = if quoted { end + 1 } else { end };
+        self.tokens = self.tokens.get(next..).unwrap_or_default();
+        token
+    }
 }
 
 impl<'a> Iterator for TokensIter<'a> {
@@ -122,6 +250,9 @@ impl<'a> Iterator for TokensIter<'a> {
         if self.empty {
             return None;
         }
+        if self.borrowed {
+            return self.next_borrowed();
+        }
         if let Some(pos) = self.tokens.as_bytes().iter().position(|&b| b == 0) {
             // SAFETY: pos is inside args slice
             let (arg, other) = unsafe {
@@ -139,11 +270,61 @@ impl<'a> Iterator for TokensIter<'a> {
     }
 }
 
+/// Iterator over byte offsets of tokens inside original (not tokenized) input.
+///
+/// Tokens are detected the same way as in `Tokens::new`,
+/// offset of quoted token points to the opening quote
+#[derive(Clone, Debug)]
+pub struct TokenOffsets<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TokenOffsets<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for TokenOffsets<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.pos
+            + self.input[self.pos..]
+                .iter()
+                .position(|&b| b != b' ' && b != 0)?;
+
+        let quoted = self.input[start] == b'"';
+        let mut pos = start + 1;
+        while pos < self.input.len() {
+            let byte = self.input[pos];
+            if quoted {
+                if byte == b'"' || byte == 0 {
+                    pos += 1;
+                    break;
+                } else if byte == b'\\' {
+                    pos += 1;
+                }
+            } else if byte == b' ' || byte == 0 {
+                break;
+            }
+            pos += 1;
+        }
+        self.pos = pos.min(self.input.len());
+
+        Some(start)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
 
-    use crate::token::Tokens;
+    use crate::token::{TokenOffsets, Tokens};
 
     #[rstest]
     #[case("", "")]
@@ -160,13 +341,90 @@ mod tests {
     #[case(r#"  " abc"   "de fg " "  he  yw""#, " abc\0de fg \0  he  yw")]
     #[case(r#"  "ab \"c\\d\" " "#, r#"ab "c\d" "#)]
     #[case(r#""abc\\""#, r#"abc\"#)]
+    #[case(r#""""#, "")]
+    #[case(r#""" abc"#, "\0abc")]
+    #[case(r#"abc "" def"#, "abc\0\0def")]
+    #[case(r#"abc """#, "abc\0")]
     fn create(#[case] input: &str, #[case] expected: &str) {
+        let blank = input.trim().is_empty();
         let mut input = input.as_bytes().to_vec();
         let input = core::str::from_utf8_mut(&mut input).unwrap();
         let result = Tokens::new(input);
 
         assert_eq!(result.tokens, expected);
+        assert_eq!(result.is_empty(), blank);
         let len = result.tokens.len();
         assert_eq!(&mut input[..len], expected);
     }
+
+    #[rstest]
+    #[case("")]
+    #[case("   ")]
+    #[case("  abc  def gh ")]
+    #[case(r#"  " abc"   "de fg " "  he  yw""#)]
+    #[case(r#"  "  abc  "#)]
+    #[case(r#"ab"c d" e"#)]
+    #[case(r#""ab"cd "" ef"#)]
+    #[case(r#"abc """#)]
+    #[case(r#"a\b "c""#)]
+    fn from_line(#[case] input: &str) {
+        let mut copy = input.as_bytes().to_vec();
+        let expected = Tokens::new(core::str::from_utf8_mut(&mut copy).unwrap());
+
+        let tokens = Tokens::from_line(input).unwrap();
+
+        assert_eq!(tokens.is_empty(), expected.is_empty());
+        assert!(tokens.iter().eq(expected.iter()));
+    }
+
+    #[rstest]
+    #[case(r#""a\"b""#)]
+    #[case(r#"set "a b\\""#)]
+    fn from_line_escaped(#[case] input: &str) {
+        assert_eq!(Tokens::from_line(input), None);
+    }
+
+    #[rstest]
+    #[case("", false)]
+    #[case("set", false)]
+    #[case(r#"set ""#, true)]
+    #[case(r#"set "a b"#, true)]
+    #[case(r#"set "a b""#, false)]
+    #[case(r#"set "a \""#, true)]
+    #[case(r#"set "a \\""#, false)]
+    #[case(r#"set a"b"#, false)]
+    #[case(r#"set "a" "b"#, true)]
+    fn is_quoted(#[case] input: &str, #[case] expected: bool) {
+        assert_eq!(Tokens::is_quoted(input), expected);
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("set", None)]
+    #[case("set; get", Some(3))]
+    #[case("set a;b", Some(5))]
+    #[case(r#"set "a;b"; get"#, Some(9))]
+    #[case(r#"set "a \";b"#, None)]
+    #[case(r#"set a"b;c"#, Some(7))]
+    fn command_end(#[case] input: &str, #[case] expected: Option<usize>) {
+        assert_eq!(Tokens::command_end(input), expected);
+    }
+
+    #[rstest]
+    #[case("", &[])]
+    #[case("   ", &[])]
+    #[case("abc", &[0])]
+    #[case("  abc  def gh ", &[2, 7, 11])]
+    #[case(r#"  " abc"   "de fg " "  he  yw""#, &[2, 11, 20])]
+    #[case(r#""ab \"c" d"#, &[0, 9])]
+    #[case(r#"ab"c d" e"#, &[0, 5, 8])]
+    fn offsets(#[case] input: &str, #[case] expected: &[usize]) {
+        let mut tokenized = input.as_bytes().to_vec();
+        let tokenized = core::str::from_utf8_mut(&mut tokenized).unwrap();
+        let tokens = Tokens::new(tokenized);
+
+        let offsets = TokenOffsets::new(input).collect::<std::vec::Vec<_>>();
+        assert_eq!(offsets, expected);
+        assert_eq!(offsets.len(), tokens.iter().count());
+    }
 }
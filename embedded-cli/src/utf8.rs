@@ -1,3 +1,18 @@
+/// Char that is emitted in place of invalid utf-8 sequences
+/// when replacement is enabled
+const REPLACEMENT: &str = "\u{FFFD}";
+
+/// All ascii chars, so single byte char is returned without copying
+const ASCII: [u8; 128] = {
+    let mut chars = [0; 128];
+    let mut i = 0;
+    while i < chars.len() {
+        chars[i] = i as u8;
+        i += 1;
+    }
+    chars
+};
+
 #[derive(Debug, Default)]
 pub struct Utf8Accum {
     /// Buffer for utf8 octets aggregation until full utf-8 char is received
@@ -8,59 +23,117 @@ pub struct Utf8Accum {
 
     /// How many utf8 octets are in the buffer
     partial: u8,
+
+    /// Whether invalid sequences are replaced with U+FFFD or silently dropped
+    replace_invalid: bool,
 }
 
 impl Utf8Accum {
+    /// Emit U+FFFD replacement char for each invalid utf-8 sequence
+    /// instead of silently dropping it
+    pub fn with_replacement(self, replace_invalid: bool) -> Self {
+        Self {
+            replace_invalid,
+            ..self
+        }
+    }
+
+    /// Whether first octets of multi-byte char were
+    /// received and more octets are expected
+    pub fn is_partial(&self) -> bool {
+        self.expected > 0
+    }
+
+    /// Drops partially received char
+    pub fn reset(&mut self) {
+        self.expected = 0;
+        self.partial = 0;
+    }
+
+    /// Accepts next byte and returns char, if it was completed.
+    ///
+    /// Invalid octets never get into accumulated char, so receiving
+    /// is resynchronized at the next valid first octet.
+    /// Partially received char, that is interrupted by a new char, is dropped
+    /// (or replaced with U+FFFD, so returned ascii char is preceded by it)
     pub fn push_byte(&mut self, byte: u8) -> Option<&str> {
+        // most of input is ascii, so it skips accumulation
+        if byte.is_ascii() {
+            if self.is_partial() && self.replace_invalid {
+                self.reset();
+                let len = REPLACEMENT.len();
+                self.buffer[..len].copy_from_slice(REPLACEMENT.as_bytes());
+                self.buffer[len] = byte;
+                // SAFETY: replacement char followed by ascii char is valid utf-8
+                return Some(unsafe { core::str::from_utf8_unchecked(&self.buffer[..=len]) });
+            }
+            self.reset();
+            let index = byte as usize;
+            // SAFETY: ascii chars are all valid utf-8 chars
+            return ASCII
+                .get(index..=index)
+                .map(|c| unsafe { core::str::from_utf8_unchecked(c) });
+        }
+
         // Plain and stupid utf-8 validation
         // Bytes are supposed to be human input so it's okay to be not blazing fast
 
-        if byte >= 0xF8 {
-            return None;
-        } else if byte >= 0xF0 {
-            // this is first octet of 4-byte value
-            self.buffer[0] = byte;
-            self.partial = 1;
-            self.expected = 3;
-        } else if byte >= 0xE0 {
-            // this is first octet of 3-byte value
-            self.buffer[0] = byte;
-            self.partial = 1;
-            self.expected = 2;
-        } else if byte >= 0xC0 {
-            // this is first octet of 2-byte value
-            self.buffer[0] = byte;
-            self.partial = 1;
-            self.expected = 1;
-        } else if byte >= 0x80 {
-            if self.expected > 0 {
+        match byte {
+            0x80..=0xBF if self.expected > 0 => {
                 // this is one of other octets of multi-byte value
                 self.buffer[self.partial as usize] = byte;
                 self.partial += 1;
                 self.expected -= 1;
                 if self.expected == 0 {
                     let len = self.partial as usize;
-                    // SAFETY: we checked previously that buffer contains valid utf8
-                    unsafe {
-                        return Some(core::str::from_utf8_unchecked(&self.buffer[..len]));
+                    self.partial = 0;
+                    // overlong encodings and surrogates are still possible here
+                    if core::str::from_utf8(&self.buffer[..len]).is_ok() {
+                        // SAFETY: we just checked that buffer contains valid utf8
+                        unsafe {
+                            return Some(core::str::from_utf8_unchecked(&self.buffer[..len]));
+                        }
                     }
+                    return self.invalid();
                 }
+                None
             }
-        } else {
-            self.expected = 0;
-            self.buffer[0] = byte;
-            // SAFETY: ascii chars are all valid utf-8 chars
-            unsafe {
-                return Some(core::str::from_utf8_unchecked(&self.buffer[..1]));
+            0xC2..=0xDF => self.start(byte, 1),
+            0xE0..=0xEF => self.start(byte, 2),
+            0xF0..=0xF4 => self.start(byte, 3),
+            // unexpected continuation octet or byte, that never appears in utf-8
+            _ => {
+                self.reset();
+                self.invalid()
             }
         }
+    }
 
-        None
+    fn invalid(&self) -> Option<&str> {
+        if self.replace_invalid {
+            Some(REPLACEMENT)
+        } else {
+            None
+        }
+    }
+
+    fn start(&mut self, byte: u8, expected: u8) -> Option<&str> {
+        let interrupted = self.is_partial();
+        // this is first octet of multi-byte value
+        self.buffer[0] = byte;
+        self.partial = 1;
+        self.expected = expected;
+        if interrupted {
+            self.invalid()
+        } else {
+            None
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
     use std::string::String;
 
     use crate::utf8::Utf8Accum;
@@ -81,4 +154,58 @@ mod tests {
 
         assert_eq!(text, expected_str);
     }
+
+    #[test]
+    fn ascii() {
+        let mut accum = Utf8Accum::default();
+
+        for b in 0..0x80u8 {
+            let expected = [b];
+            assert_eq!(
+                accum.push_byte(b),
+                Some(core::str::from_utf8(&expected).unwrap())
+            );
+        }
+
+        // partially received char is dropped
+        assert_eq!(accum.push_byte(0xD0), None);
+        assert_eq!(accum.push_byte(b'a'), Some("a"));
+        assert!(!accum.is_partial());
+        assert_eq!(accum.push_byte(0xB1), None);
+
+        // or replaced together with interrupting char
+        let mut accum = Utf8Accum::default().with_replacement(true);
+        assert_eq!(accum.push_byte(0xD0), None);
+        assert_eq!(accum.push_byte(b'a'), Some("\u{FFFD}a"));
+        assert!(!accum.is_partial());
+    }
+
+    #[rstest]
+    #[case(b"a\xFFb", "ab", "a\u{FFFD}b")]
+    #[case(b"a\x80b", "ab", "a\u{FFFD}b")]
+    #[case(b"\xD0b", "b", "\u{FFFD}b")]
+    #[case(b"\xE4\xBD\xD0\xB1", "\u{431}", "\u{FFFD}\u{431}")]
+    #[case(b"\xD0\xD0\xB1a", "\u{431}a", "\u{FFFD}\u{431}a")]
+    #[case(b"\xC0\xAFa", "a", "\u{FFFD}\u{FFFD}a")]
+    #[case(b"\xE0\x80\xAFa", "a", "\u{FFFD}a")]
+    #[case(b"\xED\xA0\x80a", "a", "\u{FFFD}a")]
+    #[case(b"\xF8\xD0\xB1", "\u{431}", "\u{FFFD}\u{431}")]
+    fn invalid_input(
+        #[case] input: &[u8],
+        #[case] expected: &str,
+        #[case] expected_replaced: &str,
+    ) {
+        for (replace, expected) in [(false, expected), (true, expected_replaced)] {
+            let mut accum = Utf8Accum::default().with_replacement(replace);
+            let mut text = String::new();
+
+            for &b in input {
+                if let Some(t) = accum.push_byte(b) {
+                    text.push_str(t);
+                }
+            }
+
+            assert_eq!(text, expected);
+        }
+    }
 }
@@ -5,33 +5,27 @@ use crate::utf8::Utf8Accum;
 /// For example, in text `abc` `b` has both char and byte index of `1`.
 /// But in text `вгд` `г` has char index of 1, but byte index of `2` (`в` is 2 bytes long)
 pub fn char_byte_index(text: &str, char_index: usize) -> Option<usize> {
-    let mut accum = Utf8Accum::default();
-    let mut byte_index = 0;
-    let mut current = 0;
-    for &b in text.as_bytes() {
-        if char_index == current {
-            return Some(byte_index);
-        }
-        if accum.push_byte(b).is_some() {
-            current += 1;
-        }
-        byte_index += 1;
+    // ascii is the most common input, so skip scanning for it
+    if text.is_ascii() {
+        return (char_index < text.len()).then_some(char_index);
     }
-    if char_index == current && byte_index < text.len() {
-        return Some(byte_index);
-    }
-    None
+    // text is valid utf-8, so every char starts at non-continuation byte
+    text.as_bytes()
+        .iter()
+        .enumerate()
+        .filter(|(_, &b)| !is_continuation(b))
+        .nth(char_index)
+        .map(|(pos, _)| pos)
 }
 
 pub fn char_count(text: &str) -> usize {
-    let mut accum = Utf8Accum::default();
-    let mut count = 0;
-    for &b in text.as_bytes() {
-        if accum.push_byte(b).is_some() {
-            count += 1;
-        }
+    if text.is_ascii() {
+        return text.len();
     }
-    count
+    text.as_bytes()
+        .iter()
+        .filter(|&&b| !is_continuation(b))
+        .count()
 }
 
 pub fn char_pop_front(text: &str) -> Option<(char, &str)> {
@@ -70,6 +64,14 @@ pub fn char_pop_front(text: &str) -> Option<(char, &str)> {
 
 /// Returns length (in bytes) of longest common prefix
 pub fn common_prefix_len(left: &str, right: &str) -> usize {
+    if left.is_ascii() && right.is_ascii() {
+        return left
+            .bytes()
+            .zip(right.bytes())
+            .take_while(|(b1, b2)| b1 == b2)
+            .count();
+    }
+
     let mut accum1 = Utf8Accum::default();
 
     let mut pos = 0;
@@ -89,6 +91,10 @@ pub fn common_prefix_len(left: &str, right: &str) -> usize {
     pos
 }
 
+fn is_continuation(byte: u8) -> bool {
+    (byte & 0xC0) == 0x80
+}
+
 /// Encodes given character as UTF-8 into the provided byte buffer,
 /// and then returns the subslice of the buffer that contains the encoded character.
 pub fn encode_utf8(ch: char, buf: &mut [u8]) -> &str {
@@ -123,9 +129,23 @@ pub fn encode_utf8(ch: char, buf: &mut [u8]) -> &str {
 
     buf[0] = code as u8 | first_b_mask;
 
-    unsafe {
-        return core::str::from_utf8_unchecked(&buf[..len]);
+    unsafe { core::str::from_utf8_unchecked(&buf[..len]) }
+}
+
+/// Encodes given number as decimal digits into the provided byte buffer,
+/// and then returns the subslice of the buffer that contains the digits.
+pub fn encode_u16(mut value: u16, buf: &mut [u8; 5]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
     }
+    // SAFETY: buffer contains only ascii digits
+    unsafe { core::str::from_utf8_unchecked(&buf[start..]) }
 }
 
 pub fn trim_start(input: &str) -> &str {
@@ -179,6 +199,17 @@ mod tests {
 
     use crate::utils;
 
+    #[rstest]
+    #[case(0, "0")]
+    #[case(7, "7")]
+    #[case(24, "24")]
+    #[case(65535, "65535")]
+    fn encode_u16(#[case] value: u16, #[case] expected: &str) {
+        let mut buf = [0; 5];
+
+        assert_eq!(utils::encode_u16(value, &mut buf), expected);
+    }
+
     #[rstest]
     #[case::no_spaces("abc", "abc")]
     #[case::leading_spaces("  abc", "abc")]
@@ -194,6 +225,9 @@ mod tests {
     #[rstest]
     #[case("abcdef")]
     #[case("abcd абв 佐佗佟𑿁 𑿆𑿌")]
+    #[case("")]
+    #[case("ab\u{7F}")]
+    #[case("й")]
     fn char_byte_pos(#[case] text: &str) {
         // last iteration will check for None
         for pos in 0..=text.chars().count() {
@@ -206,6 +240,9 @@ mod tests {
     #[rstest]
     #[case("abcdef")]
     #[case("abcd абв 佐佗佟𑿁 𑿆𑿌")]
+    #[case("")]
+    #[case("ab\u{7F}")]
+    #[case("й")]
     fn char_count(#[case] text: &str) {
         assert_eq!(utils::char_count(text), text.chars().count())
     }
@@ -238,6 +275,8 @@ mod tests {
     #[case("abcdef", "abc ghf")]
     #[case("abcdef", "")]
     #[case("", "")]
+    #[case("abcdef", "абв")]
+    #[case("абв", "абг")]
     #[case("абв 佐佗佟𑿁", "абв 佐佗佟𑿁")]
     #[case("абв 佐佗佟𑿁𑿆𑿌", "абв 佐佗佟𑿁")]
     #[case("абв 佐佗佟𑿁 𑿆𑿌", "абв 佐佗𑿁佟")]
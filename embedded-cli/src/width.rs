@@ -0,0 +1,239 @@
+/// Ranges of East Asian Wide and Fullwidth chars, that occupy two columns in terminal.
+/// Ranges are sorted and do not overlap.
+#[cfg(feature = "wide-chars")]
+const WIDE_RANGES: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x16FE0, 0x16FE4),
+    (0x17000, 0x18CFF),
+    (0x1B000, 0x1B2FF),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F90C, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+/// Ranges of chars, that extend previous char into single grapheme cluster
+/// (combining marks, joiners, variation selectors and emoji modifiers).
+/// Ranges are sorted and do not overlap.
+#[cfg(feature = "graphemes")]
+const EXTENDING_RANGES: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DC),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x0900, 0x0903),
+    (0x093A, 0x093C),
+    (0x093E, 0x094F),
+    (0x0951, 0x0957),
+    (0x0962, 0x0963),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200C, 0x200D),
+    (0x20D0, 0x20FF),
+    (0x3099, 0x309A),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0020, 0xE007F),
+    (0xE0100, 0xE01EF),
+];
+
+/// Zero width joiner, char after it is joined into same grapheme cluster
+#[cfg(feature = "graphemes")]
+const ZWJ: char = '\u{200D}';
+
+#[cfg(any(feature = "wide-chars", feature = "graphemes"))]
+const fn in_ranges(ranges: &[(u32, u32)], c: char) -> bool {
+    let code = c as u32;
+    // fast path for most common chars
+    if code < ranges[0].0 {
+        return false;
+    }
+    // binary search is written by hand, so it can be used in const context
+    let mut low = 0;
+    let mut high = ranges.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let (start, end) = ranges[mid];
+        if end < code {
+            low = mid + 1;
+        } else if start > code {
+            high = mid;
+        } else {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns how many terminal columns given char occupies
+///
+/// Without `wide-chars` feature all chars are treated as occupying single column.
+/// With `graphemes` feature chars that extend previous grapheme have zero width.
+pub const fn char_width(c: char) -> usize {
+    #[cfg(feature = "graphemes")]
+    if is_extending(c) {
+        return 0;
+    }
+    #[cfg(feature = "wide-chars")]
+    if in_ranges(WIDE_RANGES, c) {
+        return 2;
+    }
+    let _ = c;
+    1
+}
+
+/// Returns true if given char extends previous char into single grapheme cluster.
+///
+/// Without `graphemes` feature every char is treated as separate grapheme.
+pub const fn is_extending(c: char) -> bool {
+    #[cfg(feature = "graphemes")]
+    {
+        in_ranges(EXTENDING_RANGES, c)
+    }
+    #[cfg(not(feature = "graphemes"))]
+    {
+        let _ = c;
+        false
+    }
+}
+
+/// Returns true if `c` extends `prev` into single grapheme cluster.
+pub fn is_joined(prev: char, c: char) -> bool {
+    #[cfg(feature = "graphemes")]
+    if prev == ZWJ {
+        return true;
+    }
+    let _ = prev;
+    is_extending(c)
+}
+
+#[cfg(all(test, feature = "graphemes"))]
+mod grapheme_tests {
+    use rstest::rstest;
+
+    use super::{char_width, is_extending, is_joined, EXTENDING_RANGES};
+
+    #[rstest]
+    #[case('a', false)]
+    #[case('\u{0301}', true)]
+    #[case('\u{200D}', true)]
+    #[case('\u{FE0F}', true)]
+    #[case('\u{1F3FB}', true)]
+    #[case('й', false)]
+    fn extending(#[case] c: char, #[case] expected: bool) {
+        assert_eq!(is_extending(c), expected);
+        assert_eq!(char_width(c) == 0, expected);
+    }
+
+    #[test]
+    fn joined() {
+        assert!(is_joined('a', '\u{0301}'));
+        assert!(is_joined('\u{200D}', '😀'));
+        assert!(!is_joined('a', '😀'));
+    }
+
+    #[test]
+    fn ranges_sorted() {
+        for window in EXTENDING_RANGES.windows(2) {
+            assert!(window[0].0 <= window[0].1);
+            assert!(window[0].1 < window[1].0);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wide-chars"))]
+mod tests {
+    use rstest::rstest;
+
+    use super::{char_width, WIDE_RANGES};
+
+    #[rstest]
+    #[case('a', 1)]
+    #[case('б', 1)]
+    #[case('佐', 2)]
+    #[case('ア', 2)]
+    #[case('한', 2)]
+    #[case('Ａ', 2)]
+    #[case('ｱ', 1)]
+    #[case('😀', 2)]
+    #[case('𑿌', 1)]
+    fn width(#[case] c: char, #[case] expected: usize) {
+        assert_eq!(char_width(c), expected);
+    }
+
+    #[test]
+    fn ranges_sorted() {
+        for window in WIDE_RANGES.windows(2) {
+            assert!(window[0].0 <= window[0].1);
+            assert!(window[0].1 < window[1].0);
+        }
+    }
+}
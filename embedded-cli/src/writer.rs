@@ -1,34 +1,183 @@
 use core::{convert::Infallible, fmt::Debug};
 
 use embedded_io::{Error, ErrorType, Write};
-use ufmt::uWrite;
+use ufmt::{uDisplay, uWrite, Formatter};
 
-use crate::codes;
+use crate::{codes, utils};
 
-pub struct Writer<'a, W: Write<Error = E>, E: Error> {
+/// Bytes, that passed through cli. Reported to transcript hook
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transcript<'a> {
+    /// Byte received from remote side
+    Received(u8),
+
+    /// Bytes written by cli (including output of commands)
+    Sent(&'a [u8]),
+}
+
+/// Writer, that reports all written bytes to transcript hook
+pub(crate) struct Tap<W> {
+    pub(crate) inner: W,
+    /// Output is discarded (for example, while no host is connected)
+    pub(crate) muted: bool,
+    /// Output is discarded, because remote side sent XOFF
+    pub(crate) paused: bool,
+    pub(crate) transcript: Option<fn(Transcript<'_>)>,
+}
+
+impl<W> Tap<W> {
+    pub(crate) fn new(inner: W, transcript: Option<fn(Transcript<'_>)>) -> Self {
+        Self {
+            inner,
+            muted: false,
+            paused: false,
+            transcript,
+        }
+    }
+
+    /// Report received byte to transcript hook
+    pub(crate) fn received(&self, byte: u8) {
+        if let Some(transcript) = self.transcript {
+            transcript(Transcript::Received(byte));
+        }
+    }
+}
+
+impl<W: ErrorType> ErrorType for Tap<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Tap<W> {
+    /// Write and flush flow control byte, which is sent even while output is paused
+    pub(crate) fn flush_control(&mut self, byte: u8) -> Result<(), W::Error> {
+        let paused = core::mem::replace(&mut self.paused, false);
+        let res = self.flush_bytes(&[byte]);
+        self.paused = paused;
+        res
+    }
+}
+
+impl<W: Write> Write for Tap<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.muted || self.paused {
+            return Ok(buf.len());
+        }
+        let len = self.inner.write(buf)?;
+        if let Some(transcript) = self.transcript {
+            transcript(Transcript::Sent(&buf[..len]));
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.muted || self.paused {
+            return Ok(());
+        }
+        self.inner.flush()
+    }
+}
+
+/// Alignment of text inside a column
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+pub struct Writer<'a, W: Write<Error = E> + ?Sized, E: Error> {
+    colors: bool,
     last_bytes: [u8; 2],
     dirty: bool,
-    writer: &'a mut W,
+    help_short: Option<char>,
+    kv_width: usize,
+    list_prefix: &'static str,
+    machine: bool,
+    writer: Tap<&'a mut W>,
 }
 
-impl<'a, W: Write<Error = E>, E: Error> Debug for Writer<'a, W, E> {
+impl<'a, W: Write<Error = E> + ?Sized, E: Error> Debug for Writer<'a, W, E> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Writer")
+            .field("colors", &self.colors)
             .field("last_bytes", &self.last_bytes)
             .field("dirty", &self.dirty)
+            .field("help_short", &self.help_short)
+            .field("kv_width", &self.kv_width)
+            .field("list_prefix", &self.list_prefix)
+            .field("machine", &self.machine)
             .finish()
     }
 }
 
+/// Writer with type-erased sink.
+///
+/// Code, that is generic over writer (like generated help), is compiled
+/// only once for all sinks with the same error type, when it writes through this writer
+pub type DynWriter<'a, E> = Writer<'a, dyn Write<Error = E> + 'a, E>;
+
 impl<'a, W: Write<Error = E>, E: Error> Writer<'a, W, E> {
+    /// Create writer, that reports written bytes to the same hook as given tap
+    pub(crate) fn tapped(tap: &'a mut Tap<W>) -> Self {
+        let mut writer = Self::new(&mut tap.inner);
+        writer.writer.muted = tap.muted;
+        writer.writer.paused = tap.paused;
+        writer.writer.transcript = tap.transcript;
+        writer
+    }
+}
+
+impl<'a, E: Error> DynWriter<'a, E> {
+    /// Same as `Writer::tapped`, but type of the sink is erased
+    pub(crate) fn erased<W: Write<Error = E> + 'a>(tap: &'a mut Tap<W>) -> Self {
+        let mut writer = Self::new(&mut tap.inner);
+        writer.writer.muted = tap.muted;
+        writer.writer.paused = tap.paused;
+        writer.writer.transcript = tap.transcript;
+        writer
+    }
+}
+
+impl<'a, W: Write<Error = E> + ?Sized, E: Error> Writer<'a, W, E> {
     pub fn new(writer: &'a mut W) -> Self {
         Self {
+            colors: false,
             last_bytes: [0; 2],
             dirty: false,
-            writer,
+            help_short: Some('h'),
+            kv_width: 0,
+            list_prefix: "",
+            machine: false,
+            writer: Tap::new(writer, None),
         }
     }
 
+    /// Enable or disable coloring of severity tags
+    pub(crate) fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Short option, that requests help (written in help of each command)
+    pub(crate) fn with_help_short(mut self, help_short: Option<char>) -> Self {
+        self.help_short = help_short;
+        self
+    }
+
+    /// Enable or disable machine-readable output of key-value pairs
+    pub(crate) fn with_machine_mode(mut self, machine: bool) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    /// Whether key-value pairs are written in machine-readable form
+    pub fn is_machine_mode(&self) -> bool {
+        self.machine
+    }
+
+    pub(crate) fn set_machine_mode(&mut self, machine: bool) {
+        self.machine = machine;
+    }
+
     pub(crate) fn is_dirty(&self) -> bool {
         self.dirty
             && (self.last_bytes[0] != codes::CARRIAGE_RETURN
@@ -65,6 +214,50 @@ impl<'a, W: Write<Error = E>, E: Error> Writer<'a, W, E> {
         Ok(())
     }
 
+    /// Write given bytes as is, without translating `\n` to `\r\n`.
+    ///
+    /// Useful for payloads where byte fidelity matters (e.g. base64 dumps).
+    /// Cursor is still considered at line start if bytes end with `\r\n`
+    pub fn write_raw(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.writer.write_bytes(bytes)?;
+        self.dirty = true;
+
+        if bytes.len() > 1 {
+            self.last_bytes[0] = bytes[bytes.len() - 2];
+        } else {
+            self.last_bytes[0] = self.last_bytes[1];
+        }
+        self.last_bytes[1] = bytes[bytes.len() - 1];
+        Ok(())
+    }
+
+    /// Write given char `count` times.
+    ///
+    /// Chars are written in chunks, so padding and separators
+    /// don't result in a separate write call per char
+    pub fn write_repeated(&mut self, ch: char, count: usize) -> Result<(), E> {
+        const CHUNK_LEN: usize = 16;
+        let mut buf = [0; CHUNK_LEN];
+        let len = ch.len_utf8();
+        let per_chunk = CHUNK_LEN / len;
+        for i in 0..per_chunk {
+            ch.encode_utf8(&mut buf[i * len..]);
+        }
+
+        let mut left = count;
+        while left > 0 {
+            let chars = left.min(per_chunk);
+            // SAFETY: buffer is filled with complete utf-8 encoded chars
+            let chunk = unsafe { core::str::from_utf8_unchecked(&buf[..chars * len]) };
+            self.write_str(chunk)?;
+            left -= chars;
+        }
+        Ok(())
+    }
+
     pub fn writeln_str(&mut self, text: &str) -> Result<(), E> {
         self.writer.write_str(text)?;
         self.writer.write_str(codes::CRLF)?;
@@ -72,33 +265,271 @@ impl<'a, W: Write<Error = E>, E: Error> Writer<'a, W, E> {
         Ok(())
     }
 
+    /// Set prefix, that is written before each name of list element.
+    /// Returns previous prefix, so it can be restored.
+    #[doc(hidden)]
+    pub fn set_list_prefix(&mut self, prefix: &'static str) -> &'static str {
+        core::mem::replace(&mut self.list_prefix, prefix)
+    }
+
     pub fn write_list_element(
         &mut self,
         name: &str,
         description: &str,
         longest_name: usize,
+    ) -> Result<(), E> {
+        self.write_list_element_aligned(name, description, longest_name, 0, Align::Left)
+    }
+
+    /// Write list element with description aligned inside column
+    /// of given width. Useful for numeric values in tables,
+    /// that are easier to read when aligned to the right.
+    pub fn write_list_element_aligned(
+        &mut self,
+        name: &str,
+        description: &str,
+        longest_name: usize,
+        longest_description: usize,
+        align: Align,
     ) -> Result<(), E> {
         self.write_str("  ")?;
-        self.write_str(name)?;
-        if name.len() < longest_name {
-            for _ in 0..longest_name - name.len() {
-                self.write_str(" ")?;
-            }
-        }
+        self.write_str(self.list_prefix)?;
+        self.write_padded(name, longest_name, ' ')?;
         self.write_str("  ")?;
+        if align == Align::Right {
+            self.write_repeated(' ', longest_description.saturating_sub(description.len()))?;
+        }
         self.writeln_str(description)?;
 
         Ok(())
     }
 
+    /// Write list element of argument, that accepts only given values.
+    /// Values are listed after description
+    #[doc(hidden)]
+    pub fn write_list_element_with_values(
+        &mut self,
+        name: &str,
+        description: &str,
+        values: &[&str],
+        longest_name: usize,
+    ) -> Result<(), E> {
+        if values.is_empty() {
+            return self.write_list_element(name, description, longest_name);
+        }
+        self.write_str("  ")?;
+        self.write_str(self.list_prefix)?;
+        self.write_padded(name, longest_name, ' ')?;
+        self.write_str("  ")?;
+        if !description.is_empty() {
+            self.write_str(description)?;
+            self.write_str(" ")?;
+        }
+        self.write_str("[possible values: ")?;
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.write_str(", ")?;
+            }
+            self.write_str(value)?;
+        }
+        self.writeln_str("]")
+    }
+
+    /// Write list element of help option (`-h, --help` by default)
+    #[doc(hidden)]
+    pub fn write_help_option(&mut self, longest_name: usize) -> Result<(), E> {
+        const LONG: &str = "--help";
+        // dash, short option char, separator and long option
+        let mut buf = [0; 1 + 4 + 2 + LONG.len()];
+        let name = match self.help_short {
+            Some(short) => {
+                buf[0] = b'-';
+                let len = 1 + short.encode_utf8(&mut buf[1..]).len();
+                buf[len..len + 2].copy_from_slice(b", ");
+                buf[len + 2..len + 2 + LONG.len()].copy_from_slice(LONG.as_bytes());
+                // SAFETY: buffer is filled with valid utf-8
+                unsafe { core::str::from_utf8_unchecked(&buf[..len + 2 + LONG.len()]) }
+            }
+            None => LONG,
+        };
+        self.write_list_element(name, "Print help", longest_name)
+    }
+
     pub fn write_title(&mut self, title: &str) -> Result<(), E> {
         //TODO: add formatting
         self.write_str(title)?;
         Ok(())
     }
+
+    /// Save current cursor position (DECSC), so it can be restored
+    /// later with `restore_cursor`
+    pub fn save_cursor(&mut self) -> Result<(), E> {
+        self.writer.write_bytes(codes::SAVE_CURSOR)
+    }
+
+    /// Restore cursor position saved by `save_cursor` (DECRC)
+    pub fn restore_cursor(&mut self) -> Result<(), E> {
+        self.writer.write_bytes(codes::RESTORE_CURSOR)
+    }
+
+    /// Move cursor to given row and column. Both are 1-based,
+    /// so top left corner of terminal is at (1, 1)
+    pub fn move_cursor_to(&mut self, row: u16, column: u16) -> Result<(), E> {
+        self.writer
+            .write_csi(&[row, column], codes::CURSOR_POSITION)
+    }
+
+    /// Write given text (or any other displayable value)
+    /// as a single line prefixed with `error: ` tag
+    pub fn error<T: uDisplay + ?Sized>(&mut self, text: &T) -> Result<(), E> {
+        self.write_tagged("error: ", codes::SGR_FG_RED, text)
+    }
+
+    /// Write given text (or any other displayable value)
+    /// as a single line prefixed with `info: ` tag
+    pub fn info<T: uDisplay + ?Sized>(&mut self, text: &T) -> Result<(), E> {
+        self.write_tagged("info: ", codes::SGR_FG_GREEN, text)
+    }
+
+    /// Write given text (or any other displayable value)
+    /// as a single line prefixed with `warn: ` tag
+    pub fn warn<T: uDisplay + ?Sized>(&mut self, text: &T) -> Result<(), E> {
+        self.write_tagged("warn: ", codes::SGR_FG_YELLOW, text)
+    }
+
+    /// Write key-value pair as a single line.
+    ///
+    /// In normal mode pair is written as `key=value`,
+    /// in machine mode as `{"key":value}`, so value must be a valid json
+    /// value (for example, a number). Use `kv_str` for string values.
+    pub fn kv<T: uDisplay + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), E> {
+        self.start_kv(key)?;
+        value.fmt(&mut Formatter::new(self))?;
+        self.end_kv()
+    }
+
+    /// Set width, to which names are padded by `write_kv`.
+    /// Returns previous width, so it can be restored.
+    pub fn set_kv_width(&mut self, width: usize) -> usize {
+        core::mem::replace(&mut self.kv_width, width)
+    }
+
+    /// Write key-value pair as a line of status block.
+    ///
+    /// In normal mode name is padded with dots to width,
+    /// given with `set_kv_width`, so values of consecutive pairs are aligned:
+    /// `name.....: value`. In machine mode pair is written the same way as with `kv`
+    pub fn write_kv<T: uDisplay + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), E> {
+        if self.machine {
+            return self.kv(name, value);
+        }
+        if self.is_dirty() {
+            self.writeln_str("")?;
+        }
+        self.write_padded(name, self.kv_width, '.')?;
+        self.write_str(": ")?;
+        value.fmt(&mut Formatter::new(self))?;
+        self.writeln_str("")
+    }
+
+    /// Write key-value pair with string value as a single line.
+    ///
+    /// In normal mode pair is written as `key=value`,
+    /// in machine mode as `{"key":"value"}`
+    pub fn kv_str(&mut self, key: &str, value: &str) -> Result<(), E> {
+        self.start_kv(key)?;
+        if self.machine {
+            self.write_json_str(value)?;
+        } else {
+            self.write_str(value)?;
+        }
+        self.end_kv()
+    }
+
+    fn start_kv(&mut self, key: &str) -> Result<(), E> {
+        if self.is_dirty() {
+            self.writeln_str("")?;
+        }
+        if self.machine {
+            self.write_str("{")?;
+            self.write_json_str(key)?;
+            self.write_str(":")
+        } else {
+            self.write_str(key)?;
+            self.write_str("=")
+        }
+    }
+
+    fn end_kv(&mut self) -> Result<(), E> {
+        if self.machine {
+            self.writeln_str("}")
+        } else {
+            self.writeln_str("")
+        }
+    }
+
+    /// Write text padded with given char up to given width
+    fn write_padded(&mut self, text: &str, width: usize, fill: char) -> Result<(), E> {
+        self.write_str(text)?;
+        self.write_repeated(fill, width.saturating_sub(text.len()))
+    }
+
+    fn write_json_str(&mut self, mut text: &str) -> Result<(), E> {
+        const HEX: &str = "0123456789abcdef";
+
+        self.write_str("\"")?;
+        while let Some(pos) = text
+            .as_bytes()
+            .iter()
+            .position(|&b| b == b'"' || b == b'\\' || b < 0x20)
+        {
+            // SAFETY: pos is inside text slice and points to ascii char
+            let (left, right) =
+                unsafe { (text.get_unchecked(..pos), text.get_unchecked(pos + 1..)) };
+            self.write_str(left)?;
+            match text.as_bytes().get(pos).copied().unwrap_or(0) {
+                b'"' => self.write_str("\\\"")?,
+                b'\\' => self.write_str("\\\\")?,
+                b'\n' => self.write_str("\\n")?,
+                b'\r' => self.write_str("\\r")?,
+                b'\t' => self.write_str("\\t")?,
+                // other control chars are written as unicode escapes
+                byte => {
+                    let (high, low) = ((byte >> 4) as usize, (byte & 0xF) as usize);
+                    self.write_str("\\u00")?;
+                    self.write_str(HEX.get(high..=high).unwrap_or_default())?;
+                    self.write_str(HEX.get(low..=low).unwrap_or_default())?;
+                }
+            }
+            text = right;
+        }
+        self.write_str(text)?;
+        self.write_str("\"")
+    }
+
+    fn write_tagged<T: uDisplay + ?Sized>(
+        &mut self,
+        tag: &str,
+        color: &[u8],
+        text: &T,
+    ) -> Result<(), E> {
+        // tag always starts from new line
+        if self.is_dirty() {
+            self.writeln_str("")?;
+        }
+        if self.colors {
+            self.writer.write_bytes(color)?;
+            self.writer.write_str(tag)?;
+            self.writer.write_bytes(codes::SGR_RESET)?;
+        } else {
+            self.writer.write_str(tag)?;
+        }
+        text.fmt(&mut Formatter::new(self))?;
+        self.writeln_str("")
+    }
 }
 
-impl<'a, W: Write<Error = E>, E: Error> uWrite for Writer<'a, W, E> {
+impl<'a, W: Write<Error = E> + ?Sized, E: Error> uWrite for Writer<'a, W, E> {
     type Error = E;
 
     fn write_str(&mut self, s: &str) -> Result<(), E> {
@@ -106,7 +537,7 @@ impl<'a, W: Write<Error = E>, E: Error> uWrite for Writer<'a, W, E> {
     }
 }
 
-impl<'a, W: Write<Error = E>, E: Error> core::fmt::Write for Writer<'a, W, E> {
+impl<'a, W: Write<Error = E> + ?Sized, E: Error> core::fmt::Write for Writer<'a, W, E> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
         self.write_str(s).map_err(|_| core::fmt::Error)?;
         Ok(())
@@ -121,10 +552,13 @@ pub(crate) trait WriteExt: ErrorType {
 
     fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
 
+    /// Write control sequence with given numeric parameters
+    fn write_csi(&mut self, params: &[u16], final_byte: u8) -> Result<(), Self::Error>;
+
     fn write_str(&mut self, text: &str) -> Result<(), Self::Error>;
 }
 
-impl<W: Write> WriteExt for W {
+impl<W: Write + ?Sized> WriteExt for W {
     fn flush_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
         self.write_bytes(bytes)?;
         self.flush()
@@ -138,6 +572,18 @@ impl<W: Write> WriteExt for W {
         self.write_all(bytes)
     }
 
+    fn write_csi(&mut self, params: &[u16], final_byte: u8) -> Result<(), Self::Error> {
+        self.write_bytes(codes::CSI)?;
+        for (i, &param) in params.iter().enumerate() {
+            if i > 0 {
+                self.write_str(";")?;
+            }
+            let mut buf = [0; 5];
+            self.write_str(utils::encode_u16(param, &mut buf))?;
+        }
+        self.write_bytes(&[final_byte])
+    }
+
     fn write_str(&mut self, text: &str) -> Result<(), Self::Error> {
         self.write_bytes(text.as_bytes())
     }
@@ -162,7 +608,12 @@ impl Write for EmptyWriter {
 
 #[cfg(test)]
 mod tests {
-    use crate::writer::{EmptyWriter, Writer};
+    use rstest::rstest;
+    use std::format;
+
+    use embedded_io::Write;
+
+    use crate::writer::{Align, DynWriter, EmptyWriter, Writer};
 
     #[test]
     fn detect_dirty() {
@@ -183,4 +634,153 @@ mod tests {
         writer.write_str("abc\r\n").unwrap();
         assert!(!writer.is_dirty());
     }
+
+    #[test]
+    fn dyn_writer() {
+        let mut buf = [0u8; 16];
+        let mut out = &mut buf[..];
+        let sink: &mut dyn Write<Error = _> = &mut out;
+        let mut writer = DynWriter::new(sink);
+
+        writer.write_str("abc").unwrap();
+        assert!(writer.is_dirty());
+        writer.write_list_element("x", "y", 1).unwrap();
+
+        let written = 16 - out.len();
+        assert_eq!(
+            core::str::from_utf8(&buf[..written]).unwrap(),
+            "abc  x  y\r\n"
+        );
+    }
+
+    #[test]
+    fn list_alignment() {
+        let mut buf = [0u8; 64];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out);
+
+        writer
+            .write_list_element_aligned("rx", "5", 4, 3, Align::Right)
+            .unwrap();
+        writer
+            .write_list_element_aligned("tx", "120", 4, 3, Align::Right)
+            .unwrap();
+        writer
+            .write_list_element_aligned("err", "5", 4, 3, Align::Left)
+            .unwrap();
+
+        let written = 64 - out.len();
+        assert_eq!(
+            core::str::from_utf8(&buf[..written]).unwrap(),
+            "  rx      5\r\n  tx    120\r\n  err   5\r\n"
+        );
+    }
+
+    #[rstest]
+    #[case("Mode", &["on", "off"], "  -m  Mode [possible values: on, off]\r\n")]
+    #[case("", &["on"], "  -m  [possible values: on]\r\n")]
+    #[case("Mode", &[], "  -m  Mode\r\n")]
+    fn list_element_with_values(
+        #[case] description: &str,
+        #[case] values: &[&str],
+        #[case] expected: &str,
+    ) {
+        let mut buf = [0u8; 64];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out);
+
+        writer
+            .write_list_element_with_values("-m", description, values, 2)
+            .unwrap();
+
+        let written = 64 - out.len();
+        assert_eq!(core::str::from_utf8(&buf[..written]).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(Some('h'), "  -h, --help  Print help\r\n")]
+    #[case(Some('ф'), "  -ф, --help  Print help\r\n")]
+    #[case(None, "  --help      Print help\r\n")]
+    fn write_help_option(#[case] help_short: Option<char>, #[case] expected: &str) {
+        let mut buf = [0u8; 64];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out).with_help_short(help_short);
+
+        writer.write_help_option(10).unwrap();
+
+        let written = 64 - out.len();
+        assert_eq!(core::str::from_utf8(&buf[..written]).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("led \"1\"", r#"{"name":"led \"1\""}"#)]
+    #[case("a\\b", r#"{"name":"a\\b"}"#)]
+    #[case("a\nb", r#"{"name":"a\nb"}"#)]
+    #[case("a\rb", r#"{"name":"a\rb"}"#)]
+    #[case("a\tb", r#"{"name":"a\tb"}"#)]
+    #[case("a\x00b\x1Bc\x1F", r#"{"name":"a\u0000b\u001bc\u001f"}"#)]
+    #[case("привет", r#"{"name":"привет"}"#)]
+    fn json_escapes(#[case] value: &str, #[case] expected: &str) {
+        let mut buf = [0u8; 64];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out).with_machine_mode(true);
+
+        writer.kv_str("name", value).unwrap();
+
+        let written = 64 - out.len();
+        assert_eq!(
+            core::str::from_utf8(&buf[..written]).unwrap(),
+            format!("{}\r\n", expected)
+        );
+    }
+
+    #[test]
+    fn write_raw() {
+        let mut buf = [0u8; 32];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out);
+
+        writer.write_raw(b"aGk=\n").unwrap();
+        assert!(writer.is_dirty());
+        writer.write_raw(b"\r").unwrap();
+        writer.write_raw(b"\n").unwrap();
+        assert!(!writer.is_dirty());
+        writer.write_str("a\n").unwrap();
+
+        let written = 32 - out.len();
+        assert_eq!(&buf[..written], b"aGk=\n\r\na\r\n");
+    }
+
+    #[test]
+    fn write_repeated() {
+        let mut buf = [0u8; 64];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out);
+
+        writer.write_repeated('-', 20).unwrap();
+        writer.write_repeated('ф', 9).unwrap();
+        writer.write_repeated(' ', 0).unwrap();
+        assert!(writer.is_dirty());
+
+        let written = 64 - out.len();
+        assert_eq!(
+            core::str::from_utf8(&buf[..written]).unwrap(),
+            "--------------------ффффффффф"
+        );
+    }
+
+    #[test]
+    fn cursor_helpers() {
+        let mut buf = [0u8; 32];
+        let mut out = &mut buf[..];
+        let mut writer = Writer::new(&mut out);
+
+        writer.save_cursor().unwrap();
+        writer.move_cursor_to(24, 1).unwrap();
+        writer.write_str("status").unwrap();
+        writer.restore_cursor().unwrap();
+
+        let written = 32 - out.len();
+        assert_eq!(&buf[..written], b"\x1B7\x1B[24;1Hstatus\x1B8");
+    }
 }
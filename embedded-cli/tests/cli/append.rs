@@ -0,0 +1,85 @@
+use embedded_cli::{
+    arguments::Values,
+    command::RawCommand,
+    service::{FromRaw, ParseError},
+    Command,
+};
+use rstest::rstest;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+#[command(derive_handler)]
+#[cfg_attr(feature = "owned", command(derive_owned))]
+enum CliCommand<'a> {
+    Find {
+        #[arg(short, long, append)]
+        filter: Values<'a, &'a str>,
+
+        #[arg(short, long)]
+        name: Option<&'a str>,
+
+        #[arg(long, append)]
+        level: Values<'a, u8>,
+
+        #[arg(short)]
+        verbose: bool,
+
+        path: Option<&'a str>,
+    },
+}
+
+fn parse(input: &str, f: impl FnOnce(CliCommand<'_>)) {
+    let mut input = input.to_string();
+    let command = CliCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap();
+    f(command)
+}
+
+#[rstest]
+#[case("find", &[], &[])]
+#[case("find --filter a", &["a"], &[])]
+#[case("find --filter a -f b --level 2", &["a", "b"], &[2])]
+#[case("find -vf a -n a --level 3 --level 5 -f -- -b path", &["a", "-b"], &[3, 5])]
+fn values_collected(#[case] input: &str, #[case] filters: &[&str], #[case] levels: &[u8]) {
+    parse(input, |command| {
+        let CliCommand::Find { filter, level, .. } = command;
+        assert_eq!(filter.len(), filters.len());
+        assert_eq!(filter.iter().collect::<Vec<_>>(), filters);
+        assert_eq!(level.into_iter().collect::<Vec<_>>(), levels);
+    });
+}
+
+#[test]
+fn values_with_other_args() {
+    parse("find -f a -n b -f c path", |command| {
+        let CliCommand::Find {
+            filter, name, path, ..
+        } = command;
+        assert_eq!(filter.iter().collect::<Vec<_>>(), vec!["a", "c"]);
+        assert_eq!(name, Some("b"));
+        assert_eq!(path, Some("path"));
+    });
+}
+
+#[test]
+fn invalid_value() {
+    let mut input = String::from("find --level 1 --level x");
+    let result = CliCommand::parse(RawCommand::from_input(&mut input).unwrap());
+
+    assert_eq!(
+        result,
+        Err(ParseError::ParseValueError {
+            name: Some("--level"),
+            value: "x",
+            expected: "u8",
+        })
+    );
+}
+
+#[cfg(feature = "owned")]
+#[test]
+fn values_converted_to_owned() {
+    parse("find -f a -f b --level 4", |command| {
+        let CliCommandOwned::Find { filter, level, .. } = command.into_owned();
+        assert_eq!(filter, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(level, vec![4]);
+    });
+}
@@ -0,0 +1,157 @@
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+
+use embedded_cli::{
+    async_cli::{AsyncCommandProcessor, AsyncError, RunError},
+    cli::{CliBuilder, CliHandle},
+    command::RawCommand,
+    service::{FromRaw, ProcessError},
+    Command,
+};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Get,
+    Dump,
+}
+
+/// Future, that is pending on first poll (like waiting for peripheral)
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+}
+
+struct AsyncProcessor;
+
+impl<W: embedded_io::Write<Error = E>, E: embedded_io::Error> AsyncCommandProcessor<W, E>
+    for AsyncProcessor
+{
+    async fn process<'a>(
+        &mut self,
+        cli: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        match CliCommand::parse(raw)? {
+            CliCommand::Get => {
+                YieldOnce(false).await;
+                cli.writer().write_str("value: 5")?;
+            }
+            CliCommand::Dump => cli.set_prompt("# "),
+        }
+        Ok(())
+    }
+}
+
+/// Futures of AsyncCli complete immediately when writer is always ready
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn output_written_to_async_writer() {
+    let output = Output::default();
+    let mut cli = block_on(CliBuilder::default().build_async(output.clone(), [0; 64])).unwrap();
+    assert_eq!(output.bytes(), b"$ ");
+
+    let mut processor = CliCommand::processor(|cli, _| cli.writer().write_str("value: 5"));
+    block_on(cli.process_slice::<CliCommand, _>(b"get\n", &mut processor)).unwrap();
+
+    block_on(cli.write(|writer| writer.write_str("async message"))).unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ get", "value: 5", "async message", "$"]
+    );
+}
+
+#[test]
+fn output_overflow() {
+    let output = Output::default();
+    let mut cli = block_on(CliBuilder::default().build_async(output.clone(), [0; 16])).unwrap();
+
+    let mut processor =
+        CliCommand::processor(|cli, _| cli.writer().write_str("too long to fit into buffer"));
+
+    assert_eq!(
+        block_on(cli.process_slice::<CliCommand, _>(b"dump\n", &mut processor)),
+        Err(AsyncError::OutputOverflow)
+    );
+
+    // output, that fit into buffer, is still written
+    assert_eq!(output.bytes(), b"$ dump\r\ntoo long to fi");
+}
+
+#[test]
+fn async_processor() {
+    let output = Output::default();
+    let mut cli = block_on(CliBuilder::default().build_async(output.clone(), [0; 64])).unwrap();
+    let mut processor = AsyncProcessor;
+
+    {
+        let mut future = pin!(cli.process_slice_async::<CliCommand, _>(b"get\n", &mut processor));
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(future.as_mut().poll(&mut context).is_pending());
+
+        // echo of entered command is written while processor is waiting
+        assert_terminal!(output.terminal(), 0, vec!["$ get", ""]);
+
+        assert!(future.as_mut().poll(&mut context).is_ready());
+    }
+    assert_terminal!(output.terminal(), 2, vec!["$ get", "value: 5", "$"]);
+
+    block_on(cli.process_slice_async::<CliCommand, _>(b"dump\nset\n", &mut processor)).unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ get",
+            "value: 5",
+            "$ dump",
+            "# set",
+            "error: unknown command",
+            "#"
+        ]
+    );
+}
+
+#[test]
+fn run_until_end_of_input() {
+    let output = Output::default();
+    let mut cli = block_on(CliBuilder::default().build_async(output.clone(), [0; 64])).unwrap();
+
+    let mut processor = CliCommand::processor(|cli, _| cli.writer().write_str("value: 5"));
+    let input: &[u8] = b"get\nget\n";
+    let result: Result<(), RunError<_, _>> =
+        block_on(cli.run::<CliCommand, _, _>(input, &mut processor));
+    assert_eq!(result, Ok(()));
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ get", "value: 5", "$ get", "value: 5", "$"]
+    );
+}
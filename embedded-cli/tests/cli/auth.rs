@@ -0,0 +1,149 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Get,
+    Logout,
+}
+
+fn check(password: &str) -> bool {
+    password == "secret"
+}
+
+#[rstest]
+#[case(Some('*'), "password: ******")]
+#[case(None, "password:")]
+fn commands_rejected_until_login(#[case] mask: Option<char>, #[case] login_line: &str) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .auth("password: ", check)
+        .secret_mask(mask)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+    assert!(cli.is_locked());
+
+    let mut commands = vec![];
+    {
+        let mut processor = CliCommand::processor(|_, command| {
+            commands.push(command);
+            Ok(())
+        });
+        for b in b"get\nsecret\nget\n" {
+            cli.process_byte::<CliCommand, _>(*b, &mut processor)
+                .unwrap();
+        }
+    }
+    assert_eq!(commands, vec![CliCommand::Get]);
+    assert!(!cli.is_locked());
+
+    let mask_line = if mask.is_some() {
+        "password: ***"
+    } else {
+        "password:"
+    };
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![mask_line, "error: wrong password", login_line, "$ get", "$"]
+    );
+}
+
+#[test]
+fn backspace_and_cancel() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .auth("password: ", check)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"abc\x03secrex\x08" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    assert_terminal!(
+        output.terminal(),
+        15,
+        vec!["password: ***^C", "password: *****"]
+    );
+
+    for b in b"t\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    assert!(!cli.is_locked());
+}
+
+#[test]
+fn lock_from_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .auth("password: ", check)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        if command == CliCommand::Logout {
+            cli.lock();
+        }
+        Ok(())
+    });
+    for b in b"secret\nlogout\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    assert!(cli.is_locked());
+    assert_terminal!(
+        output.terminal(),
+        10,
+        vec!["password: ******", "$ logout", "password:"]
+    );
+
+    // secret is not stored in history
+    for b in b"secret\n\x1B[A" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    #[cfg(feature = "history")]
+    assert_eq!(cli.current_input(), "logout");
+}
+
+#[test]
+fn lock_from_outside() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .auth("password: ", check)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"secret\nge" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.lock().unwrap();
+    assert!(cli.is_locked());
+    assert_eq!(cli.current_input(), "");
+    assert_terminal!(output.terminal(), 10, vec!["password: ******", "password:"]);
+}
+
+#[test]
+fn lock_without_auth() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    cli.lock().unwrap();
+    assert!(!cli.is_locked());
+    assert_terminal!(output.terminal(), 2, vec!["$"]);
+}
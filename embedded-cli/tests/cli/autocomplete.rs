@@ -1,7 +1,11 @@
+use embedded_cli::autocomplete::{Autocompletion, Request};
+use embedded_cli::cli::CliBuilder;
 use embedded_cli::command::RawCommand;
 use embedded_cli::service::FromRaw;
 use embedded_cli::Command;
+use rstest::rstest;
 
+use crate::output::Output;
 use crate::wrapper::{CliWrapper, CommandConvert, ParseError};
 
 use crate::terminal::assert_terminal;
@@ -170,3 +174,90 @@ fn complete_when_inside_and_empty_completion() {
     cli.send_tab();
     assert_terminal!(cli.terminal(), 6, vec!["$ get-"]);
 }
+
+fn complete_files(request: Request<'_>, autocompletion: &mut Autocompletion<'_>) {
+    const FILES: &[&str] = &["boot.bin", "config.txt", "core.bin"];
+    if let Request::ArgValue {
+        command: "cat",
+        arg: 0,
+        partial,
+    } = request
+    {
+        for file in FILES {
+            if let Some(autocompleted) = file.strip_prefix(partial) {
+                autocompletion.merge_autocompletion(autocompleted)
+            }
+        }
+    }
+}
+
+#[test]
+fn complete_arg_value_with_hook() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .autocomplete_hook(complete_files)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = TestCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<TestCommand, _>(b"cat b	", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), 15, vec!["$ cat boot.bin"]);
+
+    cli.process_slice::<TestCommand, _>(b"cat c	", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), 8, vec!["$ cat boot.bin ^C", "$ cat co"]);
+
+    cli.process_slice::<TestCommand, _>(b"n	", &mut processor)
+        .unwrap();
+    assert_terminal!(
+        output.terminal(),
+        17,
+        vec!["$ cat boot.bin ^C", "$ cat config.txt"]
+    );
+
+    // only first argument is completed
+    cli.process_slice::<TestCommand, _>(b"b	", &mut processor)
+        .unwrap();
+    assert_terminal!(
+        output.terminal(),
+        18,
+        vec!["$ cat boot.bin ^C", "$ cat config.txt b"]
+    );
+}
+
+fn complete_option(request: Request<'_>, autocompletion: &mut Autocompletion<'_>) {
+    if let Request::ArgValue {
+        command: "cat",
+        arg: 1,
+        partial,
+    } = request
+    {
+        if let Some(autocompleted) = "--verbose".strip_prefix(partial) {
+            autocompletion.merge_autocompletion(autocompleted)
+        }
+    }
+}
+
+#[rstest]
+#[case(r#"cat "a b" --v"#, r#"$ cat "a b" --verbose"#, 22)]
+#[case(r#"cat "a b"  "#, r#"$ cat "a b"  --verbose"#, 23)]
+#[case(r#"cat "a --v"#, r#"$ cat "a --v"#, 12)]
+fn complete_arg_value_after_quoted(
+    #[case] input: &str,
+    #[case] expected: &str,
+    #[case] cursor: usize,
+) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .autocomplete_hook(complete_option)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = TestCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<TestCommand, _>(format!("{}\t", input).as_bytes(), &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), cursor, vec![expected]);
+}
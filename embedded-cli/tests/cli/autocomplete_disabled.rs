@@ -1,6 +1,8 @@
+use embedded_cli::cli::TabInsert;
 use embedded_cli::command::RawCommand;
 use embedded_cli::service::FromRaw;
 use embedded_cli::Command;
+use rstest::rstest;
 
 use crate::wrapper::{CliWrapper, CommandConvert, ParseError};
 
@@ -29,3 +31,20 @@ fn autocomplete_disabled() {
 
     assert_terminal!(cli.terminal(), 3, vec!["$ e"]);
 }
+
+#[rstest]
+#[case(TabInsert::Nothing, "$ ex", 3)]
+#[case(TabInsert::Tab, "$ e x", 4)]
+#[case(TabInsert::Spaces(2), "$ e  x", 5)]
+fn tab_inserted(#[case] tab_insert: TabInsert, #[case] expected: &str, #[case] cursor: usize) {
+    let mut cli = CliWrapper::<TestCommand>::builder()
+        .tab_insert(tab_insert)
+        .build();
+
+    cli.process_str("x");
+    cli.send_left();
+    cli.process_str("e");
+    cli.send_tab();
+
+    assert_terminal!(cli.terminal(), cursor, vec![expected]);
+}
@@ -1,5 +1,11 @@
+use embedded_cli::{
+    cli::{CliBuilder, TabInsert},
+    command::RawCommand as EmbeddedRawCommand,
+    prompt::Prompt,
+};
 use rstest::rstest;
 
+use crate::output::Output;
 use crate::wrapper::{Arg, CliWrapper, RawCommand};
 
 use crate::terminal::assert_terminal;
@@ -31,6 +37,27 @@ fn simple_input() {
     );
 }
 
+#[rstest]
+#[case(r#"set "" led"#, &["", "led"])]
+#[case(r#"set led """#, &["led", ""])]
+fn empty_quoted_args(#[case] input: &str, #[case] expected: &[&str]) {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str(input);
+    cli.send_enter();
+
+    assert_eq!(
+        cli.received_commands(),
+        vec![Ok(RawCommand {
+            name: "set".to_string(),
+            args: expected
+                .iter()
+                .map(|arg| Arg::Value(arg.to_string()))
+                .collect(),
+        })]
+    );
+}
+
 #[test]
 fn delete_with_backspace() {
     let mut cli = CliWrapper::default();
@@ -54,6 +81,106 @@ fn delete_with_backspace() {
     assert_terminal!(cli.terminal(), 2, vec!["$"]);
 }
 
+#[test]
+fn delete_at_cursor() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set");
+    cli.send_delete();
+    assert_terminal!(cli.terminal(), 5, vec!["$ set"]);
+
+    cli.send_left();
+    cli.send_left();
+    cli.send_delete();
+    assert_terminal!(cli.terminal(), 3, vec!["$ st"]);
+
+    cli.send_delete();
+    cli.send_delete();
+    assert_terminal!(cli.terminal(), 3, vec!["$ s"]);
+
+    cli.send_enter();
+    assert_eq!(
+        cli.received_commands(),
+        vec![Ok(RawCommand {
+            name: "s".to_string(),
+            args: vec![],
+        })]
+    );
+}
+
+#[test]
+fn move_by_words() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set led  on");
+    cli.process_str("\x1Bb");
+    assert_terminal!(cli.terminal(), 11, vec!["$ set led  on"]);
+
+    cli.process_str("\x1Bb");
+    assert_terminal!(cli.terminal(), 6, vec!["$ set led  on"]);
+
+    cli.process_str("\x1Bf");
+    assert_terminal!(cli.terminal(), 9, vec!["$ set led  on"]);
+
+    cli.process_str("s");
+    assert_terminal!(cli.terminal(), 10, vec!["$ set leds  on"]);
+}
+
+#[rstest]
+#[case("\x17", "$ set   on", 6)]
+#[case("\x15", "$   on", 2)]
+#[case("\x0B", "$ set led", 9)]
+fn delete_words(#[case] key: &str, #[case] expected: &str, #[case] cursor: usize) {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set led  on");
+    cli.process_str("\x1Bb\x1Bb\x1Bf");
+    cli.process_str(key);
+    assert_terminal!(cli.terminal(), cursor, vec![expected]);
+}
+
+#[test]
+fn move_home_end() {
+    let mut cli = CliWrapper::default();
+
+    cli.send_home();
+    cli.send_end();
+    assert_terminal!(cli.terminal(), 2, vec!["$"]);
+
+    cli.process_str("get led");
+    cli.send_home();
+    assert_terminal!(cli.terminal(), 2, vec!["$ get led"]);
+
+    cli.process_str("s");
+    assert_terminal!(cli.terminal(), 3, vec!["$ sget led"]);
+
+    cli.send_end();
+    assert_terminal!(cli.terminal(), 10, vec!["$ sget led"]);
+
+    cli.process_str("s");
+    assert_terminal!(cli.terminal(), 11, vec!["$ sget leds"]);
+}
+
+#[test]
+fn tab_inserted_inside_quotes() {
+    let mut cli = CliWrapper::builder().tab_insert(TabInsert::Tab).build();
+
+    cli.process_str("set \"a");
+    cli.send_tab();
+    cli.process_str("b\"");
+
+    assert_terminal!(cli.terminal(), 11, vec!["$ set \"a b\""]);
+
+    cli.send_enter();
+    assert_eq!(
+        cli.received_commands(),
+        vec![Ok(RawCommand {
+            name: "set".to_string(),
+            args: vec![Arg::Value("a\tb".to_string())],
+        })]
+    );
+}
+
 #[test]
 fn move_insert() {
     let mut cli = CliWrapper::default();
@@ -186,6 +313,53 @@ fn set_prompt_static(#[case] prompt: &'static str) {
     );
 }
 
+#[test]
+fn cancel_input() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set 1");
+    cli.send_left();
+    cli.send_left();
+    cli.send_ctrl_c();
+    assert_terminal!(cli.terminal(), 2, vec!["$ set 1^C", "$"]);
+
+    cli.send_ctrl_c();
+    assert_terminal!(cli.terminal(), 2, vec!["$ set 1^C", "$ ^C", "$"]);
+
+    cli.process_str("get");
+    cli.send_enter();
+    assert_terminal!(cli.terminal(), 2, vec!["$ set 1^C", "$ ^C", "$ get", "$"]);
+    assert_eq!(
+        cli.received_commands(),
+        vec![Ok(RawCommand {
+            name: "get".to_string(),
+            args: vec![],
+        })]
+    );
+}
+
+#[test]
+fn set_prompt_suffix() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set");
+    cli.set_prompt_suffix("[cfg] ");
+    assert_terminal!(cli.terminal(), 11, vec!["$ [cfg] set"]);
+
+    cli.set_handler(move |cli, _| {
+        cli.set_prompt_suffix("[net] ");
+        Ok(())
+    });
+    cli.send_enter();
+    assert_terminal!(cli.terminal(), 8, vec!["$ [cfg] set", "$ [net]"]);
+
+    cli.set_prompt("# ");
+    assert_terminal!(cli.terminal(), 8, vec!["$ [cfg] set", "# [net]"]);
+
+    cli.set_prompt_suffix("");
+    assert_terminal!(cli.terminal(), 2, vec!["$ [cfg] set", "#"]);
+}
+
 #[test]
 fn try_move_outside() {
     let mut cli = CliWrapper::default();
@@ -217,3 +391,85 @@ fn try_move_outside() {
         })]
     );
 }
+
+#[test]
+fn current_line_state() {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().prompt("> ").build();
+    assert_eq!(cli.prompt(), "> ");
+    assert_eq!(cli.current_input(), "");
+
+    cli.process_str("get 1");
+    cli.send_left();
+    cli.process_str("-");
+    assert_eq!(cli.current_input(), "get -1");
+
+    cli.set_prompt("$ ");
+    assert_eq!(cli.prompt(), "$ ");
+
+    cli.send_enter();
+    assert_eq!(cli.current_input(), "");
+}
+
+#[test]
+fn colored_prompt() {
+    const PROMPT: Prompt = Prompt::new("\x1B[32mdev\x1B[0m> ");
+
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().prompt(PROMPT).build();
+    assert_eq!(cli.prompt(), "\x1B[32mdev\x1B[0m> ");
+    assert_eq!(cli.prompt_width(), 5);
+
+    cli.process_str("set");
+    cli.send_left();
+
+    assert_terminal!(cli.terminal(), 7, vec!["dev> set"]);
+
+    cli.set_prompt("$ ");
+    assert_eq!(cli.prompt_width(), 2);
+}
+
+#[rstest]
+#[case(64, "set  \"a b\" \\\"c", Some("set  \"a b\" \\\"c"))]
+#[case(16, "set  \"a b\" \\\"c", Some("set  \"a b\" \\\"c"))]
+#[case(64, "set  \"a \\\"b\"", None)]
+fn original_line(#[case] buffer_size: usize, #[case] input: &str, #[case] expected: Option<&str>) {
+    let mut cli = CliBuilder::default()
+        .writer(Output::default())
+        .command_buffer(vec![0; buffer_size].leak())
+        .build()
+        .unwrap();
+
+    let mut lines = vec![];
+    let mut processor = EmbeddedRawCommand::processor(|_, command| {
+        lines.push(command.line().map(|line| line.to_string()));
+        Ok(())
+    });
+    for &b in input.as_bytes().iter().chain(b"\n") {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(lines, vec![expected.map(|line| line.to_string())]);
+}
+
+#[test]
+fn arg_offsets() {
+    let mut cli = CliBuilder::default()
+        .writer(Output::default())
+        .build()
+        .unwrap();
+
+    let mut offsets = vec![];
+    let mut processor = EmbeddedRawCommand::processor(|_, command| {
+        let sub = command.subcommand().unwrap();
+        offsets.extend(sub.args_with_offsets().unwrap().map(|(offset, _)| offset));
+        Ok(())
+    });
+    for &b in b"net  set \"a b\" -v\n" {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(offsets, vec![9, 15]);
+}
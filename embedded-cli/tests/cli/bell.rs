@@ -0,0 +1,66 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set { value: u8 },
+}
+
+#[rstest]
+#[case(true, 1)]
+#[case(false, 0)]
+fn bell_on_parse_error(#[case] enabled: bool, #[case] bells: usize) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .bell_on_error(enabled)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"set 1\nset abc\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    let terminal = output.terminal();
+    assert_eq!(terminal.bells(), bells);
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ set 1",
+            "$ set abc",
+            "error: failed to parse '<VALUE> abc', expected u8",
+            "$"
+        ]
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn bell_on_unknown_help() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .bell_on_error(true)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"help get\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    let terminal = output.terminal();
+    assert_eq!(terminal.bells(), 1);
+    assert_terminal!(
+        &terminal,
+        2,
+        vec!["$ help get", "error: unknown command", "$"]
+    );
+}
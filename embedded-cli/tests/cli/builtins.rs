@@ -0,0 +1,149 @@
+use std::cell::Cell;
+
+use embedded_cli::{cli::CliBuilder, Command};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+thread_local! {
+    static SLEPT: Cell<u32> = const { Cell::new(0) };
+}
+
+fn delay(ms: u32) {
+    SLEPT.with(|slept| slept.set(slept.get() + ms));
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Exit,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum ShadowingCommand {
+    Echo { value: u8 },
+}
+
+/// Process given input and return resulting terminal
+/// with number of commands, that were passed to processor
+fn run(input: &str, with_delay: bool) -> (Terminal, usize) {
+    let output = Output::default();
+    let builder = CliBuilder::default().writer(output.clone());
+    let builder = if with_delay {
+        builder.delay(delay)
+    } else {
+        builder
+    };
+    let mut cli = builder.build().unwrap();
+
+    let mut processed = 0;
+    let mut processor = CliCommand::processor(|_, _| {
+        processed += 1;
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(input.as_bytes(), &mut processor)
+        .unwrap();
+    drop(processor);
+
+    (output.terminal(), processed)
+}
+
+#[rstest]
+#[case("echo hello world", "hello world")]
+#[case("echo   \"hello   world\"  -v --opt", "hello   world -v --opt")]
+#[case("echo", "")]
+fn echo(#[case] command: &str, #[case] expected: &str) {
+    let (terminal, processed) = run(&format!("{}\n", command), false);
+
+    assert_terminal!(
+        terminal,
+        2,
+        vec![
+            format!("$ {}", command),
+            expected.to_string(),
+            "$".to_string()
+        ]
+    );
+    assert_eq!(processed, 0);
+}
+
+#[test]
+fn sleep() {
+    SLEPT.with(|slept| slept.set(0));
+    let (terminal, processed) = run("sleep 150\nsleep 50\n", true);
+
+    assert_eq!(SLEPT.with(|slept| slept.get()), 200);
+    assert_terminal!(terminal, 2, vec!["$ sleep 150", "$ sleep 50", "$"]);
+    assert_eq!(processed, 0);
+}
+
+#[rstest]
+#[case("sleep", "error: missing required argument: <MS>")]
+#[case("sleep abc", "error: failed to parse 'abc', expected u32")]
+#[case("sleep 1 2", "error: unexpected argument: 2")]
+#[case("sleep --ms", "error: unexpected option: --ms")]
+fn sleep_error(#[case] command: &str, #[case] error: &str) {
+    let (terminal, _) = run(&format!("{}\n", command), true);
+
+    assert_terminal!(
+        terminal,
+        2,
+        vec![format!("$ {}", command), error.to_string(), "$".to_string()]
+    );
+}
+
+#[test]
+fn sleep_without_delay() {
+    let (terminal, _) = run("sleep 100\n", false);
+
+    assert_terminal!(
+        terminal,
+        2,
+        vec!["$ sleep 100", "error: unknown command", "$"]
+    );
+}
+
+#[test]
+fn user_command_shadows_builtin() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processed = 0;
+    let mut processor = ShadowingCommand::processor(|_, _| {
+        processed += 1;
+        Ok(())
+    });
+    cli.process_slice::<ShadowingCommand, _>(b"echo 5\necho hello\n", &mut processor)
+        .unwrap();
+    drop(processor);
+
+    assert_eq!(processed, 1);
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ echo 5",
+            "$ echo hello",
+            "error: failed to parse '<VALUE> hello', expected u8",
+            "$"
+        ]
+    );
+}
+
+#[cfg(feature = "autocomplete")]
+#[rstest]
+#[case("ec", "echo")]
+#[case("sl", "sleep")]
+#[case("e", "exit")]
+fn autocomplete(#[case] input: &str, #[case] expected: &str) {
+    let (terminal, _) = run(&format!("{}\t", input), true);
+
+    assert_terminal!(
+        terminal,
+        3 + expected.len(),
+        vec![format!("$ {}", expected)]
+    );
+}
@@ -0,0 +1,21 @@
+use embedded_cli::Command;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliBase<'a> {
+    #[command(name = "base1")]
+    Base1 {
+        name: Option<&'a str>,
+    },
+
+    GetLed,
+
+    Status,
+}
+
+#[test]
+fn command_names() {
+    assert_eq!(CliBase::COMMAND_NAMES, &["base1", "get-led", "status"]);
+    assert_eq!(CliBase::NAME_BASE_1, "base1");
+    assert_eq!(CliBase::NAME_GET_LED, "get-led");
+    assert_eq!(CliBase::NAME_STATUS, "status");
+}
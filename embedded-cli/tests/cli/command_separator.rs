@@ -0,0 +1,125 @@
+use embedded_cli::{cli::CliBuilder, command::RawCommand};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[rstest]
+#[case(true, "set 5; get", &["set 5", "get"])]
+#[case(true, "set 5;get;", &["set 5", "get"])]
+#[case(true, r#"say "a;b"; get"#, &["say a;b", "get"])]
+#[case(true, "; ;get", &["get"])]
+#[case(false, "set 5; get", &["set 5; get"])]
+fn split_commands(#[case] enabled: bool, #[case] input: &str, #[case] expected: &[&str]) {
+    let mut commands = vec![];
+    let mut processor = RawCommand::processor(|_, command| {
+        let mut text = command.name().to_string();
+        for arg in command.args().args() {
+            if let embedded_cli::arguments::Arg::Value(value) = arg {
+                text.push(' ');
+                text.push_str(value);
+            }
+        }
+        commands.push(text);
+        Ok(())
+    });
+    let mut cli = CliBuilder::default()
+        .command_separator(enabled)
+        .build()
+        .unwrap();
+
+    cli.process_slice::<RawCommand<'_>, _>(input.as_bytes(), &mut processor)
+        .unwrap();
+    cli.process_byte::<RawCommand<'_>, _>(b'\r', &mut processor)
+        .unwrap();
+    drop(processor);
+
+    assert_eq!(commands, expected);
+}
+
+#[test]
+fn line_of_each_command() {
+    let mut lines = vec![];
+    let mut processor = RawCommand::processor(|_, command| {
+        lines.push(command.line().map(|line| line.to_string()));
+        Ok(())
+    });
+    // command buffer is too small to keep a copy of the whole line
+    let mut cli = CliBuilder::default()
+        .command_buffer([0; 32])
+        .command_separator(true)
+        .build()
+        .unwrap();
+
+    cli.process_slice::<RawCommand<'_>, _>(b"say  \"a;b\";get 1 ;  set \r", &mut processor)
+        .unwrap();
+    drop(processor);
+
+    assert_eq!(
+        lines,
+        vec![
+            Some("say  \"a;b\"".to_string()),
+            Some("get 1 ".to_string()),
+            Some("  set ".to_string())
+        ]
+    );
+}
+
+#[test]
+fn output_between_commands() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .command_separator(true)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|cli, command| {
+        cli.writer().write_str(command.name())?;
+        Ok(())
+    });
+    cli.process_slice::<RawCommand<'_>, _>(b"first; second\r", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ first; second", "first", "second", "$"]
+    );
+}
+
+#[test]
+fn paged_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .command_separator(true)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|cli, _| {
+        cli.pager(2, 3, |pager, len, start| {
+            for i in start..len {
+                pager.writeln_str(&format!("line {}", i))?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    });
+    cli.process_slice::<RawCommand<'_>, _>(b"first; list; last\r", &mut processor)
+        .unwrap();
+    assert_terminal!(
+        output.terminal(),
+        8,
+        vec!["$ first; list; last", "line 0", "line 1", "--More--"]
+    );
+
+    // commands after paged one are not processed
+    cli.process_slice::<RawCommand<'_>, _>(b"x", &mut processor)
+        .unwrap();
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ first; list; last", "line 0", "line 1", "line 2", "$",]
+    );
+}
@@ -0,0 +1,87 @@
+use embedded_cli::{cli::CliBuilder, Command};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Get,
+}
+
+#[test]
+fn banner_on_build() {
+    let output = Output::default();
+    let cli = CliBuilder::default()
+        .banner("Welcome\nType 'help' for help")
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    assert!(cli.is_connected());
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["Welcome", "Type 'help' for help", "$"]
+    );
+}
+
+#[test]
+fn output_discarded_while_disconnected() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .banner("Welcome\n")
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, _| {
+        cli.writer().write_str("value")?;
+        Ok(())
+    });
+
+    cli.on_disconnect();
+    assert!(!cli.is_connected());
+    for b in b"get\nge" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.write(|writer| writer.write_str("log line")).unwrap();
+    assert_terminal!(output.terminal(), 2, vec!["Welcome", "$"]);
+
+    cli.on_connect().unwrap();
+    assert!(cli.is_connected());
+    assert_terminal!(output.terminal(), 4, vec!["Welcome", "Welcome", "$ ge"]);
+
+    // second notification doesn't write anything
+    cli.on_connect().unwrap();
+    assert_terminal!(output.terminal(), 4, vec!["Welcome", "Welcome", "$ ge"]);
+
+    for b in b"t\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["Welcome", "Welcome", "$ get", "value", "$"]
+    );
+}
+
+#[test]
+fn reconnect_without_banner() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"ge" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    cli.on_disconnect();
+    cli.on_connect().unwrap();
+    assert_terminal!(output.terminal(), 4, vec!["$ ge"]);
+}
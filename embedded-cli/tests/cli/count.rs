@@ -0,0 +1,80 @@
+use embedded_cli::{command::RawCommand, service::FromRaw, Command};
+use rstest::rstest;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    /// Run test
+    Run {
+        /// Increase verbosity
+        #[arg(short, long, action = "count")]
+        verbose: u8,
+
+        /// Hide output
+        #[arg(short)]
+        quiet: bool,
+    },
+}
+
+#[rstest]
+#[case("run", 0, false)]
+#[case("run -v", 1, false)]
+#[case("run -vvv", 3, false)]
+#[case("run -vqv --verbose", 3, true)]
+fn flag_counted(#[case] input: &str, #[case] verbose: u8, #[case] quiet: bool) {
+    let mut input = input.to_string();
+    let command = CliCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap();
+
+    assert_eq!(command, CliCommand::Run { verbose, quiet });
+}
+
+#[test]
+fn count_saturated() {
+    let mut input = format!("run -{}", "v".repeat(300));
+    let command = CliCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap();
+
+    assert_eq!(
+        command,
+        CliCommand::Run {
+            verbose: 255,
+            quiet: false
+        }
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_shows_repeated_flag() {
+    use embedded_cli::cli::CliBuilder;
+
+    use crate::output::Output;
+    use crate::terminal::assert_terminal;
+
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"run --help\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ run --help",
+            "Run test",
+            "",
+            "Usage: run [OPTIONS]",
+            "",
+            "Options:",
+            "  -v, --verbose...  Increase verbosity",
+            "  -q                Hide output",
+            "  -h, --help        Print help",
+            "$",
+        ]
+    );
+}
@@ -1,6 +1,8 @@
-use embedded_cli::Command;
+use embedded_cli::{cli::CliBuilder, Command};
 use rstest::rstest;
 
+use crate::output::Output;
+
 use crate::impl_convert;
 use crate::wrapper::CliWrapper;
 
@@ -34,9 +36,7 @@ enum TestCommand {
 }
 
 impl_convert! {CliTestCommand<'_> => TestCommand, command, {
-    match command {
-        cmd => cmd.into(),
-    }
+    command.into()
 }}
 
 impl<'a> From<CliTestCommand<'a>> for TestCommand {
@@ -85,3 +85,56 @@ fn options_parsing(#[case] command: &str, #[case] expected: TestCommand) {
 
     assert_eq!(cli.received_commands(), vec![Ok(expected)]);
 }
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliDevice {
+    Led {
+        #[arg(short, long)]
+        index: Option<u8>,
+
+        #[command(subcommand, default_subcommand = "status")]
+        command: CliState,
+    },
+
+    #[command(subcommand, default_subcommand = "status")]
+    Fan(CliState),
+
+    Pump {
+        #[command(subcommand, default_subcommand = "off")]
+        command: Option<CliState>,
+    },
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliState {
+    Off,
+    On,
+    Status,
+}
+
+#[rstest]
+#[case("led", CliDevice::Led { index: None, command: CliState::Status })]
+#[case("led -i 2", CliDevice::Led { index: Some(2), command: CliState::Status })]
+#[case("led on", CliDevice::Led { index: None, command: CliState::On })]
+#[case("fan", CliDevice::Fan(CliState::Status))]
+#[case("fan off", CliDevice::Fan(CliState::Off))]
+#[case("pump", CliDevice::Pump { command: Some(CliState::Off) })]
+#[case("pump on", CliDevice::Pump { command: Some(CliState::On) })]
+fn default_subcommand(#[case] command: &str, #[case] expected: CliDevice) {
+    let mut cli = CliBuilder::default()
+        .writer(Output::default())
+        .build()
+        .unwrap();
+
+    let mut commands = vec![];
+    let mut processor = CliDevice::processor(|_, command| {
+        commands.push(command);
+        Ok(())
+    });
+    for &b in command.as_bytes().iter().chain(b"\n") {
+        cli.process_byte::<CliDevice, _>(b, &mut processor).unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(commands, vec![expected]);
+}
@@ -0,0 +1,79 @@
+use embedded_cli::{cli::CliBuilder, prompt::Prompt, Command};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+const OFFLINE: Prompt = Prompt::new("offline$ ");
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Connect,
+    Disconnect,
+}
+
+#[test]
+fn switch_prompt() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .degraded_prompt(OFFLINE)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"conn", &mut processor)
+        .unwrap();
+    assert!(!cli.is_degraded());
+    assert_terminal!(output.terminal(), 6, vec!["$ conn"]);
+
+    cli.set_degraded(true).unwrap();
+    assert!(cli.is_degraded());
+    assert_eq!(cli.prompt(), "offline$ ");
+    assert_eq!(cli.prompt_width(), 9);
+    assert_terminal!(output.terminal(), 13, vec!["offline$ conn"]);
+
+    // normal prompt is changed, but degraded one is still shown
+    cli.set_prompt("# ").unwrap();
+    assert_terminal!(output.terminal(), 13, vec!["offline$ conn"]);
+
+    cli.set_degraded(false).unwrap();
+    assert_eq!(cli.prompt(), "# ");
+    assert_terminal!(output.terminal(), 6, vec!["# conn"]);
+}
+
+#[test]
+fn switch_from_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .degraded_prompt(OFFLINE)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        cli.set_degraded(command == CliCommand::Disconnect);
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(b"disconnect\nconnect\n", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ disconnect", "offline$ connect", "$"]
+    );
+}
+
+#[test]
+fn without_degraded_prompt() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    cli.set_degraded(true).unwrap();
+
+    assert!(cli.is_degraded());
+    assert_terminal!(output.terminal(), 2, vec!["$"]);
+}
@@ -0,0 +1,82 @@
+use embedded_cli::{
+    cli::{CliBuilder, EchoMode},
+    Command,
+};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand<'a> {
+    Key { value: &'a str },
+    Secret,
+}
+
+#[test]
+fn masked_from_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut values = vec![];
+    {
+        let mut processor = CliCommand::processor(|cli, command| {
+            match command {
+                CliCommand::Key { value } => {
+                    values.push(value.to_string());
+                    cli.set_echo(EchoMode::Normal);
+                }
+                CliCommand::Secret => cli.set_echo(EchoMode::Masked('*')),
+            }
+            Ok(())
+        });
+        for b in b"secret\nkey abx\x08c\nkey d\n" {
+            cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+                .unwrap();
+        }
+    }
+    assert_eq!(values, vec!["abc", "d"]);
+    assert_eq!(cli.echo(), EchoMode::Normal);
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec!["$ secret", "$ *******", "$ key d", "$"]
+    );
+
+    // secret input is not stored in history
+    #[cfg(feature = "history")]
+    {
+        let mut processor = CliCommand::processor(|_, _| Ok(()));
+        for b in b"\x1B[A\x1B[A" {
+            cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+                .unwrap();
+        }
+        assert_eq!(cli.current_input(), "secret");
+    }
+}
+
+#[test]
+fn hidden_from_outside() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in b"key ab" {
+        cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.set_echo(EchoMode::Hidden).unwrap();
+    assert_terminal!(output.terminal(), 2, vec!["$"]);
+
+    for b in b"c\x03" {
+        cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.set_echo(EchoMode::Normal).unwrap();
+    assert_terminal!(output.terminal(), 2, vec!["$ ^C", "$"]);
+}
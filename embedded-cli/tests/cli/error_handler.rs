@@ -0,0 +1,50 @@
+use core::fmt::Write;
+
+use embedded_cli::{cli::CliBuilder, service::ParseError, Command};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set { value: u8 },
+}
+
+fn localized(writer: &mut dyn Write, error: ParseError<'_>) -> core::fmt::Result {
+    match error {
+        ParseError::ParseValueError { value, .. } => {
+            write!(writer, "ошибка: неверное значение '{}'", value)
+        }
+        // unknown commands are silently ignored
+        ParseError::UnknownCommand => Ok(()),
+        _ => writer.write_str("ошибка"),
+    }
+}
+
+#[test]
+fn custom_errors() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .error_handler(localized)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"set abc\nget\nset\nset 1\n", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ set abc",
+            "ошибка: неверное значение 'abc'",
+            "$ get",
+            "$ set",
+            "ошибка",
+            "$ set 1",
+            "$"
+        ]
+    );
+}
@@ -0,0 +1,164 @@
+use std::cell::RefCell;
+
+use embedded_cli::{
+    cli::{CliBuilder, CliEvent},
+    service::{CommandProcessor, ParseError},
+    Command,
+};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::wrapper::CliWrapper;
+
+use crate::terminal::assert_terminal;
+
+#[rstest]
+#[case("\x1BOP", 1)]
+#[case("\x1B[11~", 1)]
+#[case("\x1BOS", 4)]
+#[case("\x1B[15~", 5)]
+#[case("\x1B[17~", 6)]
+#[case("\x1B[24~", 12)]
+fn function_keys(#[case] input: &str, #[case] key: u8) {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set");
+    cli.process_str(input);
+
+    assert_eq!(cli.received_events(), vec![CliEvent::FunctionKey(key)]);
+    assert_terminal!(cli.terminal(), 5, vec!["$ set"]);
+    assert!(cli.received_commands().is_empty());
+}
+
+#[test]
+fn write_from_event() {
+    let mut cli = CliWrapper::default();
+
+    cli.set_event_handler(|cli, event| {
+        if event == CliEvent::FunctionKey(5) {
+            cli.writer().write_str("refreshed")?;
+        }
+        Ok(())
+    });
+
+    cli.process_str("set");
+    cli.process_str("\x1B[15~");
+
+    assert_terminal!(cli.terminal(), 5, vec!["refreshed", "$ set"]);
+
+    cli.process_str("\x1B[17~");
+
+    assert_terminal!(cli.terminal(), 5, vec!["refreshed", "$ set"]);
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set { value: u8 },
+}
+
+#[test]
+fn parse_error_events() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .parse_error_events(true)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let errors = RefCell::new(vec![]);
+    let mut processor = CliCommand::processor(|_, _| Ok(())).with_events(|cli, event| {
+        if let CliEvent::ParseError(error) = event {
+            errors.borrow_mut().push(format!("{:?}", error));
+            if error == ParseError::UnknownCommand {
+                cli.writer().write_str("E01")?;
+            }
+        }
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(
+        b"set x
+get
+",
+        &mut processor,
+    )
+    .unwrap();
+
+    assert_eq!(
+        errors.into_inner(),
+        vec![
+            r#"ParseValueError { name: Some("<VALUE>"), value: "x", expected: "u8" }"#,
+            "UnknownCommand",
+        ]
+    );
+    assert_terminal!(output.terminal(), 2, vec!["$ set x", "$ get", "E01", "$"]);
+}
+
+#[test]
+fn line_received() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let lines = RefCell::new(vec![]);
+    let mut processor = CliCommand::processor(|_, _| Ok(())).with_events(|_, event| {
+        if let CliEvent::LineReceived(line) = event {
+            lines.borrow_mut().push(line.to_string());
+        }
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(b"set  5\n\nget \"a b\"\n", &mut processor)
+        .unwrap();
+
+    assert_eq!(lines.into_inner(), vec!["set  5", "", "get \"a b\""]);
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ set  5",
+            "$",
+            "$ get \"a b\"",
+            "error: unknown command",
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn invalid_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let invalid = RefCell::new(vec![]);
+    let mut processor = CliCommand::processor(|_, _| Ok(())).with_events(|cli, event| {
+        if let CliEvent::InvalidCommand(command) = event {
+            invalid.borrow_mut().push(command.name().to_string());
+            if command.name() == "get" {
+                // custom fallback
+                cli.writer().write_str("value: 5")?;
+                cli.skip_error();
+            }
+        }
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(b"set x\nget\nset 1\n", &mut processor)
+        .unwrap();
+
+    assert_eq!(invalid.into_inner(), vec!["set", "get"]);
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ set x",
+            "error: failed to parse '<VALUE> x', expected u8",
+            "$ get",
+            "value: 5",
+            "$ set 1",
+            "$"
+        ]
+    );
+}
@@ -0,0 +1,89 @@
+use embedded_cli::interrupt::InterruptQueue;
+use rstest::rstest;
+use ufmt::uwrite;
+
+use crate::wrapper::{CliWrapper, RawCommand};
+
+use crate::terminal::assert_terminal;
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn pause_output(#[case] enabled: bool) {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().flow_control(enabled).build();
+
+    assert!(!cli.is_output_paused());
+
+    cli.process_str("ab\x13");
+    assert_eq!(cli.is_output_paused(), enabled);
+
+    cli.process_str("\x13c");
+    assert_eq!(cli.is_output_paused(), enabled);
+    if enabled {
+        // echo is discarded while output is paused
+        assert_terminal!(cli.terminal(), 4, vec!["$ ab"]);
+    } else {
+        assert_terminal!(cli.terminal(), 5, vec!["$ abc"]);
+    }
+
+    cli.process_str("\x11");
+    assert!(!cli.is_output_paused());
+
+    // flow control bytes are never inserted to input
+    assert_terminal!(cli.terminal(), 5, vec!["$ abc"]);
+}
+
+#[test]
+fn line_written_again_on_resume() {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().flow_control(true).build();
+
+    cli.process_str("set led");
+    cli.process_str("\x13");
+    cli.send_left();
+    cli.send_left();
+    cli.send_backspace();
+    cli.process_str("1");
+    assert_terminal!(cli.terminal(), 9, vec!["$ set led"]);
+
+    cli.process_str("\x11");
+    assert_terminal!(cli.terminal(), 7, vec!["$ set 1ed"]);
+}
+
+#[test]
+fn command_output_discarded_while_paused() {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().flow_control(true).build();
+    cli.set_handler(|cli, _| {
+        cli.writer().write_str("output")?;
+        Ok(())
+    });
+
+    cli.process_str("get\x13");
+    cli.send_enter();
+    cli.write_str("external");
+    assert_terminal!(cli.terminal(), 5, vec!["$ get"]);
+
+    // command is still processed, but line end after it was also discarded,
+    // so current line is replaced with fresh prompt
+    cli.process_str("\x11");
+    assert_eq!(cli.received_commands().len(), 1);
+    assert_terminal!(cli.terminal(), 2, vec!["$"]);
+}
+
+#[test]
+fn queued_output_kept_while_paused() {
+    let queue = InterruptQueue::<32>::new();
+    let mut writer = unsafe { queue.writer() };
+    let mut reader = unsafe { queue.reader() };
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().flow_control(true).build();
+
+    cli.process_str("\x13");
+    uwrite!(writer, "irq {}", 1).unwrap();
+    cli.write_queued(&mut reader);
+    assert!(!queue.is_empty());
+    assert_terminal!(cli.terminal(), 2, vec!["$"]);
+
+    cli.process_str("\x11");
+    cli.write_queued(&mut reader);
+    assert!(queue.is_empty());
+    assert_terminal!(cli.terminal(), 2, vec!["irq 1", "$"]);
+}
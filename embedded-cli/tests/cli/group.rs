@@ -0,0 +1,137 @@
+use embedded_cli::{
+    cli::{CliBuilder, CliHandle},
+    command::RawCommand,
+    service::{CommandProcessor, ProcessError},
+    Command, CommandGroup,
+};
+use embedded_io::Write;
+
+use crate::impl_convert;
+use crate::wrapper::{CliWrapper, ParseError};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum BaseCommand<'a> {
+    Hello { name: &'a str },
+    Stop,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum GetCommand {
+    GetLed { led: u8 },
+}
+
+// variants are used only for parsing, commands are processed by group processor
+#[allow(dead_code)]
+#[derive(CommandGroup)]
+enum Group<'a> {
+    Base(BaseCommand<'a>),
+    GetLed(GetCommand),
+    Other(RawCommand<'a>),
+}
+
+struct OtherProcessor<'v>(&'v mut Vec<String>);
+
+impl<W: Write<Error = E>, E: embedded_io::Error> CommandProcessor<W, E> for OtherProcessor<'_> {
+    fn process<'a>(
+        &mut self,
+        _: &mut CliHandle<'_, W, E>,
+        raw: RawCommand<'a>,
+    ) -> Result<(), ProcessError<'a, E>> {
+        self.0.push(raw.name().to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn route_to_group_processors() {
+    let mut cli = CliBuilder::default().build().unwrap();
+
+    let mut base = Vec::new();
+    let mut get = Vec::new();
+    let mut other = Vec::new();
+
+    let mut processor = Group::group_processor(
+        BaseCommand::processor(|_, cmd| {
+            base.push(match cmd {
+                BaseCommand::Hello { name } => format!("hello {}", name),
+                BaseCommand::Stop => "stop".to_string(),
+            });
+            Ok(())
+        }),
+        GetCommand::processor(|_, cmd| {
+            get.push(cmd);
+            Ok(())
+        }),
+        OtherProcessor(&mut other),
+    );
+
+    for b in b"hello world\nget-led 3\nunknown 1\nstop\nget-led abc\n" {
+        cli.process_byte::<Group<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(base, vec!["hello world".to_string(), "stop".to_string()]);
+    assert_eq!(get, vec![GetCommand::GetLed { led: 3 }]);
+    assert_eq!(other, vec!["unknown".to_string()]);
+}
+
+#[derive(Debug, Clone, CommandGroup)]
+enum FallbackGroup<'a> {
+    // fallback is always tried last, regardless of its position
+    #[group(fallback)]
+    Legacy(RawCommand<'a>),
+
+    Base(BaseCommand<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TestCommand {
+    Hello { name: String },
+    Stop,
+    Legacy { name: String },
+}
+
+impl_convert! {FallbackGroup<'_> => TestCommand, command, { command.into() }}
+
+impl<'a> From<FallbackGroup<'a>> for TestCommand {
+    fn from(value: FallbackGroup<'a>) -> Self {
+        match value {
+            FallbackGroup::Base(BaseCommand::Hello { name }) => Self::Hello {
+                name: name.to_string(),
+            },
+            FallbackGroup::Base(BaseCommand::Stop) => Self::Stop,
+            FallbackGroup::Legacy(raw) => Self::Legacy {
+                name: raw.name().to_string(),
+            },
+        }
+    }
+}
+
+#[test]
+fn fallback_group() {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    cli.process_str("hello world");
+    cli.send_enter();
+    cli.process_str("old-cmd 1 2");
+    cli.send_enter();
+    cli.process_str("hello");
+    cli.send_enter();
+
+    assert_eq!(
+        cli.received_commands(),
+        vec![
+            Ok(TestCommand::Hello {
+                name: "world".to_string()
+            }),
+            Ok(TestCommand::Legacy {
+                name: "old-cmd".to_string()
+            }),
+            // typed commands with invalid arguments are not passed to fallback
+            Err(ParseError::MissingRequiredArgument {
+                name: "<NAME>".to_string()
+            }),
+        ]
+    );
+}
@@ -0,0 +1,135 @@
+use std::cell::Cell;
+
+use embedded_cli::{Command, CommandGroup};
+
+use crate::impl_convert;
+use crate::wrapper::{CliWrapper, ParseError};
+
+#[cfg(any(feature = "autocomplete", feature = "help"))]
+use crate::terminal::assert_terminal;
+
+std::thread_local! {
+    static DEBUG_UNLOCKED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn debug_unlocked() -> bool {
+    DEBUG_UNLOCKED.with(|unlocked| unlocked.get())
+}
+
+fn set_debug_unlocked(unlocked: bool) {
+    DEBUG_UNLOCKED.with(|cell| cell.set(unlocked))
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliBase {
+    /// Stop everything
+    Stop,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliDebug {
+    /// Dump memory
+    Dump,
+}
+
+#[derive(Debug, Clone, CommandGroup, PartialEq)]
+enum CliGroup {
+    Base(CliBase),
+
+    #[group(enabled = "debug_unlocked")]
+    Debug(CliDebug),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TestCommand {
+    Stop,
+    Dump,
+}
+
+impl_convert! {CliGroup => TestCommand, command, { command.into() }}
+
+impl From<CliGroup> for TestCommand {
+    fn from(value: CliGroup) -> Self {
+        match value {
+            CliGroup::Base(CliBase::Stop) => Self::Stop,
+            CliGroup::Debug(CliDebug::Dump) => Self::Dump,
+        }
+    }
+}
+
+#[test]
+fn parsing() {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    set_debug_unlocked(false);
+    cli.process_str("dump");
+    cli.send_enter();
+
+    set_debug_unlocked(true);
+    cli.process_str("dump");
+    cli.send_enter();
+
+    assert_eq!(
+        cli.received_commands(),
+        vec![Err(ParseError::UnknownCommand), Ok(TestCommand::Dump)]
+    );
+}
+
+#[cfg(feature = "autocomplete")]
+#[test]
+fn autocomplete() {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    set_debug_unlocked(false);
+    cli.process_str("d");
+    cli.send_tab();
+    assert_terminal!(cli.terminal(), 3, vec!["$ d"]);
+
+    set_debug_unlocked(true);
+    cli.send_tab();
+    assert_terminal!(cli.terminal(), 7, vec!["$ dump"]);
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help() {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    set_debug_unlocked(false);
+    cli.process_str("help");
+    cli.send_enter();
+    cli.process_str("dump --help");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ help",
+            "Commands:",
+            "  stop  Stop everything",
+            "$ dump --help",
+            "error: unknown command",
+            "$",
+        ]
+    );
+
+    set_debug_unlocked(true);
+    let mut cli = CliWrapper::<TestCommand>::new();
+    cli.process_str("help");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ help",
+            "Commands:",
+            "  stop  Stop everything",
+            "",
+            "Commands:",
+            "  dump  Dump memory",
+            "$",
+        ]
+    );
+}
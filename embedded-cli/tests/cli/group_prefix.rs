@@ -0,0 +1,136 @@
+use embedded_cli::{Command, CommandGroup};
+use rstest::rstest;
+
+use crate::impl_convert;
+use crate::wrapper::{CliWrapper, ParseError};
+
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliBase {
+    /// Stop everything
+    Stop,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliNet<'a> {
+    /// Connect to host
+    Connect {
+        /// Host name
+        host: &'a str,
+    },
+
+    /// Show status
+    Status,
+}
+
+#[derive(Debug, Clone, CommandGroup, PartialEq)]
+enum CliGroup<'a> {
+    Base(CliBase),
+
+    #[group(prefix = "net")]
+    Net(CliNet<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TestCommand {
+    Stop,
+    Connect { host: String },
+    Status,
+}
+
+impl_convert! {CliGroup<'_> => TestCommand, command, { command.into() }}
+
+impl<'a> From<CliGroup<'a>> for TestCommand {
+    fn from(value: CliGroup<'a>) -> Self {
+        match value {
+            CliGroup::Base(CliBase::Stop) => Self::Stop,
+            CliGroup::Net(CliNet::Connect { host }) => Self::Connect {
+                host: host.to_string(),
+            },
+            CliGroup::Net(CliNet::Status) => Self::Status,
+        }
+    }
+}
+
+#[rstest]
+#[case("stop", Ok(TestCommand::Stop))]
+#[case("net connect host1", Ok(TestCommand::Connect { host: "host1".to_string() }))]
+#[case("net status", Ok(TestCommand::Status))]
+#[case("status", Err(ParseError::UnknownCommand))]
+#[case("net stop", Err(ParseError::UnknownCommand))]
+#[case("net", Err(ParseError::MissingSubcommand {
+    expected: vec!["connect".to_string(), "status".to_string()]
+}))]
+fn parsing(#[case] command: &str, #[case] expected: Result<TestCommand, ParseError>) {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    cli.process_str(command);
+    cli.send_enter();
+
+    assert_eq!(cli.received_commands(), vec![expected]);
+}
+
+#[cfg(feature = "autocomplete")]
+#[rstest]
+#[case("n", "$ net", 6)]
+#[case("net", "$ net", 6)]
+#[case("s", "$ stop", 7)]
+#[case("net st", "$ net status", 13)]
+#[case("net c", "$ net connect", 14)]
+#[case("net x", "$ net x", 7)]
+#[case("stop st", "$ stop st", 9)]
+fn autocomplete(#[case] input: &str, #[case] expected: &str, #[case] cursor: usize) {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    cli.process_str(input);
+    cli.send_tab();
+
+    assert_terminal!(cli.terminal(), cursor, vec![expected]);
+}
+
+#[cfg(feature = "help")]
+#[rstest]
+#[case("help", &[
+    "Commands:",
+    "  stop  Stop everything",
+    "",
+    "Commands:",
+    "  net connect  Connect to host",
+    "  net status   Show status",
+])]
+#[case("help net", &[
+    "Commands:",
+    "  net connect  Connect to host",
+    "  net status   Show status",
+])]
+#[case("net --help", &[
+    "Commands:",
+    "  net connect  Connect to host",
+    "  net status   Show status",
+])]
+#[case("net connect --help", &[
+    "Connect to host",
+    "",
+    "Usage: net connect <HOST>",
+    "",
+    "Arguments:",
+    "  <HOST>  Host name",
+    "",
+    "Options:",
+    "  -h, --help  Print help",
+])]
+fn help(#[case] command: &str, #[case] expected: &[&str]) {
+    let mut cli = CliWrapper::<TestCommand>::new();
+    let all_lines = [format!("$ {}", command)]
+        .into_iter()
+        .chain(expected.iter().map(|s| s.to_string()))
+        .chain(Some("$".to_string()))
+        .collect::<Vec<_>>();
+
+    cli.process_str(command);
+    cli.send_enter();
+
+    assert_terminal!(cli.terminal(), 2, all_lines);
+    assert!(cli.received_commands().is_empty());
+}
@@ -0,0 +1,87 @@
+use embedded_cli::{cli::CliBuilder, cli::CliHandle, Command};
+use embedded_io::{Error, Write};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+#[command(derive_handler)]
+enum CliCommand<'a> {
+    Led {
+        id: u8,
+
+        #[arg(long)]
+        name: Option<&'a str>,
+    },
+
+    #[command(subcommand)]
+    Adc(AdcCommand),
+
+    Reset,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum AdcCommand {
+    Read { channel: u8 },
+}
+
+#[derive(Debug, Default)]
+struct App {
+    calls: Vec<String>,
+}
+
+impl<W: Write<Error = E>, E: Error> CliCommandHandler<W, E> for App {
+    fn led(&mut self, cli: &mut CliHandle<'_, W, E>, id: u8, name: Option<&str>) -> Result<(), E> {
+        self.calls.push(format!("led {} {:?}", id, name));
+        cli.writer().write_str("led updated")
+    }
+
+    fn adc(&mut self, _: &mut CliHandle<'_, W, E>, command: AdcCommand) -> Result<(), E> {
+        self.calls.push(format!("adc {:?}", command));
+        Ok(())
+    }
+
+    fn reset(&mut self, _: &mut CliHandle<'_, W, E>) -> Result<(), E> {
+        self.calls.push("reset".to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn commands_dispatched_to_handler() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+    let mut app = App::default();
+
+    let mut processor =
+        CliCommand::stateful_processor(|cli, app: &mut App, command| command.dispatch(cli, app));
+
+    for b in b"led 3 --name red\nadc read 2\nreset\n" {
+        cli.process_byte_with_state::<CliCommand<'_>, _, _>(*b, &mut app, &mut processor)
+            .unwrap();
+    }
+
+    assert_eq!(
+        app.calls,
+        vec![
+            "led 3 Some(\"red\")".to_string(),
+            "adc Read { channel: 2 }".to_string(),
+            "reset".to_string(),
+        ]
+    );
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ led 3 --name red",
+            "led updated",
+            "$ adc read 2",
+            "$ reset",
+            "$"
+        ]
+    );
+}
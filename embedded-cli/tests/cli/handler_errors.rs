@@ -0,0 +1,73 @@
+use embedded_cli::{cli::CliBuilder, service::CommandError, Command};
+use ufmt::{uDisplay, uWrite, Formatter};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Read { address: u8 },
+}
+
+enum DeviceError {
+    Busy,
+    OutOfRange(u8),
+}
+
+impl uDisplay for DeviceError {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        match self {
+            DeviceError::Busy => f.write_str("device is busy"),
+            DeviceError::OutOfRange(address) => {
+                f.write_str("address out of range: ")?;
+                address.fmt(f)
+            }
+        }
+    }
+}
+
+#[test]
+fn user_errors() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::fallible_processor(|cli, command| match command {
+        CliCommand::Read { address: 0 } => Err(CommandError::User(DeviceError::Busy)),
+        CliCommand::Read { address } if address > 10 => {
+            cli.writer().write_str("reading")?;
+            Err(CommandError::User(DeviceError::OutOfRange(address)))
+        }
+        CliCommand::Read { .. } => {
+            cli.writer().write_str("ok")?;
+            Ok(())
+        }
+    });
+
+    for b in b"read 0\nread 20\nread 5\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    let terminal = output.terminal();
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ read 0",
+            "error: device is busy",
+            "$ read 20",
+            "reading",
+            "error: address out of range: 20",
+            "$ read 5",
+            "ok",
+            "$",
+        ]
+    );
+}
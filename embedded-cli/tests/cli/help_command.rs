@@ -0,0 +1,90 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use rstest::rstest;
+use ufmt::uwrite;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand<'a> {
+    /// Show firmware manual
+    Help {
+        /// Manual topic
+        topic: Option<&'a str>,
+    },
+}
+
+fn run(help_command: bool, input: &str) -> Terminal {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .help_command(help_command)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        match command {
+            CliCommand::Help { topic } => {
+                uwrite!(cli.writer(), "manual: {}", topic.unwrap_or("all"))?
+            }
+        }
+        Ok(())
+    });
+    for b in input.as_bytes() {
+        cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    output.terminal()
+}
+
+#[rstest]
+#[case("help", "manual: all")]
+#[case("help leds", "manual: leds")]
+fn user_help_command(#[case] command: &str, #[case] output: &str) {
+    let terminal = run(false, &format!("{}\n", command));
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            format!("$ {}", command),
+            output.to_string(),
+            "$".to_string()
+        ]
+    );
+}
+
+#[test]
+fn builtin_help_command() {
+    let terminal = run(true, "help leds\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec!["$ help leds", "error: unknown command", "$",]
+    );
+}
+
+#[test]
+fn help_option_kept() {
+    let terminal = run(false, "help --help\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ help --help",
+            "Show firmware manual",
+            "",
+            "Usage: help [TOPIC]",
+            "",
+            "Arguments:",
+            "  [TOPIC]  Manual topic",
+            "",
+            "Options:",
+            "  -h, --help  Print help",
+            "$",
+        ]
+    );
+}
@@ -0,0 +1,85 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    /// Set value
+    Set {
+        /// Value to set
+        value: u8,
+    },
+}
+
+fn run(help_on_error: bool, input: &str) -> Terminal {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .help_on_error(help_on_error)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    for b in input.as_bytes() {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    output.terminal()
+}
+
+#[rstest]
+#[case("set", "error: missing required argument: <VALUE>")]
+#[case("set 1 2", "error: unexpected argument: 2")]
+#[case("set 1 --opt", "error: unexpected option: --opt")]
+#[case("set 1 -o", "error: unexpected option: -o")]
+fn help_printed(#[case] command: &str, #[case] error: &str) {
+    let terminal = run(true, &format!("{}\n", command));
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            format!("$ {}", command),
+            error.to_string(),
+            "Set value".to_string(),
+            "".to_string(),
+            "Usage: set <VALUE>".to_string(),
+            "".to_string(),
+            "Arguments:".to_string(),
+            "  <VALUE>  Value to set".to_string(),
+            "".to_string(),
+            "Options:".to_string(),
+            "  -h, --help  Print help".to_string(),
+            "$".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn help_not_printed_for_value_errors() {
+    let terminal = run(true, "set abc\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ set abc",
+            "error: failed to parse '<VALUE> abc', expected u8",
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn help_not_printed_when_disabled() {
+    let terminal = run(false, "set\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec!["$ set", "error: missing required argument: <VALUE>", "$"]
+    );
+}
@@ -0,0 +1,69 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use ufmt::uwrite;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand<'a> {
+    /// Connect to server
+    Connect {
+        /// Server address
+        #[arg(short, long)]
+        host: &'a str,
+    },
+}
+
+fn run(input: &str) -> Terminal {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .help_short(None)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        match command {
+            CliCommand::Connect { host } => uwrite!(cli.writer(), "connecting to {}", host)?,
+        }
+        Ok(())
+    });
+    for b in input.as_bytes() {
+        cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    output.terminal()
+}
+
+#[test]
+fn short_option_not_intercepted() {
+    let terminal = run("connect -h local\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec!["$ connect -h local", "connecting to local", "$"]
+    );
+}
+
+#[test]
+fn help_without_short_option() {
+    let terminal = run("connect --help\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ connect --help",
+            "Connect to server",
+            "",
+            "Usage: connect [OPTIONS]",
+            "",
+            "Options:",
+            "  -h, --host <HOST>  Server address",
+            "  --help             Print help",
+            "$",
+        ]
+    );
+}
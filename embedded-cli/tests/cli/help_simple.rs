@@ -1,4 +1,4 @@
-use embedded_cli::Command;
+use embedded_cli::{cli::CliEvent, help::HelpRequest, Command};
 use rstest::rstest;
 
 use crate::impl_convert;
@@ -28,6 +28,25 @@ enum CliBase<'a> {
         level: u8,
     },
 
+    /// Connect to server
+    Connect {
+        /// Server address
+        #[arg(short, long, help_heading = "Connection")]
+        address: &'a str,
+
+        /// Print more info
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Server port
+        #[arg(short, long, help_heading = "Connection")]
+        port: Option<u16>,
+
+        /// Login name
+        #[arg(short, long, help_heading = "Authentication")]
+        user: Option<&'a str>,
+    },
+
     /// Test command
     Test {
         /// Some task job
@@ -52,6 +71,12 @@ enum Base {
     Base2 {
         level: u8,
     },
+    Connect {
+        address: String,
+        verbose: bool,
+        port: Option<u16>,
+        user: Option<String>,
+    },
     Test {
         task: String,
 
@@ -71,6 +96,17 @@ impl<'a> From<CliBase<'a>> for Base {
                 verbose,
             },
             CliBase::Base2 { level } => Self::Base2 { level },
+            CliBase::Connect {
+                address,
+                verbose,
+                port,
+                user,
+            } => Self::Connect {
+                address: address.to_string(),
+                verbose,
+                port,
+                user: user.map(|u| u.to_string()),
+            },
             CliBase::Test { task, file1, file2 } => Self::Test {
                 task: task.to_string(),
                 file1: file1.to_string(),
@@ -110,6 +146,22 @@ impl<'a> From<CliBase<'a>> for Base {
     "  -l, --level <lvl>  Some level",
     "  -h, --help         Print help",
 ])]
+#[case("connect --help", &[
+    "Connect to server",
+    "",
+    "Usage: connect [OPTIONS]",
+    "",
+    "Options:",
+    "  -v, --verbose            Print more info",
+    "  -h, --help               Print help",
+    "",
+    "Connection:",
+    "  -a, --address <ADDRESS>  Server address",
+    "  -p, --port [PORT]        Server port",
+    "",
+    "Authentication:",
+    "  -u, --user [USER]        Login name",
+])]
 #[case("test --help", &[
     "Test command",
     "",
@@ -139,3 +191,61 @@ fn help(#[case] command: &str, #[case] expected: &[&str]) {
 
     assert!(cli.received_commands().is_empty());
 }
+
+#[test]
+fn help_event() {
+    let mut cli = CliWrapper::<Base>::new();
+
+    cli.set_event_handler(|cli, event| {
+        match event {
+            CliEvent::Help(HelpRequest::All) => {
+                cli.writer().write_str("Custom commands:")?;
+            }
+            CliEvent::Help(HelpRequest::Command(command)) if command.name() == "test" => {
+                cli.writer().write_str("Test is not available")?;
+                cli.skip_help();
+            }
+            _ => {}
+        }
+        Ok(())
+    });
+
+    cli.process_str("help");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ help",
+            "Custom commands:",
+            "Commands:",
+            "  base1    Base command",
+            "  base2    Another base command",
+            "  connect  Connect to server",
+            "  test     Test command",
+            "$",
+        ]
+    );
+
+    cli.process_str("test -h");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ help",
+            "Custom commands:",
+            "Commands:",
+            "  base1    Base command",
+            "  base2    Another base command",
+            "  connect  Connect to server",
+            "  test     Test command",
+            "$ test -h",
+            "Test is not available",
+            "$",
+        ]
+    );
+    assert!(cli.received_commands().is_empty());
+}
@@ -1,7 +1,8 @@
-use embedded_cli::Command;
+use embedded_cli::{help::render, service::HelpError, writer::Writer, Command};
 use rstest::rstest;
 
 use crate::impl_convert;
+use crate::output::Output;
 use crate::wrapper::CliWrapper;
 
 use crate::terminal::assert_terminal;
@@ -384,3 +385,30 @@ fn help(#[case] command: &str, #[case] expected: &[&str]) {
 
     assert!(cli.received_commands().is_empty());
 }
+
+#[rstest]
+#[case("base1 get", "Usage: base1 get [OPTIONS] <COMMAND>")]
+#[case("base2 write", "Usage: base2 write <LINE>")]
+#[case("base2", "Usage: base2 <COMMAND>")]
+fn render_help(#[case] path: &str, #[case] usage: &str) {
+    let mut output = Output::default();
+    let mut writer = Writer::new(&mut output);
+
+    render::<CliBase<'_>, _, _>(path, &mut writer).unwrap();
+
+    let (lines, _) = output.terminal().view();
+    assert!(lines.contains(&usage.to_string()), "{:?}", lines);
+}
+
+#[rstest]
+#[case("base3")]
+#[case("base1 get unknown")]
+fn render_help_unknown(#[case] path: &str) {
+    let mut output = Output::default();
+    let mut writer = Writer::new(&mut output);
+
+    assert!(matches!(
+        render::<CliBase<'_>, _, _>(path, &mut writer),
+        Err(HelpError::UnknownCommand)
+    ));
+}
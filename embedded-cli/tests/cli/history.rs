@@ -1,4 +1,13 @@
-use crate::wrapper::{CliWrapper, RawCommand};
+use embedded_cli::{
+    cli::{CliBuilder, HistoryInitError, HistoryUsage},
+    codes,
+    command::RawCommand as EmbeddedRawCommand,
+    Command,
+};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::wrapper::{Arg, CliWrapper, RawCommand};
 
 use crate::terminal::assert_terminal;
 
@@ -124,3 +133,354 @@ fn modify_when_in_history() {
         vec!["$ abc", "$ test1", "$ def", "$ test1"]
     );
 }
+
+#[test]
+fn reverse_search() {
+    let mut cli = CliWrapper::default();
+
+    for command in ["abc 1", "def", "abc 2"] {
+        cli.process_str(command);
+        cli.send_enter();
+    }
+
+    cli.process_str("x\x12");
+    assert_terminal!(
+        cli.terminal(),
+        22,
+        vec!["$ abc 1", "$ def", "$ abc 2", "(reverse-i-search)`':"]
+    );
+
+    cli.process_str("ab");
+    assert_terminal!(
+        cli.terminal(),
+        29,
+        vec![
+            "$ abc 1",
+            "$ def",
+            "$ abc 2",
+            "(reverse-i-search)`ab': abc 2"
+        ]
+    );
+
+    cli.process_str("\x12");
+    assert_terminal!(
+        cli.terminal(),
+        29,
+        vec![
+            "$ abc 1",
+            "$ def",
+            "$ abc 2",
+            "(reverse-i-search)`ab': abc 1"
+        ]
+    );
+
+    // no older match, so current one is kept
+    cli.process_str("\x12");
+    assert_terminal!(
+        cli.terminal(),
+        29,
+        vec![
+            "$ abc 1",
+            "$ def",
+            "$ abc 2",
+            "(reverse-i-search)`ab': abc 1"
+        ]
+    );
+
+    cli.send_enter();
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec!["$ abc 1", "$ def", "$ abc 2", "$ abc 1", "$"]
+    );
+    assert_eq!(
+        cli.received_commands().last().unwrap(),
+        &Ok(RawCommand {
+            name: "abc".to_string(),
+            args: vec![Arg::Value("1".to_string())],
+        })
+    );
+}
+
+#[test]
+fn reverse_search_cancelled() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("abc");
+    cli.send_enter();
+
+    cli.process_str("x\x12b");
+    cli.send_backspace();
+    assert_terminal!(cli.terminal(), 22, vec!["$ abc", "(reverse-i-search)`':"]);
+
+    cli.send_ctrl_c();
+    assert_terminal!(cli.terminal(), 3, vec!["$ abc", "$ x"]);
+    assert_eq!(cli.current_input(), "x");
+}
+
+#[test]
+fn reverse_search_accepted_with_control() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("abc");
+    cli.send_enter();
+
+    cli.process_str("\x12bc");
+    cli.send_left();
+    cli.process_str("d");
+    assert_terminal!(cli.terminal(), 5, vec!["$ abc", "$ abdc"]);
+    assert_eq!(cli.current_input(), "abdc");
+}
+
+#[test]
+fn recall_redraws_line_in_place() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = EmbeddedRawCommand::processor(|_, _| Ok(()));
+    for &b in b"abc\nlonger\n" {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+
+    let written = output.bytes().len();
+    for &b in b"\x1B[A" {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+    let mut expected = b"\r$ longer".to_vec();
+    expected.extend_from_slice(codes::CLEAR_LINE_END);
+    assert_eq!(&output.bytes()[written..], expected.as_slice());
+
+    for &b in b"\x1B[A" {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+    assert_terminal!(output.terminal(), 5, vec!["$ abc", "$ longer", "$ abc"]);
+}
+
+#[test]
+fn down_keeps_input_when_not_navigating() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("abc");
+    cli.send_enter();
+    cli.process_str("de");
+
+    cli.send_down();
+    assert_terminal!(cli.terminal(), 4, vec!["$ abc", "$ de"]);
+
+    cli.send_up();
+    cli.send_down();
+    assert_terminal!(cli.terminal(), 4, vec!["$ abc", "$ de"]);
+}
+
+#[test]
+fn edited_line_restored_after_navigation() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("abc");
+    cli.send_enter();
+    cli.process_str("test1");
+    cli.send_enter();
+    cli.process_str("unfinished");
+
+    cli.send_up();
+    cli.send_up();
+    assert_terminal!(cli.terminal(), 5, vec!["$ abc", "$ test1", "$ abc"]);
+
+    cli.send_down();
+    cli.send_down();
+    assert_terminal!(cli.terminal(), 12, vec!["$ abc", "$ test1", "$ unfinished"]);
+
+    // input is not stashed again once navigation left history
+    cli.send_down();
+    assert_terminal!(cli.terminal(), 12, vec!["$ abc", "$ test1", "$ unfinished"]);
+
+    cli.process_str("!");
+    cli.send_enter();
+    assert_eq!(
+        cli.received_commands().last().unwrap(),
+        &Ok(RawCommand {
+            name: "unfinished!".to_string(),
+            args: vec![],
+        })
+    );
+}
+
+#[test]
+fn disabled_history() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .history_disabled()
+        .build()
+        .unwrap();
+
+    let mut processor = EmbeddedRawCommand::processor(|_, _| Ok(()));
+    for &b in b"abc\nde\x1B[A\x1B[B" {
+        cli.process_byte::<EmbeddedRawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+
+    assert_terminal!(output.terminal(), 4, vec!["$ abc", "$ de"]);
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set { value: u8 },
+    Status,
+}
+
+#[rstest]
+#[case(false, "$ get")]
+#[case(true, "$ set 2")]
+fn invalid_commands_in_history(#[case] valid_only: bool, #[case] recalled: &str) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .history_valid_only(valid_only)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"set 2\nset abc\nget\n\x1B[A", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        recalled.len(),
+        vec![
+            "$ set 2",
+            "$ set abc",
+            "error: failed to parse '<VALUE> abc', expected u8",
+            "$ get",
+            "error: unknown command",
+            recalled,
+        ]
+    );
+}
+
+#[test]
+fn usage_reported() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .history_buffer([0; 32])
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        cli.history_usage(),
+        HistoryUsage {
+            entries: 0,
+            free: 32,
+            used: 0
+        }
+    );
+
+    let mut reported = None;
+    let mut processor = CliCommand::processor(|cli, command| {
+        if command == CliCommand::Status {
+            reported = Some(cli.history_usage());
+        }
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(b"set 1\nstatus\n", &mut processor)
+        .unwrap();
+    drop(processor);
+
+    // command is pushed to history before it is processed
+    assert_eq!(
+        reported,
+        Some(HistoryUsage {
+            entries: 2,
+            free: 19,
+            used: 13
+        })
+    );
+    assert_eq!(cli.history_usage(), reported.unwrap());
+}
+
+#[rstest]
+#[case(b"set 1\0status\0", Ok(()), "$ status")]
+#[case(b"set 1\0status", Err(HistoryInitError::Invalid), "$ ")]
+#[case(b"set 1\0\0", Err(HistoryInitError::Invalid), "$ ")]
+#[case(
+    b"set 1\0status\0status 1\0status 2\0status 3\0",
+    Err(HistoryInitError::TooLong),
+    "$ "
+)]
+fn restored_from_bytes(
+    #[case] bytes: &[u8],
+    #[case] result: Result<(), HistoryInitError>,
+    #[case] recalled: &str,
+) {
+    let output = Output::default();
+    let mut builder = CliBuilder::default().history_buffer([0; 32]);
+    assert_eq!(builder.history_init(bytes), result);
+    let mut cli = builder.writer(output.clone()).build().unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"\x1B[A", &mut processor)
+        .unwrap();
+
+    assert_terminal!(output.terminal(), recalled.len(), vec![recalled.trim_end()]);
+}
+
+#[rstest]
+#[case(b"set 1\0status\0", 32, "$ status")]
+#[case(b"set 1\0status\0", 8, "$ ")]
+fn restored_before_buffer(#[case] bytes: &[u8], #[case] len: usize, #[case] recalled: &str) {
+    let output = Output::default();
+    let mut buffer = vec![0; len];
+    let mut builder = CliBuilder::default();
+    assert_eq!(builder.history_init(bytes), Ok(()));
+    let mut cli = builder
+        .history_buffer(buffer.as_mut_slice())
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let restored: &[u8] = if len >= bytes.len() { bytes } else { b"" };
+    assert_eq!(cli.history_bytes(), restored);
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"\x1B[A", &mut processor)
+        .unwrap();
+
+    assert_terminal!(output.terminal(), recalled.len(), vec![recalled.trim_end()]);
+}
+
+#[test]
+fn restore_too_long_for_default_buffer() {
+    let bytes = "status\0".repeat(20);
+    let mut builder = CliBuilder::default();
+    assert_eq!(
+        builder.history_init(bytes.as_bytes()),
+        Err(HistoryInitError::TooLong)
+    );
+
+    // restored into given buffer instead
+    let mut builder = builder.history_buffer([0; 256]);
+    assert_eq!(builder.history_init(bytes.as_bytes()), Ok(()));
+    let cli = builder.build().unwrap();
+    assert_eq!(cli.history_bytes(), bytes.as_bytes());
+}
+
+#[test]
+fn bytes_exported() {
+    let mut cli = CliBuilder::default()
+        .history_buffer([0; 32])
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<CliCommand, _>(b"set 1\nstatus\nset 1\n", &mut processor)
+        .unwrap();
+
+    assert_eq!(cli.history_bytes(), b"status\0set 1\0");
+}
@@ -0,0 +1,45 @@
+use embedded_cli::input::ControlInput;
+
+use crate::wrapper::{CliWrapper, RecordedInput};
+
+#[test]
+fn record_inputs() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("aб\x1B[D\x1BOP\x08\t\r\n");
+
+    assert_eq!(
+        cli.received_inputs(),
+        vec![
+            RecordedInput::Char("a".to_string()),
+            RecordedInput::Char("б".to_string()),
+            RecordedInput::Control(ControlInput::Back),
+            RecordedInput::Control(ControlInput::FunctionKey(1)),
+            RecordedInput::Control(ControlInput::Backspace),
+            RecordedInput::Control(ControlInput::Tab),
+            RecordedInput::Control(ControlInput::Enter),
+        ]
+    );
+}
+
+#[test]
+fn inputs_replayed() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("abc\x1B[D\x08d\n");
+    let inputs = cli.received_inputs();
+
+    let mut replayed = CliWrapper::default();
+    for input in inputs {
+        match input {
+            RecordedInput::Char(text) => replayed.process_str(&text),
+            RecordedInput::Control(ControlInput::Back) => replayed.send_left(),
+            RecordedInput::Control(ControlInput::Backspace) => replayed.send_backspace(),
+            RecordedInput::Control(ControlInput::Enter) => replayed.send_enter(),
+            control => panic!("Unexpected input: {:?}", control),
+        }
+    }
+
+    assert_eq!(cli.received_commands(), replayed.received_commands());
+    assert_eq!(cli.terminal().view(), replayed.terminal().view());
+}
@@ -0,0 +1,45 @@
+use embedded_cli::interrupt::InterruptQueue;
+use ufmt::uwrite;
+
+use crate::terminal::assert_terminal;
+use crate::wrapper::CliWrapper;
+
+#[test]
+fn queued_output_above_prompt() {
+    let queue = InterruptQueue::<32>::new();
+    let mut writer = unsafe { queue.writer() };
+    let mut reader = unsafe { queue.reader() };
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set");
+    cli.write_queued(&mut reader);
+    assert_terminal!(cli.terminal(), 5, vec!["$ set"]);
+
+    uwrite!(writer, "irq {}\nirq", 1).unwrap();
+    uwrite!(writer, " {}", 2).unwrap();
+    assert_terminal!(cli.terminal(), 5, vec!["$ set"]);
+
+    cli.write_queued(&mut reader);
+    assert!(queue.is_empty());
+    assert_terminal!(cli.terminal(), 5, vec!["irq 1", "irq 2", "$ set"]);
+
+    cli.process_str(" led");
+    assert_terminal!(cli.terminal(), 9, vec!["irq 1", "irq 2", "$ set led"]);
+}
+
+#[test]
+fn queued_output_wrapped() {
+    let queue = InterruptQueue::<8>::new();
+    let mut writer = unsafe { queue.writer() };
+    let mut reader = unsafe { queue.reader() };
+    let mut cli = CliWrapper::default();
+
+    uwrite!(writer, "abcde\n").unwrap();
+    cli.write_queued(&mut reader);
+    uwrite!(writer, "fghij\n").unwrap();
+    uwrite!(writer, "overflow").unwrap();
+    cli.write_queued(&mut reader);
+
+    assert!(reader.take_overflow());
+    assert_terminal!(cli.terminal(), 2, vec!["abcde", "fghij", "ov", "$"]);
+}
@@ -0,0 +1,35 @@
+use embedded_cli::{
+    cli::CliBuilder,
+    command::RawCommand,
+    input::{ControlInput, Key, KeyMap},
+};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+const KEYMAP: KeyMap = KeyMap::new(&[
+    (Key::Ctrl('?'), ControlInput::Backspace),
+    (Key::Ctrl('a'), ControlInput::Home),
+    (Key::Ctrl('e'), ControlInput::End),
+]);
+
+#[test]
+fn custom_bindings() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .keymap(KEYMAP)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"bcdx\x7F\x01a", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "abcd");
+    assert_terminal!(output.terminal(), 3, vec!["$ abcd"]);
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\x05e", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "abcde");
+    assert_terminal!(output.terminal(), 7, vec!["$ abcde"]);
+}
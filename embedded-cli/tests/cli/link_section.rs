@@ -0,0 +1,57 @@
+use embedded_cli::{cli::CliBuilder, command::RawCommand, Command};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Command, PartialEq)]
+#[command(strings_link_section = ".rodata.cli_help")]
+enum Base<'a> {
+    /// Set value
+    Set {
+        /// Name of value
+        #[arg(short, long)]
+        name: Option<&'a str>,
+
+        /// New value
+        value: u8,
+    },
+
+    Get,
+}
+
+#[test]
+fn help_from_link_section() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<Base<'_>, _>(b"help\rset -h\r", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ help",
+            "Commands:",
+            "  set  Set value",
+            "  get",
+            "$ set -h",
+            "Set value",
+            "",
+            "Usage: set [OPTIONS] <VALUE>",
+            "",
+            "Arguments:",
+            "  <VALUE>  New value",
+            "",
+            "Options:",
+            "  -n, --name [NAME]  Name of value",
+            "  -h, --help         Print help",
+            "$",
+        ]
+    );
+}
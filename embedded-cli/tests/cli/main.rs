@@ -1,11 +1,38 @@
 #![warn(rust_2018_idioms)]
 
+mod append;
+#[cfg(feature = "async")]
+mod async_cli;
+mod auth;
 #[cfg(feature = "autocomplete")]
 mod autocomplete;
 #[cfg(not(feature = "autocomplete"))]
 mod autocomplete_disabled;
 mod base;
+mod bell;
+#[cfg(feature = "builtins")]
+mod builtins;
+mod command_names;
+mod command_separator;
+mod connect;
+mod count;
 mod defaults;
+mod degraded;
+mod echo;
+mod error_handler;
+mod events;
+mod flow_control;
+mod group;
+mod group_enabled;
+mod group_prefix;
+mod handler;
+mod handler_errors;
+#[cfg(feature = "help")]
+mod help_command;
+#[cfg(feature = "help")]
+mod help_on_error;
+#[cfg(feature = "help")]
+mod help_short;
 #[cfg(feature = "help")]
 mod help_simple;
 #[cfg(feature = "help")]
@@ -14,8 +41,32 @@ mod help_subcommand;
 mod history;
 #[cfg(not(feature = "history"))]
 mod history_disabled;
+mod input_trace;
+mod interrupt;
+mod keymap;
+#[cfg(all(feature = "help", target_os = "linux"))]
+mod link_section;
+mod max_tokens;
+#[cfg(feature = "metadata")]
+mod metadata;
+mod on_parsed;
 mod options;
+mod options_first;
+mod output;
+#[cfg(feature = "owned")]
+mod owned;
+mod pager;
+mod paste;
+mod poll;
+mod reset;
+mod scroll_region;
+mod stateful;
 mod subcommand;
 mod terminal;
+mod trace;
+mod trailing;
+mod transcript;
+mod utf8;
+mod value_enum;
 mod wrapper;
 mod writer;
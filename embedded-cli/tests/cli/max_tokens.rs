@@ -0,0 +1,55 @@
+use embedded_cli::{cli::CliBuilder, Command};
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set {
+        first: u8,
+        second: Option<u8>,
+        third: Option<u8>,
+        fourth: Option<u8>,
+    },
+}
+
+#[test]
+fn too_many_tokens() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .max_tokens(4)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands = vec![];
+    let mut processor = CliCommand::processor(|_, command| {
+        commands.push(command);
+        Ok(())
+    });
+    for b in b"set 1 2 3\nset 1 2 3 4\n" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(
+        commands,
+        vec![CliCommand::Set {
+            first: 1,
+            second: Some(2),
+            third: Some(3),
+            fourth: None,
+        }]
+    );
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ set 1 2 3",
+            "$ set 1 2 3 4",
+            "error: too many tokens, at most 4 allowed",
+            "$"
+        ]
+    );
+}
@@ -0,0 +1,153 @@
+use embedded_cli::{
+    command::RawCommand,
+    metadata::{self, ArgInfo, ArgKind, CommandInfo, Metadata, ValueHint},
+    Command, CommandGroup,
+};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum Base<'a> {
+    /// Set value
+    ///
+    /// Value is stored in memory
+    Set {
+        /// Name of value
+        #[arg(short, long, value_hint = Hostname)]
+        name: Option<&'a str>,
+
+        /// Force update
+        #[arg(short)]
+        force: bool,
+
+        /// New value
+        value: u8,
+    },
+
+    #[command(subcommand)]
+    Net(NetCommand),
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum NetCommand {
+    /// Start network
+    Up,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum LedCommand {
+    /// Toggle LED
+    Toggle,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, CommandGroup, PartialEq)]
+enum Group<'a> {
+    Base(Base<'a>),
+
+    #[group(prefix = "led")]
+    Led(LedCommand),
+
+    #[group(fallback)]
+    Other(RawCommand<'a>),
+}
+
+#[test]
+fn group_metadata() {
+    assert_eq!(
+        Group::commands(),
+        vec![
+            CommandInfo {
+                name: "set",
+                about: Some("Set value"),
+                help: Some("Set value\r\n\r\nValue is stored in memory"),
+                args: vec![
+                    ArgInfo {
+                        kind: ArgKind::Option,
+                        long: Some("name"),
+                        short: Some('n'),
+                        value_name: "NAME",
+                        value_hint: ValueHint::Hostname,
+                        required: false,
+                        help: Some("Name of value"),
+                    },
+                    ArgInfo {
+                        kind: ArgKind::Flag,
+                        long: None,
+                        short: Some('f'),
+                        value_name: "FORCE",
+                        value_hint: ValueHint::Other,
+                        required: false,
+                        help: Some("Force update"),
+                    },
+                    ArgInfo {
+                        kind: ArgKind::Positional,
+                        long: None,
+                        short: None,
+                        value_name: "VALUE",
+                        value_hint: ValueHint::Other,
+                        required: true,
+                        help: Some("New value"),
+                    },
+                ],
+                subcommands: vec![],
+            },
+            CommandInfo {
+                name: "net",
+                about: None,
+                help: None,
+                args: vec![],
+                subcommands: vec![CommandInfo {
+                    name: "up",
+                    about: Some("Start network"),
+                    help: Some("Start network"),
+                    args: vec![],
+                    subcommands: vec![],
+                }],
+            },
+            CommandInfo {
+                name: "led",
+                about: None,
+                help: None,
+                args: vec![],
+                subcommands: vec![CommandInfo {
+                    name: "toggle",
+                    about: Some("Toggle LED"),
+                    help: Some("Toggle LED"),
+                    args: vec![],
+                    subcommands: vec![],
+                }],
+            },
+        ]
+    );
+}
+
+#[test]
+fn json() {
+    assert_eq!(
+        metadata::to_json::<NetCommand>(),
+        r#"[{"name":"up","about":"Start network","help":"Start network","args":[],"subcommands":[]}]"#
+    );
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum FileCommand<'a> {
+    Upload {
+        #[arg(long, value_hint = "Hostname")]
+        host: Option<&'a str>,
+
+        #[arg(value_hint = FilePath)]
+        path: &'a str,
+    },
+}
+
+#[test]
+fn value_hints() {
+    assert_eq!(
+        metadata::to_json::<FileCommand<'_>>(),
+        concat!(
+            r#"[{"name":"upload","about":null,"help":null,"args":["#,
+            r#"{"kind":"option","long":"host","short":null,"value_name":"HOST","value_hint":"hostname","required":false,"help":null},"#,
+            r#"{"kind":"positional","long":null,"short":null,"value_name":"PATH","value_hint":"file_path","required":true,"help":null}"#,
+            r#"],"subcommands":[]}]"#,
+        )
+    );
+}
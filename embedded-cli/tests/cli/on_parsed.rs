@@ -0,0 +1,75 @@
+use embedded_cli::{service::ParseError as CliParseError, Command};
+use rstest::rstest;
+
+use crate::impl_convert;
+use crate::wrapper::{CliWrapper, ParseError};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliTestCommand<'a> {
+    #[command(on_parsed = "clamp_speed")]
+    Speed {
+        value: u8,
+    },
+
+    #[command(on_parsed = "check_mode")]
+    Mode {
+        name: &'a str,
+    },
+
+    Stop,
+}
+
+fn clamp_speed(command: &mut CliTestCommand<'_>) -> Result<(), CliParseError<'static>> {
+    if let CliTestCommand::Speed { value } = command {
+        *value = (*value).min(100);
+    }
+    Ok(())
+}
+
+fn check_mode<'a>(command: &mut CliTestCommand<'a>) -> Result<(), CliParseError<'a>> {
+    match command {
+        CliTestCommand::Mode { name } if !["fast", "slow"].contains(name) => {
+            Err(CliParseError::ParseValueError {
+                name: Some("<NAME>"),
+                value: name,
+                expected: "fast or slow",
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TestCommand {
+    Speed { value: u8 },
+    Mode { name: String },
+    Stop,
+}
+
+impl_convert! {CliTestCommand<'_> => TestCommand, command, {
+    match command {
+        CliTestCommand::Speed { value } => TestCommand::Speed { value },
+        CliTestCommand::Mode { name } => TestCommand::Mode { name: name.to_string() },
+        CliTestCommand::Stop => TestCommand::Stop,
+    }
+}}
+
+#[rstest]
+#[case("speed 30", Ok(TestCommand::Speed { value: 30 }))]
+#[case("speed 200", Ok(TestCommand::Speed { value: 100 }))]
+#[case("mode fast", Ok(TestCommand::Mode { name: "fast".to_string() }))]
+#[case("mode medium", Err(ParseError::ParseValueError {
+    name: Some("<NAME>".to_string()),
+    value: "medium".to_string(),
+    expected: "fast or slow".to_string(),
+}))]
+#[case("stop", Ok(TestCommand::Stop))]
+fn hook_called(#[case] command: &str, #[case] expected: Result<TestCommand, ParseError>) {
+    let mut cli = CliWrapper::new();
+
+    cli.process_str(command);
+
+    cli.send_enter();
+
+    assert_eq!(cli.received_commands(), vec![expected]);
+}
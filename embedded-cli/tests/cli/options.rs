@@ -1,8 +1,13 @@
-use embedded_cli::Command;
+use embedded_cli::{
+    arguments::Size,
+    command::{OwnedCommand, RawCommand},
+    service::FromRaw,
+    Command,
+};
 use rstest::rstest;
 
 use crate::impl_convert;
-use crate::wrapper::CliWrapper;
+use crate::wrapper::{CliWrapper, ParseError};
 
 use crate::terminal::assert_terminal;
 
@@ -37,9 +42,7 @@ enum TestCommand {
 }
 
 impl_convert! {CliTestCommand<'_> => TestCommand, command, {
-    match command {
-        cmd => cmd.into(),
-    }
+    command.into()
 }}
 
 impl<'a> From<CliTestCommand<'a>> for TestCommand {
@@ -106,3 +109,81 @@ fn options_parsing(#[case] command: &str, #[case] expected: TestCommand) {
 
     assert_eq!(cli.received_commands(), vec![Ok(expected)]);
 }
+
+#[test]
+fn parse_without_cli() {
+    let mut input = String::from("cmd --конф \"my conf\" -l 3 file");
+    let command = CliTestCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap();
+
+    let owned = OwnedCommand::<64>::new("cmd")
+        .arg("--конф")
+        .arg("my conf")
+        .arg("-l")
+        .arg("3")
+        .arg("file");
+
+    assert_eq!(CliTestCommand::parse(owned.as_raw()).unwrap(), command);
+    assert_eq!(
+        command,
+        CliTestCommand::Cmd {
+            name: None,
+            config: "my conf",
+            level: 3,
+            verbose: false,
+            file: "file",
+        }
+    );
+}
+
+#[test]
+fn value_error_has_option_name() {
+    let mut cli = CliWrapper::<TestCommand>::new();
+
+    cli.process_str("cmd --конф conf -l abc file");
+    cli.send_enter();
+
+    assert_eq!(
+        cli.received_commands(),
+        vec![Err(ParseError::ParseValueError {
+            name: Some("-l".to_string()),
+            value: "abc".to_string(),
+            expected: "u8".to_string(),
+        })]
+    );
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum SizeCommand {
+    Erase {
+        #[arg(short, long)]
+        offset: Option<Size<u32>>,
+
+        len: Size<u16>,
+    },
+}
+
+#[rstest]
+#[case("erase 4k", SizeCommand::Erase { offset: None, len: Size(4000) })]
+#[case("erase --offset 2Ki 512", SizeCommand::Erase { offset: Some(Size(2048)), len: Size(512) })]
+#[case("erase -o 1M 1Ki", SizeCommand::Erase { offset: Some(Size(1_000_000)), len: Size(1024) })]
+fn size_arguments(#[case] command: &str, #[case] expected: SizeCommand) {
+    let mut input = String::from(command);
+    let command = SizeCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap();
+
+    assert_eq!(command, expected);
+}
+
+#[test]
+fn size_out_of_range() {
+    let mut input = String::from("erase 64Ki");
+    let err = SizeCommand::parse(RawCommand::from_input(&mut input).unwrap()).unwrap_err();
+
+    assert_eq!(
+        err,
+        embedded_cli::service::ParseError::ParseValueError {
+            name: Some("<LEN>"),
+            value: "64Ki",
+            expected: "u16 size",
+        }
+    );
+}
@@ -0,0 +1,56 @@
+use embedded_cli::{command::RawCommand, service::FromRaw, Command};
+use rstest::rstest;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand<'a> {
+    #[command(options_first)]
+    Move {
+        #[arg(short, long)]
+        fast: bool,
+
+        x: i32,
+
+        y: Option<i32>,
+    },
+
+    Name {
+        #[arg(short, long)]
+        fast: bool,
+
+        name: &'a str,
+    },
+}
+
+#[rstest]
+#[case("move -5 -10", CliCommand::Move { fast: false, x: -5, y: Some(-10) })]
+#[case("move -f -5", CliCommand::Move { fast: true, x: -5, y: None })]
+#[case("move --fast 3 -4", CliCommand::Move { fast: true, x: 3, y: Some(-4) })]
+#[case("name -f -- -x", CliCommand::Name { fast: true, name: "-x" })]
+fn parsed(#[case] input: &str, #[case] expected: CliCommand<'static>) {
+    let mut input = input.to_string();
+    let command = RawCommand::from_input(&mut input).unwrap();
+
+    assert_eq!(CliCommand::parse(command).ok(), Some(expected));
+}
+
+#[test]
+fn options_after_positional() {
+    let mut input = "move 3 --fast".to_string();
+    let command = RawCommand::from_input(&mut input).unwrap();
+
+    assert!(CliCommand::parse(command).is_err());
+}
+
+#[test]
+fn options_after_positional_without_attribute() {
+    let mut input = "name x -f".to_string();
+    let command = RawCommand::from_input(&mut input).unwrap();
+
+    assert_eq!(
+        CliCommand::parse(command).unwrap(),
+        CliCommand::Name {
+            fast: true,
+            name: "x"
+        }
+    );
+}
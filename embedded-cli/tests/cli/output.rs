@@ -0,0 +1,44 @@
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+use embedded_io::{ErrorType, Write};
+
+use crate::terminal::Terminal;
+
+/// Writer, that keeps all written bytes, so they can be inspected
+/// after writer is moved into `Cli`
+#[derive(Clone, Debug, Default)]
+pub struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Output {
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.borrow().clone()
+    }
+
+    pub fn terminal(&self) -> Terminal {
+        let mut terminal = Terminal::new();
+        terminal.receive_bytes(&self.0.borrow());
+        terminal
+    }
+}
+
+impl ErrorType for Output {
+    type Error = Infallible;
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Write for Output {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Write::write(self, buf)
+    }
+}
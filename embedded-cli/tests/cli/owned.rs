@@ -0,0 +1,95 @@
+use embedded_cli::{cli::CliBuilder, Command};
+
+use crate::output::Output;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+#[command(derive_owned)]
+enum CliBase<'a> {
+    Get {
+        #[arg(short, long)]
+        item: Option<&'a str>,
+
+        #[arg(short, long)]
+        level: u8,
+
+        file: &'a str,
+    },
+
+    #[command(subcommand)]
+    Nested(CliNested<'a>),
+
+    Reset,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+#[command(derive_owned, owned_derive(Clone, Debug, PartialEq))]
+enum CliNested<'a> {
+    Write { line: &'a str, count: Option<u8> },
+}
+
+#[test]
+fn commands_converted_to_owned() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands: Vec<CliBaseOwned> = vec![];
+    let mut processor = CliBase::processor(|_, command| {
+        commands.push(command.into());
+        Ok(())
+    });
+    for b in b"get -l 3 --item abc file.txt\nnested write \"some line\"\nreset\n" {
+        cli.process_byte::<CliBase<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(commands.len(), 3);
+    assert!(matches!(
+        &commands[0],
+        CliBaseOwned::Get { item: Some(item), level: 3, file }
+            if item == "abc" && file == "file.txt"
+    ));
+    assert!(matches!(
+        &commands[1],
+        CliBaseOwned::Nested(CliNestedOwned::Write { line, count: None }) if line == "some line"
+    ));
+    assert!(matches!(&commands[2], CliBaseOwned::Reset));
+}
+
+#[test]
+fn owned_commands_compared() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands: Vec<CliNestedOwned> = vec![];
+    let mut processor = CliNested::processor(|_, command| {
+        commands.push(command.into_owned());
+        Ok(())
+    });
+    for b in b"write abc 3\nwrite \"d e\"\n" {
+        cli.process_byte::<CliNested<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+    drop(processor);
+
+    assert_eq!(
+        commands,
+        vec![
+            CliNestedOwned::Write {
+                line: "abc".to_string(),
+                count: Some(3),
+            },
+            CliNestedOwned::Write {
+                line: "d e".to_string(),
+                count: None,
+            },
+        ]
+    );
+    assert_eq!(commands[0].clone(), commands[0]);
+}
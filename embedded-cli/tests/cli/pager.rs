@@ -0,0 +1,147 @@
+use embedded_cli::cli::EchoMode;
+use embedded_cli::pager::Pager;
+use embedded_io::{Error, Write};
+use rstest::rstest;
+
+use crate::wrapper::{CliWrapper, RawCommand};
+
+use crate::terminal::assert_terminal;
+
+/// Writes list of `len` elements, page starts with element `start`
+fn list<W: Write<Error = E>, E: Error>(
+    pager: &mut Pager<'_, '_, W, E>,
+    len: usize,
+    start: usize,
+) -> Result<(), E> {
+    for i in start..len {
+        pager.writeln_str(&format!("list {}", i))?;
+    }
+    Ok(())
+}
+
+fn paged_cli() -> CliWrapper<RawCommand> {
+    let mut cli = CliWrapper::default();
+
+    cli.set_handler(|cli, _| {
+        cli.pager(2, 5, list)?;
+        Ok(())
+    });
+
+    cli
+}
+
+#[test]
+fn show_all_pages() {
+    let mut cli = paged_cli();
+
+    cli.process_str("list");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        8,
+        vec!["$ list", "list 0", "list 1", "--More--"]
+    );
+
+    cli.process_str(" ");
+
+    assert_terminal!(
+        cli.terminal(),
+        8,
+        vec!["$ list", "list 0", "list 1", "list 2", "list 3", "--More--"]
+    );
+
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec!["$ list", "list 0", "list 1", "list 2", "list 3", "list 4", "$"]
+    );
+
+    cli.process_str("abc");
+    assert_terminal!(
+        cli.terminal(),
+        5,
+        vec!["$ list", "list 0", "list 1", "list 2", "list 3", "list 4", "$ abc"]
+    );
+
+    // command is processed once, next pages are written by the same source
+    assert_eq!(cli.received_commands().len(), 1);
+}
+
+#[test]
+fn context_passed_to_source() {
+    let mut cli = paged_cli();
+    cli.set_handler(|cli, _| {
+        cli.pager(2, 3, list)?;
+        Ok(())
+    });
+
+    cli.process_str("list");
+    cli.send_enter();
+    cli.process_str(" ");
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec!["$ list", "list 0", "list 1", "list 2", "$"]
+    );
+}
+
+#[rstest]
+#[case("q")]
+#[case("\x03")]
+fn quit_paging(#[case] quit: &str) {
+    let mut cli = paged_cli();
+
+    cli.process_str("list");
+    cli.send_enter();
+    cli.process_str(quit);
+
+    assert_terminal!(cli.terminal(), 2, vec!["$ list", "list 0", "list 1", "$"]);
+
+    cli.process_str("get");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        8,
+        vec!["$ list", "list 0", "list 1", "$ get", "list 0", "list 1", "--More--"]
+    );
+}
+
+#[test]
+fn write_while_paging() {
+    let mut cli = paged_cli();
+
+    cli.process_str("list");
+    cli.send_enter();
+    cli.write_str("external");
+
+    assert_terminal!(
+        cli.terminal(),
+        8,
+        vec!["$ list", "list 0", "list 1", "external", "--More--"]
+    );
+}
+
+#[test]
+fn paging_with_hidden_echo() {
+    let mut cli = paged_cli();
+    cli.set_handler(|cli, _| {
+        cli.set_echo(EchoMode::Hidden);
+        cli.pager(2, 5, list)?;
+        Ok(())
+    });
+
+    cli.process_str("list");
+    cli.send_enter();
+    cli.process_str(" ");
+
+    assert_terminal!(
+        cli.terminal(),
+        8,
+        vec!["$ list", "list 0", "list 1", "list 2", "list 3", "--More--"]
+    );
+}
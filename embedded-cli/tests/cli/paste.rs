@@ -0,0 +1,142 @@
+use embedded_cli::{cli::CliBuilder, command::RawCommand};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[test]
+fn pasted_text_echoed_at_end() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands = 0;
+    let mut processor = RawCommand::processor(|_, _| {
+        commands += 1;
+        Ok(())
+    });
+    cli.process_slice::<RawCommand<'_>, _>(b"a \x1B[200~set\tled\r\n1", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "a set led 1");
+    assert_terminal!(output.terminal(), 4, vec!["$ a"]);
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\x1B[201~", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), 13, vec!["$ a set led 1"]);
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\r", &mut processor)
+        .unwrap();
+    drop(processor);
+    assert_eq!(commands, 1);
+}
+
+#[test]
+fn paste_inside_input() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    for &b in b"set 1\x1B[D\x1B[200~led \x1B[201~" {
+        cli.process_byte::<RawCommand<'_>, _>(b, &mut processor)
+            .unwrap();
+    }
+    assert_eq!(cli.current_input(), "set led 1");
+    assert_terminal!(output.terminal(), 10, vec!["$ set led 1"]);
+
+    cli.process_slice::<RawCommand<'_>, _>(b"x", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), 11, vec!["$ set led x1"]);
+}
+
+#[rstest]
+#[case(b"\x1B", 9, vec!["$ set led"])]
+#[case(b"\x03", 3, vec!["$ set led^C", "$ x"])]
+fn unterminated_paste(#[case] escape: &[u8], #[case] cursor: usize, #[case] terminal: Vec<&str>) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands = 0;
+    let mut processor = RawCommand::processor(|_, _| {
+        commands += 1;
+        Ok(())
+    });
+    cli.process_slice::<RawCommand<'_>, _>(b"\x1B[200~set led", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), 2, vec!["$"]);
+
+    // paste end was lost, so paste is left manually (Esc is followed by any key)
+    cli.process_slice::<RawCommand<'_>, _>(escape, &mut processor)
+        .unwrap();
+    cli.process_slice::<RawCommand<'_>, _>(b"x", &mut processor)
+        .unwrap();
+    assert_terminal!(output.terminal(), cursor, terminal);
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\r", &mut processor)
+        .unwrap();
+    drop(processor);
+    assert_eq!(commands, 1);
+}
+
+#[test]
+fn unterminated_paste_reset() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"\x1B[200~set led", &mut processor)
+        .unwrap();
+
+    cli.reset(false).unwrap();
+    cli.process_slice::<RawCommand<'_>, _>(b"ab", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "ab");
+    assert_terminal!(output.terminal(), 4, vec!["$ ab"]);
+}
+
+#[test]
+fn paste_longer_than_buffer() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .command_buffer([0; 8])
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"\x1B[200~0123456789", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "01234567");
+    assert_terminal!(output.terminal(), 10, vec!["$ 01234567"]);
+}
+
+#[test]
+fn flow_control_while_pasting() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .flow_control(true)
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"\x1B[200~ab\x13cd", &mut processor)
+        .unwrap();
+    assert!(cli.is_output_paused());
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\x11\x1B[201~", &mut processor)
+        .unwrap();
+    assert!(!cli.is_output_paused());
+    assert_eq!(cli.current_input(), "abcd");
+    assert_terminal!(output.terminal(), 6, vec!["$ abcd"]);
+}
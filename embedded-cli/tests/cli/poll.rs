@@ -0,0 +1,141 @@
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+use embedded_cli::{cli::CliBuilder, command::RawCommand};
+use embedded_io::{ErrorType, Write};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[test]
+fn poll_stops_after_command() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands = vec![];
+    let mut processor = RawCommand::processor(|_, command| {
+        commands.push(command.name().to_string());
+        Ok(())
+    });
+
+    let input = b"abc\ndef\nghijkl";
+    let mut polls = vec![];
+    let mut rest = &input[..];
+    while !rest.is_empty() {
+        let processed = cli
+            .poll_slice::<RawCommand<'_>, _>(rest, 3, &mut processor)
+            .unwrap();
+        polls.push(processed);
+        rest = &rest[processed..];
+    }
+    drop(processor);
+
+    assert_eq!(polls, vec![3, 1, 3, 1, 3, 3]);
+    assert_eq!(commands, vec!["abc", "def"]);
+    assert_terminal!(output.terminal(), 8, vec!["$ abc", "$ def", "$ ghijkl"]);
+}
+
+#[test]
+fn process_whole_slice() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut commands = vec![];
+    let mut processor = RawCommand::processor(|_, command| {
+        commands.push(command.name().to_string());
+        Ok(())
+    });
+
+    cli.process_slice::<RawCommand<'_>, _>(b"abc\ndef\ngh", &mut processor)
+        .unwrap();
+    drop(processor);
+
+    assert_eq!(commands, vec!["abc", "def"]);
+    assert_terminal!(output.terminal(), 4, vec!["$ abc", "$ def", "$ gh"]);
+}
+
+fn run(input: &[u8], by_slice: bool) -> (Vec<String>, Vec<u8>) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .command_buffer([0; 16])
+        .build()
+        .unwrap();
+
+    let mut commands = vec![];
+    let mut processor = RawCommand::processor(|_, command| {
+        commands.push(command.line().unwrap_or_default().to_string());
+        Ok(())
+    });
+    if by_slice {
+        cli.process_slice::<RawCommand<'_>, _>(input, &mut processor)
+            .unwrap();
+    } else {
+        for &b in input {
+            cli.process_byte::<RawCommand<'_>, _>(b, &mut processor)
+                .unwrap();
+        }
+    }
+    drop(processor);
+
+    (commands, output.bytes())
+}
+
+#[rstest]
+#[case(b"get 1 2\r\nset \"a b\"\n")]
+#[case(b"abc\x1B[Dd\x1B[Ce\n")]
+#[case(b"\x1B[A\x1BOAabc\x08d\n")]
+#[case("абв gh\n".as_bytes())]
+#[case(b"very long pasted line, that doesn't fit\nok\n")]
+fn slice_same_as_bytes(#[case] input: &[u8]) {
+    let (commands, output) = run(input, true);
+    let (expected_commands, expected_output) = run(input, false);
+
+    assert_eq!(commands, expected_commands);
+    let mut terminal = Terminal::new();
+    terminal.receive_bytes(&output);
+    let mut expected = Terminal::new();
+    expected.receive_bytes(&expected_output);
+    assert_eq!(terminal.view(), expected.view());
+}
+
+/// Writer, that keeps each write separately
+#[derive(Clone, Debug, Default)]
+struct Writes(Rc<RefCell<Vec<Vec<u8>>>>);
+
+impl ErrorType for Writes {
+    type Error = Infallible;
+}
+
+impl Write for Writes {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().push(buf.to_vec());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn pasted_text_echoed_at_once() {
+    let writes = Writes::default();
+    let mut cli = CliBuilder::default()
+        .writer(writes.clone())
+        .build()
+        .unwrap();
+    writes.0.borrow_mut().clear();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"set value 123", &mut processor)
+        .unwrap();
+
+    assert_eq!(*writes.0.borrow(), vec![b"set value 123".to_vec()]);
+}
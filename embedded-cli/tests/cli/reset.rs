@@ -0,0 +1,40 @@
+use embedded_cli::{cli::CliBuilder, command::RawCommand};
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::terminal::assert_terminal;
+
+#[rstest]
+#[case(false, "$ abc")]
+#[case(true, "$ ")]
+fn input_and_sequence_dropped(#[case] clear_history: bool, #[case] recalled: &str) {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = RawCommand::processor(|_, _| Ok(()));
+    cli.process_slice::<RawCommand<'_>, _>(b"abc\nde\x1B[", &mut processor)
+        .unwrap();
+
+    cli.reset(clear_history).unwrap();
+    assert_eq!(cli.current_input(), "");
+    assert_terminal!(output.terminal(), 2, vec!["$ abc", "$"]);
+
+    // escape sequence was dropped, so this is a usual char
+    cli.process_slice::<RawCommand<'_>, _>(b"A", &mut processor)
+        .unwrap();
+    assert_eq!(cli.current_input(), "A");
+
+    cli.process_slice::<RawCommand<'_>, _>(b"\x08\x1B[A", &mut processor)
+        .unwrap();
+    #[cfg(feature = "history")]
+    assert_terminal!(
+        output.terminal(),
+        recalled.len(),
+        vec!["$ abc", recalled.trim_end()]
+    );
+    #[cfg(not(feature = "history"))]
+    let _ = recalled;
+}
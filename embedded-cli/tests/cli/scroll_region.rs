@@ -0,0 +1,143 @@
+use embedded_cli::{cli::CliBuilder, Command};
+
+use crate::output::Output;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set { value: u8 },
+}
+
+/// Minimal emulation of terminal screen with scroll region support
+struct Screen {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+    saved: (usize, usize),
+    top: usize,
+    bottom: usize,
+}
+
+impl Screen {
+    fn new(rows: usize, bytes: &[u8]) -> Self {
+        let mut screen = Self {
+            lines: vec![vec![]; rows],
+            row: 0,
+            col: 0,
+            saved: (0, 0),
+            top: 0,
+            bottom: rows - 1,
+        };
+        let text = std::str::from_utf8(bytes).unwrap();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1B' => match chars.next().unwrap() {
+                    '7' => screen.saved = (screen.row, screen.col),
+                    '8' => (screen.row, screen.col) = screen.saved,
+                    '[' => {
+                        let mut params = String::new();
+                        let final_char = loop {
+                            let c = chars.next().unwrap();
+                            if c.is_ascii_digit() || c == ';' {
+                                params.push(c);
+                            } else {
+                                break c;
+                            }
+                        };
+                        let params = params
+                            .split(';')
+                            .map(|p| p.parse::<usize>().unwrap_or(1))
+                            .collect::<Vec<_>>();
+                        screen.csi(&params, final_char);
+                    }
+                    _ => unimplemented!(),
+                },
+                '\r' => screen.col = 0,
+                '\n' => {
+                    if screen.row == screen.bottom {
+                        screen.lines.remove(screen.top);
+                        screen.lines.insert(screen.bottom, vec![]);
+                    } else if screen.row + 1 < screen.lines.len() {
+                        screen.row += 1;
+                    }
+                }
+                c => {
+                    let line = &mut screen.lines[screen.row];
+                    while line.len() <= screen.col {
+                        line.push(' ');
+                    }
+                    line[screen.col] = c;
+                    screen.col += 1;
+                }
+            }
+        }
+        screen
+    }
+
+    fn csi(&mut self, params: &[usize], final_char: char) {
+        match final_char {
+            'H' => {
+                self.row = params[0] - 1;
+                self.col = params[1] - 1;
+            }
+            'r' => {
+                self.top = params[0] - 1;
+                self.bottom = params[1] - 1;
+                self.row = 0;
+                self.col = 0;
+            }
+            'K' => self.lines[self.row].clear(),
+            'm' => {}
+            _ => unimplemented!(),
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+}
+
+#[test]
+fn prompt_is_pinned() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .scroll_region(5)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, _| {
+        cli.writer().write_str("ok")?;
+        Ok(())
+    });
+
+    let screen = Screen::new(5, &output.bytes());
+    assert_eq!(screen.lines(), ["", "", "", "", "$"]);
+    assert_eq!(screen.cursor(), (4, 2));
+
+    for b in b"set 1" {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.write(|writer| writer.write_str("log")).unwrap();
+
+    // input is not touched by output
+    let screen = Screen::new(5, &output.bytes());
+    assert_eq!(screen.lines(), ["", "", "log", "", "$ set 1"]);
+    assert_eq!(screen.cursor(), (4, 7));
+
+    cli.process_byte::<CliCommand, _>(b'\n', &mut processor)
+        .unwrap();
+
+    // entered command and its output are scrolled above prompt
+    let screen = Screen::new(5, &output.bytes());
+    assert_eq!(screen.lines(), ["log", "$ set 1", "ok", "", "$"]);
+    assert_eq!(screen.cursor(), (4, 2));
+}
@@ -0,0 +1,69 @@
+use embedded_cli::{cli::CliBuilder, command::RawCommand, service::ProcessError, Command};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Inc { value: u8 },
+    Reset,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    counter: u32,
+    commands: usize,
+}
+
+#[test]
+fn typed_processor() {
+    let mut cli = CliBuilder::default().build().unwrap();
+    let mut state = State::default();
+
+    let mut processor = CliCommand::stateful_processor(|_, state: &mut State, command| {
+        state.commands += 1;
+        match command {
+            CliCommand::Inc { value } => state.counter += value as u32,
+            CliCommand::Reset => state.counter = 0,
+        }
+        Ok(())
+    });
+
+    for b in b"inc 5\ninc 3\n" {
+        cli.process_byte_with_state::<CliCommand, _, _>(*b, &mut state, &mut processor)
+            .unwrap();
+    }
+    // state is accessible between calls
+    assert_eq!(state.counter, 8);
+
+    for b in b"reset\ninc 2\n" {
+        cli.process_byte_with_state::<CliCommand, _, _>(*b, &mut state, &mut processor)
+            .unwrap();
+    }
+
+    assert_eq!(state.counter, 2);
+    assert_eq!(state.commands, 4);
+}
+
+fn count_commands<'a>(
+    _: &mut embedded_cli::cli::CliHandle<
+        '_,
+        embedded_cli::writer::EmptyWriter,
+        core::convert::Infallible,
+    >,
+    state: &mut usize,
+    _: RawCommand<'a>,
+) -> Result<(), ProcessError<'a, core::convert::Infallible>> {
+    *state += 1;
+    Ok(())
+}
+
+#[test]
+fn raw_processor() {
+    let mut cli = CliBuilder::default().build().unwrap();
+    let mut state = 0;
+
+    for b in b"abc\ndef 1\n" {
+        cli.process_byte_with_state::<RawCommand<'_>, _, _>(*b, &mut state, &mut count_commands)
+            .unwrap();
+    }
+
+    assert_eq!(state, 2);
+}
@@ -1,8 +1,9 @@
-use embedded_cli::Command;
+use embedded_cli::{cli::CliBuilder, Command};
 use rstest::rstest;
 
 use crate::impl_convert;
-use crate::wrapper::CliWrapper;
+use crate::output::Output;
+use crate::wrapper::{CliWrapper, ParseError};
 
 use crate::terminal::assert_terminal;
 
@@ -205,3 +206,44 @@ fn options_parsing(#[case] command: &str, #[case] expected: Base) {
 
     assert_eq!(cli.received_commands(), vec![Ok(expected)]);
 }
+
+#[rstest]
+#[case("base1 --level 1", vec!["get", "set"])]
+#[case("base2", vec!["get", "write"])]
+fn missing_subcommand(#[case] command: &str, #[case] expected: Vec<&str>) {
+    let mut cli = CliWrapper::<Base>::new();
+
+    cli.process_str(command);
+
+    cli.send_enter();
+
+    assert_eq!(
+        cli.received_commands(),
+        vec![Err(ParseError::MissingSubcommand {
+            expected: expected.into_iter().map(String::from).collect()
+        })]
+    );
+}
+
+#[test]
+fn missing_subcommand_lists_expected() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliBase::processor(|_, _| Ok(()));
+    cli.process_slice::<CliBase<'_>, _>(b"base2\n", &mut processor)
+        .unwrap();
+
+    assert_terminal!(
+        output.terminal(),
+        2,
+        vec![
+            "$ base2",
+            "error: missing subcommand, expected one of: get, write",
+            "$"
+        ]
+    );
+}
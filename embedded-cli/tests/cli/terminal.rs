@@ -33,6 +33,11 @@ impl Terminal {
         }
     }
 
+    /// Returns how many times bell was rung
+    pub fn bells(&self) -> usize {
+        self.received.iter().filter(|&&b| b == 0x07).count()
+    }
+
     /// Returns vector of terminal lines
     /// and current cursor position (cursor column)
     ///
@@ -68,7 +73,7 @@ impl Terminal {
             };
 
             if let Some(normal) = normal {
-                for c in normal.chars().into_iter() {
+                for c in normal.chars() {
                     match c {
                         '\r' => {
                             cursor = 0;
@@ -77,19 +82,18 @@ impl Terminal {
                             // start new line (but keep cursor position)
                             output.push("".to_string());
                         }
+                        // bell is not displayed
+                        '\x07' => {}
                         c if c >= ' ' => {
                             let current = output.last_mut().unwrap();
                             if current.chars().count() > cursor {
-                                current
-                                    .remove(current.char_indices().skip(cursor).next().unwrap().0);
+                                current.remove(current.char_indices().nth(cursor).unwrap().0);
                             } else {
                                 while current.chars().count() < cursor {
                                     current.push(' ');
                                 }
                             }
-                            if let Some((insert_pos, _)) =
-                                current.char_indices().skip(cursor).next()
-                            {
+                            if let Some((insert_pos, _)) = current.char_indices().nth(cursor) {
                                 current.insert(insert_pos, c);
                             } else {
                                 current.push(c);
@@ -110,21 +114,24 @@ impl Terminal {
                     }
                     // cursor backward
                     "\x1B[D" => {
-                        if cursor > 0 {
-                            cursor -= 1;
-                        }
+                        cursor = cursor.saturating_sub(1);
                     }
                     // delete char
                     "\x1B[P" => {
                         if current.chars().count() > cursor {
-                            current.remove(current.char_indices().skip(cursor).next().unwrap().0);
+                            current.remove(current.char_indices().nth(cursor).unwrap().0);
                         }
                     }
                     // insert char
                     "\x1B[@" => {
                         if current.chars().count() > cursor {
-                            current
-                                .insert(current.char_indices().skip(cursor).next().unwrap().0, ' ');
+                            current.insert(current.char_indices().nth(cursor).unwrap().0, ' ');
+                        }
+                    }
+                    // clear line from cursor to the end
+                    "\x1B[K" => {
+                        if let Some((pos, _)) = current.char_indices().nth(cursor) {
+                            current.truncate(pos);
                         }
                     }
                     // clear whole line
@@ -132,6 +139,8 @@ impl Terminal {
                         // cursor position does not change
                         current.clear();
                     }
+                    // colors are not tracked
+                    seq if seq.ends_with('m') => {}
                     _ => unimplemented!(),
                 }
             }
@@ -286,4 +295,16 @@ mod tests {
         terminal.receive_byte(b'd');
         assert_terminal!(&terminal, 4, vec!["   d"]);
     }
+
+    #[test]
+    fn clear_line_end() {
+        let mut terminal = Terminal::new();
+
+        terminal.receive_bytes(b"abcd\x1B[D\x1B[D");
+        terminal.receive_bytes(codes::CLEAR_LINE_END);
+        assert_terminal!(&terminal, 2, vec!["ab"]);
+
+        terminal.receive_byte(b'e');
+        assert_terminal!(&terminal, 3, vec!["abe"]);
+    }
 }
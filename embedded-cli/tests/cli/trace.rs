@@ -0,0 +1,36 @@
+use crate::wrapper::{CliWrapper, RawCommand};
+
+use crate::terminal::assert_terminal;
+
+#[test]
+fn trace_command() {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().trace_commands(true).build();
+
+    cli.process_str("set --led \"a b\" -vf -- -c\n");
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ set --led \"a b\" -vf -- -c",
+            "trace: name=\"set\"",
+            "trace: long=\"led\"",
+            "trace: value=\"a b\"",
+            "trace: short=\"v\"",
+            "trace: short=\"f\"",
+            "trace: --",
+            "trace: value=\"-c\"",
+            "$"
+        ]
+    );
+    assert_eq!(cli.received_commands().len(), 1);
+}
+
+#[test]
+fn trace_disabled() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set 1\n");
+
+    assert_terminal!(cli.terminal(), 2, vec!["$ set 1", "$"]);
+}
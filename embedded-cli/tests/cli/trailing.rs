@@ -0,0 +1,119 @@
+use embedded_cli::{cli::CliBuilder, Command};
+use ufmt::uwrite;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand<'a> {
+    /// Print text
+    Say {
+        /// How many times to print
+        #[arg(short, long)]
+        times: Option<u8>,
+
+        /// Text to print
+        #[arg(trailing)]
+        text: Option<&'a str>,
+    },
+}
+
+fn run(command_buffer: [u8; 64], input: &str) -> Terminal {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .command_buffer(command_buffer)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        match command {
+            CliCommand::Say { times, text } => {
+                for _ in 0..times.unwrap_or(1) {
+                    uwrite!(cli.writer(), "[{}]", text.unwrap_or(""))?;
+                }
+            }
+        }
+        Ok(())
+    });
+    for b in input.as_bytes() {
+        cli.process_byte::<CliCommand<'_>, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    output.terminal()
+}
+
+#[test]
+fn rest_of_line_captured() {
+    let terminal = run([0; 64], "say -t 2 hello  \"big\" -t world  \n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ say -t 2 hello  \"big\" -t world",
+            "[hello  \"big\" -t world][hello  \"big\" -t world]",
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn empty_rest() {
+    let terminal = run([0; 64], "say\n");
+
+    assert_terminal!(&terminal, 2, vec!["$ say", "[]", "$"]);
+}
+
+#[test]
+fn quotes_kept() {
+    let terminal = run([0; 64], "say \"hello  world\"\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec!["$ say \"hello  world\"", "[\"hello  world\"]", "$"]
+    );
+}
+
+#[test]
+fn error_without_line() {
+    // escaped quote is removed when input is tokenized in place,
+    // so line is not known and rest of it can't be taken as typed
+    let terminal = run([0; 64], "say hello \"my \\\"dear\\\" world\"\n");
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ say hello \"my \\\"dear\\\" world\"",
+            "error: original line is not available for <TEXT>",
+            "$"
+        ]
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_shows_trailing() {
+    let terminal = run([0; 64], "say --help\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ say --help",
+            "Print text",
+            "",
+            "Usage: say [OPTIONS] [TEXT]...",
+            "",
+            "Arguments:",
+            "  [TEXT]...  Text to print",
+            "",
+            "Options:",
+            "  -t, --times [TIMES]  How many times to print",
+            "  -h, --help           Print help",
+            "$",
+        ]
+    );
+}
@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+
+use embedded_cli::{cli::CliBuilder, writer::Transcript, Command};
+use ufmt::uwrite;
+
+use crate::output::Output;
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Get { item: u8 },
+}
+
+thread_local! {
+    static RECEIVED: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    static SENT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(transcript: Transcript<'_>) {
+    match transcript {
+        Transcript::Received(byte) => RECEIVED.with(|r| r.borrow_mut().push(byte)),
+        Transcript::Sent(bytes) => SENT.with(|s| s.borrow_mut().extend_from_slice(bytes)),
+    }
+}
+
+#[test]
+fn transcript_mirrors_session() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .transcript(record)
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        match command {
+            CliCommand::Get { item } => uwrite!(cli.writer(), "item {}", item)?,
+        }
+        Ok(())
+    });
+    let input = b"get 5\ngat\n\x1B[A";
+    for b in input {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+    cli.write(|writer| writer.write_str("log")).unwrap();
+
+    assert_eq!(RECEIVED.with(|r| r.borrow().clone()), input.to_vec());
+    assert_eq!(SENT.with(|s| s.borrow().clone()), output.bytes());
+    assert!(output.terminal().view().0.contains(&"item 5".to_string()));
+}
@@ -0,0 +1,26 @@
+use rstest::rstest;
+
+use crate::wrapper::{CliWrapper, RawCommand};
+
+use crate::terminal::assert_terminal;
+
+#[rstest]
+#[case(false, "$ abвг", 6)]
+#[case(true, "$ a\u{FFFD}b\u{FFFD}вг", 8)]
+fn invalid_bytes_skipped(#[case] replace: bool, #[case] line: &str, #[case] cursor: usize) {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().utf8_replacement(replace).build();
+
+    // stray continuation octet, then truncated char interrupted by "в"
+    cli.process_bytes(b"a\xBFb\xD0\xD0\xB2\xD0\xB3");
+
+    assert_terminal!(cli.terminal(), cursor, vec![line]);
+}
+
+#[test]
+fn control_byte_interrupts_char() {
+    let mut cli = CliWrapper::<RawCommand>::new();
+
+    cli.process_bytes(b"ab\xD0\x08\xB1c");
+
+    assert_terminal!(cli.terminal(), 4, vec!["$ ac"]);
+}
@@ -0,0 +1,113 @@
+use embedded_cli::{arguments::ValueEnum, cli::CliBuilder, Command, ValueEnum};
+use ufmt::uwrite;
+
+use crate::output::Output;
+use crate::terminal::{assert_terminal, Terminal};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Mode {
+    Fast,
+    LowPower,
+    #[value(name = "off")]
+    Disabled,
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    /// Set device mode
+    Set {
+        /// Mode to use
+        #[arg(short, long)]
+        mode: Mode,
+
+        /// Mode after reset
+        reset: Option<Mode>,
+    },
+}
+
+fn run(input: &str) -> Terminal {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+
+    let mut processor = CliCommand::processor(|cli, command| {
+        match command {
+            CliCommand::Set { mode, reset } => {
+                uwrite!(cli.writer(), "mode: {}", mode.name())?;
+                if let Some(reset) = reset {
+                    uwrite!(cli.writer(), ", reset: {}", reset.name())?;
+                }
+            }
+        }
+        Ok(())
+    });
+    for b in input.as_bytes() {
+        cli.process_byte::<CliCommand, _>(*b, &mut processor)
+            .unwrap();
+    }
+
+    output.terminal()
+}
+
+#[test]
+fn values() {
+    assert_eq!(Mode::VALUES, &["fast", "low-power", "off"]);
+    assert_eq!(Mode::LowPower.name(), "low-power");
+}
+
+#[test]
+fn parse_values() {
+    let terminal = run("set --mode low-power off\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ set --mode low-power off",
+            "mode: low-power, reset: off",
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn unknown_value() {
+    let terminal = run("set -m slow\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ set -m slow",
+            "error: failed to parse '--mode slow', expected one of: fast, low-power, off",
+            "$"
+        ]
+    );
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn possible_values_in_help() {
+    let terminal = run("set --help\n");
+
+    assert_terminal!(
+        &terminal,
+        2,
+        vec![
+            "$ set --help",
+            "Set device mode",
+            "",
+            "Usage: set [OPTIONS] [RESET]",
+            "",
+            "Arguments:",
+            "  [RESET]  Mode after reset [possible values: fast, low-power, off]",
+            "",
+            "Options:",
+            "  -m, --mode <MODE>  Mode to use [possible values: fast, low-power, off]",
+            "  -h, --help         Print help",
+            "$",
+        ]
+    );
+}
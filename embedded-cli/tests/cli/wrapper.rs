@@ -2,8 +2,12 @@ use std::{cell::RefCell, convert::Infallible, fmt::Debug, marker::PhantomData, r
 
 use embedded_cli::{
     arguments::Arg as CliArg,
-    cli::{Cli, CliBuilder, CliHandle},
+    cli::{Cli, CliBuilder, CliEvent, CliHandle, TabInsert},
+    codes,
     command::RawCommand as CliRawCommand,
+    input::{ControlInput, Input},
+    interrupt::InterruptReader,
+    prompt::Prompt,
     service::{Autocomplete, CommandProcessor, Help, ParseError as CliParseError, ProcessError},
 };
 use embedded_io::ErrorType;
@@ -35,7 +39,7 @@ macro_rules! impl_convert {
             }
 
             #[cfg(feature = "help")]
-            fn list_commands<W: embedded_io::Write<Error = E>, E: embedded_io::Error>(
+            fn list_commands<W: embedded_io::Write<Error = E> + ?Sized, E: embedded_io::Error>(
                 writer: &mut embedded_cli::writer::Writer<'_, W, E>,
             ) -> Result<(), E> {
                 <$from_ty>::list_commands(writer)
@@ -43,7 +47,7 @@ macro_rules! impl_convert {
 
             #[cfg(feature = "help")]
             fn command_help<
-                W: embedded_io::Write<Error = E>,
+                W: embedded_io::Write<Error = E> + ?Sized,
                 E: embedded_io::Error,
                 F: FnMut(&mut embedded_cli::writer::Writer<'_, W, E>) -> Result<(), E>,
             >(
@@ -55,10 +59,10 @@ macro_rules! impl_convert {
             }
         }
 
-        impl crate::wrapper::CommandConvert for $to_ty {
+        impl $crate::wrapper::CommandConvert for $to_ty {
             fn convert(
                 cmd: embedded_cli::command::RawCommand<'_>,
-            ) -> Result<Self, crate::wrapper::ParseError> {
+            ) -> Result<Self, $crate::wrapper::ParseError> {
                 let $var_name = <$from_ty as embedded_cli::service::FromRaw>::parse(cmd)?;
                 let cmd = $conversion;
                 Ok(cmd)
@@ -82,9 +86,7 @@ pub struct RawCommand {
 }
 
 impl_convert! {CliRawCommand<'_> => RawCommand, command, {
-    match command {
-        cmd => cmd.into(),
-    }
+    command.into()
 }}
 
 impl<'a> From<CliRawCommand<'a>> for RawCommand {
@@ -105,10 +107,28 @@ impl<'a> From<CliRawCommand<'a>> for RawCommand {
     }
 }
 
+/// Owned version of decoded input
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecordedInput {
+    Char(String),
+    Control(ControlInput),
+}
+
+impl From<Input<'_>> for RecordedInput {
+    fn from(value: Input<'_>) -> Self {
+        match value {
+            Input::Char(text) => RecordedInput::Char(text.to_string()),
+            Input::Control(control) => RecordedInput::Control(control),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct State<T> {
     written: Vec<u8>,
     commands: Vec<Result<T, ParseError>>,
+    events: Vec<CliEvent<'static>>,
+    inputs: Vec<RecordedInput>,
 }
 
 impl<T> Default for State<T> {
@@ -116,21 +136,39 @@ impl<T> Default for State<T> {
         Self {
             written: Default::default(),
             commands: Default::default(),
+            events: Default::default(),
+            inputs: Default::default(),
         }
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
-    MissingRequiredArgument { name: String },
+    MissingRequiredArgument {
+        name: String,
+    },
+
+    MissingSubcommand {
+        expected: Vec<String>,
+    },
 
-    ParseValueError { value: String, expected: String },
+    ParseValueError {
+        name: Option<String>,
+        value: String,
+        expected: String,
+    },
 
-    UnexpectedArgument { value: String },
+    UnexpectedArgument {
+        value: String,
+    },
 
-    UnexpectedLongOption { name: String },
+    UnexpectedLongOption {
+        name: String,
+    },
 
-    UnexpectedShortOption { name: char },
+    UnexpectedShortOption {
+        name: char,
+    },
 
     UnknownCommand,
     Other,
@@ -142,7 +180,15 @@ impl<'a> From<CliParseError<'a>> for ParseError {
             CliParseError::MissingRequiredArgument { name } => {
                 Self::MissingRequiredArgument { name: name.into() }
             }
-            CliParseError::ParseValueError { value, expected } => Self::ParseValueError {
+            CliParseError::MissingSubcommand { expected } => Self::MissingSubcommand {
+                expected: expected.iter().map(|name| name.to_string()).collect(),
+            },
+            CliParseError::ParseValueError {
+                name,
+                value,
+                expected,
+            } => Self::ParseValueError {
+                name: name.map(|name| name.into()),
                 value: value.into(),
                 expected: expected.into(),
             },
@@ -152,22 +198,27 @@ impl<'a> From<CliParseError<'a>> for ParseError {
             CliParseError::UnexpectedLongOption { name } => {
                 Self::UnexpectedLongOption { name: name.into() }
             }
-            CliParseError::UnexpectedShortOption { name } => {
-                Self::UnexpectedShortOption { name: name.into() }
-            }
+            CliParseError::UnexpectedShortOption { name } => Self::UnexpectedShortOption { name },
             CliParseError::UnknownCommand => Self::UnknownCommand,
             _ => Self::Other,
         }
     }
 }
 
+type Handler<T> =
+    Box<dyn FnMut(&mut CliHandle<'_, Writer<T>, Infallible>, T) -> Result<(), Infallible>>;
+
+type EventHandler<T> = Box<
+    dyn FnMut(&mut CliHandle<'_, Writer<T>, Infallible>, CliEvent<'_>) -> Result<(), Infallible>,
+>;
+
 pub struct CliWrapper<T: Autocomplete + Help + CommandConvert + Clone> {
     /// Actual cli object
     cli: Cli<Writer<T>, Infallible, &'static mut [u8], &'static mut [u8]>,
 
-    handler: Option<
-        Box<dyn FnMut(&mut CliHandle<'_, Writer<T>, Infallible>, T) -> Result<(), Infallible>>,
-    >,
+    event_handler: Option<EventHandler<T>>,
+
+    handler: Option<Handler<T>>,
 
     state: Rc<RefCell<State<T>>>,
 
@@ -175,9 +226,8 @@ pub struct CliWrapper<T: Autocomplete + Help + CommandConvert + Clone> {
 }
 
 struct App<T: CommandConvert + Clone> {
-    handler: Option<
-        Box<dyn FnMut(&mut CliHandle<'_, Writer<T>, Infallible>, T) -> Result<(), Infallible>>,
-    >,
+    event_handler: Option<EventHandler<T>>,
+    handler: Option<Handler<T>>,
     state: Rc<RefCell<State<T>>>,
 }
 
@@ -195,6 +245,28 @@ impl<T: CommandConvert + Clone> CommandProcessor<Writer<T>, Infallible> for App<
         }
         Ok(())
     }
+
+    fn on_event(
+        &mut self,
+        cli: &mut CliHandle<'_, Writer<T>, Infallible>,
+        event: CliEvent<'_>,
+    ) -> Result<(), Infallible> {
+        // help requests borrow input, so they can only be checked in event handler
+        if let CliEvent::FunctionKey(key) = event {
+            self.state
+                .borrow_mut()
+                .events
+                .push(CliEvent::FunctionKey(key));
+        }
+        if let Some(handler) = &mut self.event_handler {
+            handler(cli, event)?;
+        }
+        Ok(())
+    }
+
+    fn on_input(&mut self, input: Input<'_>) {
+        self.state.borrow_mut().inputs.push(input.into());
+    }
 }
 
 impl Default for CliWrapper<RawCommand> {
@@ -206,9 +278,14 @@ impl Default for CliWrapper<RawCommand> {
 impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
     pub fn builder() -> CliWrapperBuilder<T> {
         CliWrapperBuilder {
+            colors: false,
             command_size: 80,
+            flow_control: false,
             history_size: 500,
             prompt: None,
+            tab_insert: TabInsert::Nothing,
+            trace_commands: false,
+            utf8_replacement: false,
             _ph: PhantomData,
         }
     }
@@ -218,14 +295,20 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
     }
 
     pub fn process_str(&mut self, text: &str) {
+        self.process_bytes(text.as_bytes())
+    }
+
+    pub fn process_bytes(&mut self, bytes: &[u8]) {
         let mut app = App {
+            event_handler: self.event_handler.take(),
             handler: self.handler.take(),
             state: self.state.clone(),
         };
-        for b in text.as_bytes() {
+        for b in bytes {
             self.cli.process_byte::<T, _>(*b, &mut app).unwrap();
         }
 
+        self.event_handler = app.event_handler.take();
         self.handler = app.handler.take();
         self.update_terminal();
     }
@@ -234,20 +317,36 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
         self.process_str("\x08")
     }
 
+    pub fn send_ctrl_c(&mut self) {
+        self.process_str("\x03")
+    }
+
+    pub fn send_delete(&mut self) {
+        self.process_str("\x1B[3~")
+    }
+
     pub fn send_down(&mut self) {
-        self.process_str("\x1B[B")
+        self.process_bytes(codes::CURSOR_DOWN)
+    }
+
+    pub fn send_end(&mut self) {
+        self.process_str("\x1B[F")
     }
 
     pub fn send_enter(&mut self) {
         self.process_str("\n")
     }
 
+    pub fn send_home(&mut self) {
+        self.process_str("\x1B[H")
+    }
+
     pub fn send_left(&mut self) {
-        self.process_str("\x1B[D")
+        self.process_bytes(codes::CURSOR_BACKWARD)
     }
 
     pub fn send_right(&mut self) {
-        self.process_str("\x1B[C")
+        self.process_bytes(codes::CURSOR_FORWARD)
     }
 
     pub fn send_tab(&mut self) {
@@ -255,7 +354,15 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
     }
 
     pub fn send_up(&mut self) {
-        self.process_str("\x1B[A")
+        self.process_bytes(codes::CURSOR_UP)
+    }
+
+    pub fn set_event_handler(
+        &mut self,
+        handler: impl FnMut(&mut CliHandle<'_, Writer<T>, Infallible>, CliEvent<'_>) -> Result<(), Infallible>
+            + 'static,
+    ) {
+        self.event_handler = Some(Box::new(handler));
     }
 
     pub fn set_handler(
@@ -271,6 +378,23 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
         self.update_terminal();
     }
 
+    pub fn set_prompt_suffix(&mut self, suffix: &'static str) {
+        self.cli.set_prompt_suffix(suffix).unwrap();
+        self.update_terminal();
+    }
+
+    pub fn is_output_paused(&self) -> bool {
+        self.cli.is_output_paused()
+    }
+
+    pub fn received_events(&self) -> Vec<CliEvent<'static>> {
+        self.state.borrow().events.to_vec()
+    }
+
+    pub fn received_inputs(&self) -> Vec<RecordedInput> {
+        self.state.borrow().inputs.to_vec()
+    }
+
     pub fn received_commands(&self) -> Vec<Result<T, ParseError>> {
         self.state.borrow().commands.to_vec()
     }
@@ -279,11 +403,41 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
         &self.terminal
     }
 
+    pub fn resume_prompt(&mut self) {
+        self.cli.resume_prompt().unwrap();
+        self.update_terminal();
+    }
+
+    pub fn current_input(&self) -> &str {
+        self.cli.current_input()
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        self.cli.prompt()
+    }
+
+    pub fn prompt_width(&self) -> usize {
+        self.cli.prompt_width()
+    }
+
+    pub fn is_processing(&self) -> bool {
+        self.cli.is_processing()
+    }
+
+    pub fn suspend_prompt(&mut self) {
+        self.cli.suspend_prompt();
+    }
+
     pub fn write_str(&mut self, text: &str) {
         self.cli.write(|writer| writer.write_str(text)).unwrap();
         self.update_terminal();
     }
 
+    pub fn write_queued<const N: usize>(&mut self, reader: &mut InterruptReader<'_, N>) {
+        self.cli.write_queued(reader).unwrap();
+        self.update_terminal();
+    }
+
     fn update_terminal(&mut self) {
         for byte in self.state.borrow_mut().written.drain(..) {
             self.terminal.receive_byte(byte)
@@ -293,9 +447,14 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapper<T> {
 
 #[derive(Debug)]
 pub struct CliWrapperBuilder<T: Autocomplete + Help + CommandConvert + Clone> {
+    colors: bool,
     command_size: usize,
+    flow_control: bool,
     history_size: usize,
-    prompt: Option<&'static str>,
+    prompt: Option<Prompt>,
+    tab_insert: TabInsert,
+    trace_commands: bool,
+    utf8_replacement: bool,
     _ph: PhantomData<T>,
 }
 
@@ -309,6 +468,11 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapperBuilder<T> {
 
         //TODO: impl Buffer for Vec so no need to leak
         let builder = CliBuilder::default()
+            .colors(self.colors)
+            .flow_control(self.flow_control)
+            .tab_insert(self.tab_insert)
+            .trace_commands(self.trace_commands)
+            .utf8_replacement(self.utf8_replacement)
             .writer(writer)
             .command_buffer(vec![0; self.command_size].leak())
             .history_buffer(vec![0; self.history_size].leak());
@@ -322,6 +486,7 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapperBuilder<T> {
         let terminal = Terminal::new();
         let mut wrapper = CliWrapper {
             cli,
+            event_handler: None,
             handler: None,
             state,
             terminal,
@@ -330,8 +495,33 @@ impl<T: Autocomplete + Help + CommandConvert + Clone> CliWrapperBuilder<T> {
         wrapper
     }
 
-    pub fn prompt(mut self, prompt: &'static str) -> Self {
-        self.prompt = Some(prompt);
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    pub fn flow_control(mut self, flow_control: bool) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    pub fn prompt(mut self, prompt: impl Into<Prompt>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn tab_insert(mut self, tab_insert: TabInsert) -> Self {
+        self.tab_insert = tab_insert;
+        self
+    }
+
+    pub fn trace_commands(mut self, trace_commands: bool) -> Self {
+        self.trace_commands = trace_commands;
+        self
+    }
+
+    pub fn utf8_replacement(mut self, utf8_replacement: bool) -> Self {
+        self.utf8_replacement = utf8_replacement;
         self
     }
 }
@@ -1,4 +1,10 @@
-use crate::wrapper::CliWrapper;
+use embedded_cli::cli::{CliBuilder, CliError};
+use embedded_cli::command::RawCommand as CliRawCommand;
+use embedded_cli::Command;
+use rstest::rstest;
+
+use crate::output::Output;
+use crate::wrapper::{CliWrapper, RawCommand};
 
 use crate::terminal::assert_terminal;
 
@@ -33,6 +39,67 @@ fn write_external() {
     );
 }
 
+#[test]
+fn processing_finished_after_each_byte() {
+    let mut cli = CliWrapper::default();
+
+    assert!(!cli.is_processing());
+
+    cli.process_str("set 1\n");
+
+    assert!(!cli.is_processing());
+    cli.write_str("abc");
+    assert_terminal!(cli.terminal(), 2, vec!["$ set 1", "abc", "$"]);
+}
+
+#[derive(Debug, Clone, Command, PartialEq)]
+enum CliCommand {
+    Set,
+}
+
+#[test]
+fn access_while_processing() {
+    let output = Output::default();
+    let mut cli = CliBuilder::default()
+        .writer(output.clone())
+        .build()
+        .unwrap();
+    // simulates access to cli from outside of processor,
+    // that can't be done with references
+    let ptr = std::ptr::addr_of_mut!(cli);
+
+    let mut results = vec![];
+    let mut processor = CliCommand::processor(|_, _| {
+        // SAFETY: busy cli returns before its state is touched
+        let cli = unsafe { &mut *ptr };
+        results.push(cli.write(|writer| writer.write_str("abc")));
+        results.push(cli.reset(false));
+        results.push(cli.process_byte::<CliRawCommand<'_>, _>(
+            b'a',
+            &mut CliRawCommand::processor(|_, _| Ok(())),
+        ));
+        results.push(cli.process_slice::<CliRawCommand<'_>, _>(
+            b"a",
+            &mut CliRawCommand::processor(|_, _| Ok(())),
+        ));
+        Ok(())
+    });
+    cli.process_slice::<CliCommand, _>(
+        b"set
+",
+        &mut processor,
+    )
+    .unwrap();
+    drop(processor);
+
+    assert_eq!(results, vec![Err(CliError::Busy); 4]);
+    assert_terminal!(output.terminal(), 2, vec!["$ set", "$"]);
+
+    // after processing cli is available again
+    cli.write(|writer| writer.write_str("abc")).unwrap();
+    assert_terminal!(output.terminal(), 2, vec!["$ set", "abc", "$"]);
+}
+
 #[test]
 fn write_from_service() {
     let mut cli = CliWrapper::default();
@@ -56,3 +123,180 @@ fn write_from_service() {
         vec!["$ set 123", r#"from command "set""#, "another line", "$"]
     );
 }
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn write_severity(#[case] colors: bool) {
+    let mut cli: CliWrapper<RawCommand> = CliWrapper::builder().colors(colors).build();
+
+    cli.set_handler(|cli, _| {
+        cli.writer().write_str("some text")?;
+        cli.writer().info("first")?;
+        cli.writer().warn("second")?;
+        cli.writer().error("third")?;
+        Ok(())
+    });
+
+    cli.process_str("set");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ set",
+            "some text",
+            "info: first",
+            "warn: second",
+            "error: third",
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn write_key_values() {
+    let mut cli = CliWrapper::default();
+
+    cli.set_handler(|cli, cmd| {
+        if cmd.name == "machine" {
+            cli.set_machine_mode(true);
+        } else if cmd.name == "human" {
+            cli.set_machine_mode(false);
+        }
+        cli.writer().kv("temp", &23)?;
+        cli.writer().kv_str("name", r#"led "1""#)?;
+        Ok(())
+    });
+
+    cli.process_str("human");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec!["$ human", "temp=23", r#"name=led "1""#, "$"]
+    );
+
+    cli.process_str("machine");
+    cli.send_enter();
+    cli.process_str("other");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ human",
+            "temp=23",
+            r#"name=led "1""#,
+            "$ machine",
+            r#"{"temp":23}"#,
+            r#"{"name":"led \"1\""}"#,
+            "$ other",
+            r#"{"temp":23}"#,
+            r#"{"name":"led \"1\""}"#,
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn write_status_block() {
+    let mut cli = CliWrapper::default();
+
+    cli.set_handler(|cli, cmd| {
+        if cmd.name == "machine" {
+            cli.set_machine_mode(true);
+        }
+        let writer = cli.writer();
+        writer.set_kv_width(8);
+        writer.write_kv("uptime", &120)?;
+        writer.write_kv("errors", &0)?;
+        writer.write_kv("temperature", &23)?;
+        Ok(())
+    });
+
+    cli.process_str("status");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ status",
+            "uptime..: 120",
+            "errors..: 0",
+            "temperature: 23",
+            "$"
+        ]
+    );
+
+    cli.process_str("machine");
+    cli.send_enter();
+
+    assert_terminal!(
+        cli.terminal(),
+        2,
+        vec![
+            "$ status",
+            "uptime..: 120",
+            "errors..: 0",
+            "temperature: 23",
+            "$ machine",
+            r#"{"uptime":120}"#,
+            r#"{"errors":0}"#,
+            r#"{"temperature":23}"#,
+            "$"
+        ]
+    );
+}
+
+#[test]
+fn write_with_suspended_prompt() {
+    let mut cli = CliWrapper::default();
+
+    cli.process_str("set");
+    cli.suspend_prompt();
+
+    cli.write_str("abc");
+    cli.write_str("def");
+
+    assert_terminal!(cli.terminal(), 0, vec!["abc", "def", ""]);
+
+    cli.set_prompt("> ");
+    cli.write_str("gh");
+
+    assert_terminal!(cli.terminal(), 0, vec!["abc", "def", "gh", ""]);
+
+    cli.resume_prompt();
+
+    assert_terminal!(cli.terminal(), 5, vec!["abc", "def", "gh", "> set"]);
+
+    cli.write_str("ij");
+
+    assert_terminal!(cli.terminal(), 5, vec!["abc", "def", "gh", "ij", "> set"]);
+}
+
+#[test]
+fn input_with_suspended_prompt() {
+    let mut cli = CliWrapper::default();
+
+    cli.suspend_prompt();
+    cli.write_str("abc");
+
+    assert_terminal!(cli.terminal(), 0, vec!["abc", ""]);
+
+    cli.process_str("s");
+
+    assert_terminal!(cli.terminal(), 3, vec!["abc", "$ s"]);
+
+    cli.write_str("def");
+
+    assert_terminal!(cli.terminal(), 0, vec!["abc", "def", ""]);
+
+    cli.resume_prompt();
+
+    assert_terminal!(cli.terminal(), 3, vec!["abc", "def", "$ s"]);
+}
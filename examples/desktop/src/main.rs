@@ -37,6 +37,9 @@ enum BaseCommand<'a> {
     /// Show some status
     Status,
 
+    /// Lock CLI, so password is required again
+    Logout,
+
     /// Stop CLI and exit
     Exit,
 }
@@ -187,6 +190,17 @@ fn main() {
         .writer(writer)
         .command_buffer(command_buffer)
         .history_buffer(history_buffer)
+        // written before the first prompt
+        .banner(
+            "Cli is running. Press 'Esc' to exit
+Enter password \"admin\" to login and type \"logout\" to lock cli again.
+Type \"help\" for a list of commands.
+Use backspace and tab to remove chars and autocomplete.
+Use up and down for history navigation and Ctrl+R to search it.
+Use left and right to move inside input.",
+        )
+        // commands are accepted only after password is entered
+        .auth("password: ", |password| password == "admin")
         .build()
         .expect("Failed to build CLI");
 
@@ -200,19 +214,6 @@ fn main() {
         should_exit: false,
     };
 
-    cli.write(|writer| {
-        uwrite!(
-            writer,
-            "Cli is running. Press 'Esc' to exit
-Type \"help\" for a list of commands.
-Use backspace and tab to remove chars and autocomplete.
-Use up and down for history navigation.
-Use left and right to move inside input."
-        )?;
-        Ok(())
-    })
-    .unwrap();
-
     let stdin = stdin();
     for c in stdin.events() {
         let evt = c.unwrap();
@@ -224,6 +225,7 @@ Use left and right to move inside input."
             Event::Key(Key::Left) => vec![codes::ESCAPE, b'[', b'D'],
             Event::Key(Key::BackTab) => vec![codes::TABULATION],
             Event::Key(Key::Backspace) => vec![codes::BACKSPACE],
+            Event::Key(Key::Ctrl('r')) => vec![codes::DEVICE_CONTROL_2],
             Event::Key(Key::Char(c)) => {
                 let mut buf = [0; 4];
                 c.encode_utf8(&mut buf).as_bytes().to_vec()
@@ -234,7 +236,6 @@ Use left and right to move inside input."
         // Command type is specified for autocompletion and help
         // Processor accepts closure where we can process parsed command
         // we can use different command and processor with each call
-        // TODO: add example of login that uses different states
         for byte in bytes {
             cli.process_byte::<BaseCommand<'_>, _>(
                 byte,
@@ -242,6 +243,10 @@ Use left and right to move inside input."
                     BaseCommand::Led { id, command } => on_led(cli, &mut state, id, command),
                     BaseCommand::Adc { id, command } => on_adc(cli, &mut state, id, command),
                     BaseCommand::Status => on_status(cli, &mut state),
+                    BaseCommand::Logout => {
+                        cli.lock();
+                        Ok(())
+                    }
                     BaseCommand::Exit => {
                         state.should_exit = true;
                         cli.writer().write_str("Cli will shutdown now")